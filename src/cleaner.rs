@@ -0,0 +1,536 @@
+// 原生删除引擎 - 用 std::fs 遍历目录并直接删除其中的条目，统计释放的字节数和删除的文件数，
+// 逐个记录删除失败的文件，代替通过 cmd 执行 rmdir 再解析其错误输出；
+// 另外提供备用数据流（如 Zone.Identifier）的枚举和删除
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+// 删除范围：整个目录，或保留目录只清空其中的内容
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeleteScope {
+    Remove,
+    EmptyContents,
+}
+
+// 文件筛选：只删除最后修改时间早于 min_age 且不小于 min_size 的文件。
+// 设置任一条件时保留全部目录和符号链接，只删除符合条件的文件
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FileFilter {
+    pub min_age: Option<Duration>,
+    pub min_size: Option<u64>,
+}
+
+impl FileFilter {
+    pub fn is_active(&self) -> bool {
+        self.min_age.is_some() || self.min_size.is_some()
+    }
+
+    pub fn matches(&self, metadata: &fs::Metadata) -> bool {
+        let old_enough = self.min_age.is_none_or(|min_age| {
+            metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age >= min_age)
+        });
+        old_enough && self.min_size.is_none_or(|min_size| metadata.len() >= min_size)
+    }
+}
+
+// 删除失败的单个条目
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeleteFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeleteReport {
+    pub bytes_freed: u64,
+    pub files_removed: usize,
+    pub dirs_removed: usize,
+    pub failures: Vec<DeleteFailure>,
+    // 删除途中被取消，已删除的条目仍计入统计
+    pub cancelled: bool,
+}
+
+impl DeleteReport {
+    fn fail(&mut self, path: &Path, error: &io::Error) {
+        self.failures.push(DeleteFailure {
+            path: path.to_path_buf(),
+            error: describe_error(error),
+        });
+    }
+}
+
+// 识别可以由原生引擎执行的删除命令，返回目标路径和删除范围：
+// rmdir /s /q <目录> 与新建规则生成的 Remove-Item -Recurse -Force '<目录>\*'，
+// 含 &、|、> 等组合或重定向的命令仍交给 cmd 执行
+pub fn parse_delete_command(command: &str) -> Option<(String, DeleteScope)> {
+    let command = command.trim();
+    if command.contains(['&', '|', '>', '<']) {
+        return None;
+    }
+
+    let lower = command.to_lowercase();
+    for prefix in ["rmdir /s /q ", "rd /s /q "] {
+        if lower.starts_with(prefix) {
+            let target = unquote(&command[prefix.len()..]);
+            return (!target.is_empty() && !target.contains('"'))
+                .then(|| (target.to_string(), DeleteScope::Remove));
+        }
+    }
+
+    const REMOVE_ITEM: &str = "powershell -noprofile remove-item -recurse -force '";
+    if lower.starts_with(REMOVE_ITEM) && command.ends_with("\\*'") {
+        let target = &command[REMOVE_ITEM.len()..command.len() - "\\*'".len()];
+        return (!target.is_empty() && !target.contains('\''))
+            .then(|| (target.to_string(), DeleteScope::EmptyContents));
+    }
+    None
+}
+
+fn unquote(text: &str) -> &str {
+    let text = text.trim();
+    text.strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .unwrap_or(text)
+}
+
+// 删除目标目录（或其内容）。不跟随符号链接和目录联接，只删除链接本身；
+// 已不存在的条目不计为失败。cancel 置位后在下一个条目前停止；
+// filter 生效时只删除符合条件的文件，目录（包括 Remove 时的目标目录本身）一律保留
+pub fn native_delete(root: &Path, scope: DeleteScope, filter: &FileFilter, cancel: &AtomicBool) -> DeleteReport {
+    let root = extended_length_path(root);
+    let mut report = DeleteReport::default();
+
+    // 目标本身是符号链接或目录联接时与 rmdir /s /q 相同，只删除链接，不清空其指向的目录
+    if fs::symlink_metadata(&root).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+        match scope {
+            _ if filter.is_active() => {}
+            DeleteScope::Remove => match fs::remove_file(&root).or_else(|_| fs::remove_dir(&root)) {
+                Ok(()) => report.files_removed += 1,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => report.fail(&root, &e),
+            },
+            DeleteScope::EmptyContents => report.failures.push(DeleteFailure {
+                path: root,
+                error: "目标是符号链接或目录联接，不清空其指向的目录".to_string(),
+            }),
+        }
+        return report;
+    }
+
+    remove_children(&root, &mut report, filter, cancel);
+
+    if scope == DeleteScope::Remove && !report.cancelled && !filter.is_active() {
+        match fs::remove_dir(&root) {
+            Ok(()) => report.dirs_removed += 1,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            // 子项删除失败时目录必然非空，不再重复记录
+            Err(_) if !report.failures.is_empty() => {}
+            Err(e) => report.fail(&root, &e),
+        }
+    }
+    report
+}
+
+// 加上 \\?\ 前缀，使子项不受 MAX_PATH 限制。与 canonicalize 不同，不解析路径中的符号链接和目录联接
+#[cfg(windows)]
+fn extended_length_path(path: &Path) -> PathBuf {
+    let Ok(absolute) = std::path::absolute(path) else {
+        return path.to_path_buf();
+    };
+    match absolute.to_str() {
+        Some(text) if text.starts_with(r"\\?\") => absolute,
+        Some(text) => match text.strip_prefix(r"\\") {
+            Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+            None => PathBuf::from(format!(r"\\?\{}", text)),
+        },
+        None => absolute,
+    }
+}
+
+#[cfg(not(windows))]
+fn extended_length_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+fn remove_children(dir: &Path, report: &mut DeleteReport, filter: &FileFilter, cancel: &AtomicBool) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+        Err(e) => {
+            report.fail(dir, &e);
+            return;
+        }
+    };
+
+    for entry in entries {
+        if cancel.load(Ordering::Relaxed) {
+            report.cancelled = true;
+            return;
+        }
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                report.fail(dir, &e);
+                continue;
+            }
+        };
+        let metadata = match fs::symlink_metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                report.fail(&path, &e);
+                continue;
+            }
+        };
+
+        if metadata.is_dir() {
+            remove_children(&path, report, filter, cancel);
+            if report.cancelled {
+                return;
+            }
+            if filter.is_active() {
+                continue;
+            }
+            match fs::remove_dir(&path) {
+                Ok(()) => report.dirs_removed += 1,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(_)
+                    if report
+                        .failures
+                        .iter()
+                        .any(|failure| failure.path.starts_with(&path)) => {}
+                Err(e) => report.fail(&path, &e),
+            }
+        } else if filter.is_active() && (metadata.file_type().is_symlink() || !filter.matches(&metadata)) {
+            continue;
+        } else if metadata.file_type().is_symlink() {
+            // 指向目录的符号链接和目录联接需要用 remove_dir 删除
+            match fs::remove_file(&path).or_else(|_| fs::remove_dir(&path)) {
+                Ok(()) => report.files_removed += 1,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => report.fail(&path, &e),
+            }
+        } else {
+            match remove_file(&path, &metadata) {
+                Ok(()) => {
+                    report.files_removed += 1;
+                    report.bytes_freed += metadata.len();
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => report.fail(&path, &e),
+            }
+        }
+    }
+}
+
+// 只读文件需要先去掉只读属性才能删除
+#[allow(clippy::permissions_set_readonly_false)]
+fn remove_file(path: &Path, metadata: &fs::Metadata) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e)
+            if e.kind() == io::ErrorKind::PermissionDenied && metadata.permissions().readonly() =>
+        {
+            let mut permissions = metadata.permissions();
+            permissions.set_readonly(false);
+            fs::set_permissions(path, permissions)?;
+            fs::remove_file(path)
+        }
+        result => result,
+    }
+}
+
+// 常见错误给出中文说明，便于归类（权限不足、文件占用）
+fn describe_error(error: &io::Error) -> String {
+    match (error.kind(), error.raw_os_error()) {
+        (_, Some(32)) | (_, Some(33)) => format!("文件正在被使用 ({})", error),
+        (io::ErrorKind::PermissionDenied, _) => format!("权限不足 ({})", error),
+        _ => error.to_string(),
+    }
+}
+
+// 文件的一个备用数据流（NTFS ADS），name 不含前导冒号和 :$DATA 后缀，如 "Zone.Identifier"
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlternateStream {
+    pub name: String,
+    pub size: u64,
+}
+
+// 通过备份 API（BackupRead）枚举文件的备用数据流，只读取各流的头部并跳过数据
+#[cfg(windows)]
+pub fn alternate_streams(path: &Path) -> io::Result<Vec<AlternateStream>> {
+    use std::ffi::c_void;
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{CloseHandle, GENERIC_READ, HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        BackupRead, BackupSeek, CreateFileW, BACKUP_ALTERNATE_DATA, FILE_FLAG_BACKUP_SEMANTICS,
+        FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    };
+    // WIN32_STREAM_ID 中流名之前的固定部分：流类型、属性、数据大小和流名长度
+    const HEADER_LEN: usize = 20;
+
+    // 读满整个缓冲区，读到末尾或出错时返回 false
+    fn read_exact(handle: HANDLE, buffer: &mut [u8], context: &mut *mut c_void) -> bool {
+        let mut read = 0u32;
+        let ok = unsafe {
+            BackupRead(
+                handle,
+                buffer.as_mut_ptr(),
+                buffer.len() as u32,
+                &mut read,
+                0,
+                0,
+                context,
+            )
+        };
+        ok != 0 && read as usize == buffer.len()
+    }
+
+    let wide = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect::<Vec<u16>>();
+    let handle = unsafe {
+        CreateFileW(
+            wide.as_ptr(),
+            GENERIC_READ,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+            std::ptr::null(),
+            OPEN_EXISTING,
+            FILE_FLAG_BACKUP_SEMANTICS,
+            std::ptr::null_mut(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut streams = Vec::new();
+    let mut context = std::ptr::null_mut();
+    let mut header = [0u8; HEADER_LEN];
+    while read_exact(handle, &mut header, &mut context) {
+        let id = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let mut size_bytes = [0u8; 8];
+        size_bytes.copy_from_slice(&header[8..16]);
+        let size = u64::from_le_bytes(size_bytes);
+        let name_len = u32::from_le_bytes([header[16], header[17], header[18], header[19]]) as usize;
+
+        let mut name = vec![0u8; name_len];
+        if name_len > 0 && !read_exact(handle, &mut name, &mut context) {
+            break;
+        }
+        if id == BACKUP_ALTERNATE_DATA {
+            let units = name
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect::<Vec<u16>>();
+            // 流名形如 ":Zone.Identifier:$DATA"
+            let name = String::from_utf16_lossy(&units);
+            streams.push(AlternateStream {
+                name: name
+                    .trim_start_matches(':')
+                    .trim_end_matches(":$DATA")
+                    .to_string(),
+                size,
+            });
+        }
+
+        if size > 0 {
+            let (mut low, mut high) = (0u32, 0u32);
+            let ok = unsafe {
+                BackupSeek(
+                    handle,
+                    size as u32,
+                    (size >> 32) as u32,
+                    &mut low,
+                    &mut high,
+                    &mut context,
+                )
+            };
+            if ok == 0 {
+                break;
+            }
+        }
+    }
+
+    // 以中止方式再调用一次，释放 BackupRead 分配的上下文
+    unsafe {
+        let mut read = 0u32;
+        BackupRead(
+            handle,
+            std::ptr::null_mut(),
+            0,
+            &mut read,
+            1,
+            0,
+            &mut context,
+        );
+        CloseHandle(handle);
+    }
+    Ok(streams)
+}
+
+#[cfg(not(windows))]
+pub fn alternate_streams(_path: &Path) -> io::Result<Vec<AlternateStream>> {
+    Ok(Vec::new())
+}
+
+// 数据流的完整路径 "<文件>:<流名>"，可以像普通文件一样直接删除，文件内容不受影响
+pub fn stream_path(file: &Path, stream: &str) -> PathBuf {
+    let mut path = file.as_os_str().to_os_string();
+    path.push(":");
+    path.push(stream);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_tree(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("wincleaner-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("a.log"), b"1234").unwrap();
+        fs::write(root.join("sub").join("b.log"), b"12").unwrap();
+        root
+    }
+
+    #[test]
+    fn parses_native_delete_commands() {
+        assert_eq!(
+            parse_delete_command(r#"rmdir /s /q "C:\Temp\cache""#),
+            Some((r"C:\Temp\cache".to_string(), DeleteScope::Remove))
+        );
+        assert_eq!(
+            parse_delete_command(r"RD /S /Q C:\Temp"),
+            Some((r"C:\Temp".to_string(), DeleteScope::Remove))
+        );
+        assert_eq!(
+            parse_delete_command(r"powershell -NoProfile Remove-Item -Recurse -Force 'C:\Temp\*'"),
+            Some((r"C:\Temp".to_string(), DeleteScope::EmptyContents))
+        );
+        assert_eq!(parse_delete_command(r"rmdir /s /q C:\Temp && echo done"), None);
+        assert_eq!(parse_delete_command(r"del /s /q C:\Temp\*"), None);
+        assert_eq!(parse_delete_command("rmdir /s /q "), None);
+    }
+
+    #[test]
+    fn remove_scope_deletes_directory() {
+        let root = temp_tree("remove");
+        let report = native_delete(&root, DeleteScope::Remove, &FileFilter::default(), &AtomicBool::new(false));
+        assert!(!root.exists());
+        assert_eq!(report.files_removed, 2);
+        assert_eq!(report.dirs_removed, 2);
+        assert_eq!(report.bytes_freed, 6);
+        assert!(report.failures.is_empty());
+    }
+
+    #[test]
+    fn empty_contents_keeps_directory() {
+        let root = temp_tree("empty");
+        let report = native_delete(&root, DeleteScope::EmptyContents, &FileFilter::default(), &AtomicBool::new(false));
+        assert!(root.is_dir());
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+        assert_eq!(report.files_removed, 2);
+        assert_eq!(report.dirs_removed, 1);
+        fs::remove_dir(&root).unwrap();
+    }
+
+    #[test]
+    fn cancelled_delete_stops_early() {
+        let root = temp_tree("cancel");
+        let report = native_delete(&root, DeleteScope::Remove, &FileFilter::default(), &AtomicBool::new(true));
+        assert!(report.cancelled);
+        assert!(root.join("a.log").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn filter_size_and_age_boundaries() {
+        let root = temp_tree("filter-bounds");
+        let file = root.join("a.log");
+        let two_days = Duration::from_secs(2 * 86400);
+        fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() - two_days)
+            .unwrap();
+        let metadata = fs::metadata(&file).unwrap();
+
+        assert!(!FileFilter::default().is_active());
+        assert!(FileFilter::default().matches(&metadata));
+        let size = |min_size| FileFilter { min_age: None, min_size: Some(min_size) };
+        assert!(size(4).matches(&metadata));
+        assert!(!size(5).matches(&metadata));
+        let age = |days: u64| FileFilter { min_age: Some(Duration::from_secs(days * 86400)), min_size: None };
+        assert!(age(1).matches(&metadata));
+        assert!(!age(3).matches(&metadata));
+        // 两个条件同时满足才删除
+        let both = FileFilter { min_age: Some(Duration::from_secs(86400)), min_size: Some(5) };
+        assert!(!both.matches(&metadata));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn filtered_delete_keeps_directories() {
+        let root = temp_tree("filter-dirs");
+        fs::create_dir(root.join("empty")).unwrap();
+        let filter = FileFilter { min_age: None, min_size: Some(4) };
+
+        let report = native_delete(&root, DeleteScope::Remove, &filter, &AtomicBool::new(false));
+        assert!(!root.join("a.log").exists());
+        assert!(root.join("sub").join("b.log").exists());
+        assert!(root.join("empty").is_dir());
+        assert_eq!(report.files_removed, 1);
+        assert_eq!(report.dirs_removed, 0);
+        assert_eq!(report.bytes_freed, 4);
+        assert!(report.failures.is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn filtered_delete_keeps_links() {
+        let root = temp_tree("filter-links");
+        std::os::unix::fs::symlink(root.join("a.log"), root.join("link.log")).unwrap();
+        let filter = FileFilter { min_age: None, min_size: Some(0) };
+
+        let report = native_delete(&root, DeleteScope::EmptyContents, &filter, &AtomicBool::new(false));
+        assert!(fs::symlink_metadata(root.join("link.log")).is_ok());
+        assert_eq!(report.files_removed, 2);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn link_root_is_removed_without_following() {
+        let target = temp_tree("link-target");
+        let link = target.with_file_name(format!("wincleaner-link-{}", std::process::id()));
+        let _ = fs::remove_file(&link);
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let report = native_delete(&link, DeleteScope::Remove, &FileFilter::default(), &AtomicBool::new(false));
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert!(target.join("sub").join("b.log").exists());
+        assert_eq!(report.bytes_freed, 0);
+
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let report = native_delete(&link, DeleteScope::EmptyContents, &FileFilter::default(), &AtomicBool::new(false));
+        assert_eq!(report.failures.len(), 1);
+        assert!(target.join("a.log").exists());
+
+        fs::remove_file(&link).unwrap();
+        fs::remove_dir_all(&target).unwrap();
+    }
+}
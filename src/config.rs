@@ -0,0 +1,788 @@
+// 配置 - 读取配置文件中的自定义规则、内置任务覆盖和 [settings] 全局设置，
+// 以及审计、信息亭等运行模式
+use crate::engine::{current_av_hint, TaskSchedule, IS_ELEVATED};
+use crate::logging::{log, record_feature};
+use crate::sizing::{format_size, free_space_snapshot, growth_alerts, run_query_command, SizeUnits};
+use crate::tasks::{
+    builtin_tasks, generalize_task, CleanCategory, CleanTask, DeleteMode, EstimatedSize, RiskLevel,
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+
+pub(crate) const CONFIG_FILE: &str = "wincleaner-config.toml";
+
+// 用户配置文件结构，匹配 TOML 格式
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct UserConfig {
+    // 原始规则定义，加载后按语言区域解析为 task
+    #[serde(default, rename = "task")]
+    pub(crate) task_definitions: Vec<TaskDefinition>,
+    #[serde(skip)]
+    pub(crate) task: Vec<CleanTask>,
+    // 内置任务字段覆盖
+    #[serde(default, rename = "override")]
+    pub(crate) overrides: Vec<TaskOverride>,
+    #[serde(default)]
+    pub(crate) settings: AppSettings,
+}
+
+// 可本地化文本：普通字符串，或按语言区域键入的表（如 name.en、name.zh-CN）
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum LocalizedText {
+    Plain(String),
+    Localized(BTreeMap<String, String>),
+}
+
+impl LocalizedText {
+    // 依次匹配完整区域（zh-CN）、语言（zh）、英文，最后取第一个
+    pub(crate) fn resolve(&self, locale: &str) -> String {
+        let map = match self {
+            LocalizedText::Plain(text) => return text.clone(),
+            LocalizedText::Localized(map) => map,
+        };
+
+        let language = locale.split('-').next().unwrap_or(locale);
+        map.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(locale))
+            .or_else(|| {
+                map.iter().find(|(key, _)| {
+                    key.split('-').next().is_some_and(|prefix| prefix.eq_ignore_ascii_case(language))
+                })
+            })
+            .or_else(|| map.iter().find(|(key, _)| key.eq_ignore_ascii_case("en")))
+            .or_else(|| map.iter().next())
+            .map(|(_, text)| text.clone())
+            .unwrap_or_default()
+    }
+}
+
+// 自定义规则的原始定义：name / description 可提供多种语言，其余字段原样交给 CleanTask
+#[derive(Debug, Deserialize)]
+pub(crate) struct TaskDefinition {
+    pub(crate) name: LocalizedText,
+    pub(crate) description: LocalizedText,
+    #[serde(flatten)]
+    pub(crate) fields: toml::Table,
+}
+
+impl TaskDefinition {
+    pub(crate) fn resolve(&self, locale: &str) -> Result<CleanTask, String> {
+        let mut fields = self.fields.clone();
+        fields.insert("name".to_string(), toml::Value::String(self.name.resolve(locale)));
+        fields.insert(
+            "description".to_string(),
+            toml::Value::String(self.description.resolve(locale)),
+        );
+        toml::Value::Table(fields)
+            .try_into::<CleanTask>()
+            .map_err(|e| e.to_string())
+    }
+}
+
+// 界面语言区域，如 "zh-CN"、"en-US"；设置为 "auto" 时跟随系统
+pub(crate) static LOCALE: Lazy<String> = Lazy::new(|| resolve_locale(&SETTINGS.language));
+
+fn resolve_locale(language: &str) -> String {
+    if !language.is_empty() && !language.eq_ignore_ascii_case("auto") {
+        return language.to_string();
+    }
+    system_locale().unwrap_or_else(|| "zh-CN".to_string())
+}
+
+#[cfg(windows)]
+fn system_locale() -> Option<String> {
+    use windows_sys::Win32::Globalization::{GetUserDefaultLocaleName, LOCALE_NAME_MAX_LENGTH};
+
+    let mut buffer = [0u16; LOCALE_NAME_MAX_LENGTH as usize];
+    let len = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+    // 返回值包含结尾的空字符
+    (len > 1).then(|| String::from_utf16_lossy(&buffer[..len as usize - 1]))
+}
+
+#[cfg(not(windows))]
+fn system_locale() -> Option<String> {
+    let lang = std::env::var("LANG").ok()?;
+    let locale = lang.split('.').next()?.replace('_', "-");
+    (!locale.is_empty() && locale != "C" && locale != "POSIX").then_some(locale)
+}
+
+// 全局设置，对应配置文件中的 [settings]
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct AppSettings {
+    // 单个任务预计永久删除超过该大小时需要额外确认，如 "20GB"
+    pub(crate) large_delete_threshold: String,
+    // 大小的单位制："binary"（1 KB = 1024 B）或 "decimal"（1 kB = 1000 B）
+    pub(crate) size_units: SizeUnits,
+    // 删除目标的最小目录层级（C:\ 为0，C:\Users 为1），更浅的路径一律拒绝
+    pub(crate) min_target_depth: usize,
+    // rmdir /s /q 和新建规则生成的清空目录命令由内置删除引擎直接执行，关闭后交给 cmd
+    pub(crate) native_delete: bool,
+    // 删除前先将文件移入撤销区（%LOCALAPPDATA%\WinCleaner\quarantine），可以撤销上次清理
+    pub(crate) quarantine_enabled: bool,
+    // 撤销区总大小上限，超过时删除最旧的批次，如 "10GB"；单次清理超过该大小的部分直接删除
+    pub(crate) quarantine_max_size: String,
+    // 撤销区中的文件保留天数，过期后自动删除
+    pub(crate) quarantine_days: u64,
+    // 日志清理只包含早于该天数的日志和转储文件
+    pub(crate) log_min_age_days: u64,
+    // 查找备用数据流（Zone.Identifier 等）的文件夹，支持环境变量，会递归扫描子目录
+    pub(crate) stream_scan_dirs: Vec<String>,
+    // 大文件查找只列出不小于该大小的文件，如 "1GB"
+    pub(crate) large_file_threshold: String,
+    // 大文件查找"移到归档文件夹"的默认目标，支持环境变量，如 "D:\\Archive"
+    pub(crate) archive_dir: String,
+    // 单次任务日志（logs 目录）的保留天数，过期后自动删除
+    pub(crate) task_log_days: u64,
+    // 同时执行的单项清理任务数（1-4），其余任务在清理队列中排队
+    pub(crate) max_concurrent_tasks: usize,
+    // 规则名称和描述使用的语言区域，如 "en"、"zh-CN"；"auto" 跟随系统
+    pub(crate) language: String,
+    // 启动时最小化窗口，适合开机自启后在后台运行
+    pub(crate) start_minimized: bool,
+    // 记住上次的最大化和置顶状态
+    pub(crate) remember_window_state: bool,
+    // 监测目录一周内增长超过该大小时提醒，如 "5GB"
+    pub(crate) growth_alert_threshold: String,
+    // 同一磁盘上同时进行的目录扫描数，0 表示按磁盘类型自动选择（SSD 4，机械硬盘 1）
+    pub(crate) scan_max_workers: usize,
+    // 扫描时每处理多少个文件/目录检查一次节流
+    pub(crate) scan_batch_size: usize,
+    // 每批之后暂停的毫秒数，未设置时按磁盘类型自动选择（SSD 0，机械硬盘 10）
+    pub(crate) scan_throttle_ms: Option<u64>,
+    // 窗口背景材质："none"、"mica" 或 "acrylic"，仅在 Windows 11 上生效
+    pub(crate) window_backdrop: WindowBackdrop,
+    // 界面字体
+    pub(crate) font_family: String,
+    // 界面字体缺少字形（中文、表情符号等）时依次尝试的后备字体
+    pub(crate) font_fallback: Vec<String>,
+    // 限时清理的时间预算（分钟）
+    pub(crate) time_box_minutes: u64,
+    // 清理完成提示："system_sound"、"custom_sound"、"toast"（只显示通知）或 "silent"
+    pub(crate) completion_feedback: CompletionFeedback,
+    // 计划任务等无人值守运行时的完成提示
+    pub(crate) scheduled_completion_feedback: CompletionFeedback,
+    // custom_sound 使用的 .wav 文件，支持环境变量
+    pub(crate) completion_sound_file: String,
+    // 只读审计模式，等同于以 --audit 启动
+    pub(crate) audit_mode: bool,
+    // 信息亭模式，等同于以 --kiosk 启动
+    pub(crate) kiosk_mode: bool,
+    // 信息亭按钮执行的任务 ID，为空时执行全部低风险内置任务
+    pub(crate) kiosk_tasks: Vec<String>,
+    // 信息亭按钮的文字
+    pub(crate) kiosk_button_label: String,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            large_delete_threshold: "20GB".to_string(),
+            size_units: SizeUnits::Binary,
+            min_target_depth: 2,
+            native_delete: true,
+            quarantine_enabled: false,
+            quarantine_max_size: "10GB".to_string(),
+            quarantine_days: 7,
+            log_min_age_days: 14,
+            stream_scan_dirs: vec![
+                "%USERPROFILE%\\Downloads".to_string(),
+                "%USERPROFILE%\\source\\repos".to_string(),
+            ],
+            large_file_threshold: "1GB".to_string(),
+            archive_dir: String::new(),
+            task_log_days: 30,
+            max_concurrent_tasks: 1,
+            language: "auto".to_string(),
+            start_minimized: false,
+            remember_window_state: true,
+            growth_alert_threshold: "5GB".to_string(),
+            scan_max_workers: 0,
+            scan_batch_size: 256,
+            scan_throttle_ms: None,
+            window_backdrop: WindowBackdrop::None,
+            font_family: "Segoe UI".to_string(),
+            font_fallback: [
+                "Microsoft YaHei UI",
+                "Microsoft YaHei",
+                "SimSun",
+                "Segoe UI Emoji",
+                "Segoe UI Symbol",
+                "Noto Sans CJK SC",
+                "Noto Color Emoji",
+                "Arial",
+            ]
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+            time_box_minutes: 5,
+            completion_feedback: CompletionFeedback::SystemSound,
+            scheduled_completion_feedback: CompletionFeedback::Toast,
+            completion_sound_file: String::new(),
+            audit_mode: false,
+            kiosk_mode: false,
+            kiosk_tasks: Vec::new(),
+            kiosk_button_label: "一键清理".to_string(),
+        }
+    }
+}
+
+// 完成提示 - 清理结束时播放系统提示音、自定义声音文件、只显示通知或完全静默
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CompletionFeedback {
+    #[default]
+    SystemSound,
+    CustomSound, // 播放 completion_sound_file，文件不可用时退回系统提示音
+    Toast,       // 只显示 Windows 通知，不发声
+    Silent,
+}
+
+// 窗口背景材质 - Windows 11 上可使用系统的 Mica 或亚克力背景，旧系统保持纯色主题
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum WindowBackdrop {
+    #[default]
+    None,
+    Mica,
+    Acrylic,
+}
+
+// 设置在启动时读取一次，修改后需重启生效
+pub(crate) static SETTINGS: Lazy<AppSettings> = Lazy::new(load_settings);
+
+// 只读取 [settings]，不解析规则定义；窗口创建前就会用到，需尽量快
+fn load_settings() -> AppSettings {
+    #[derive(Default, Deserialize)]
+    #[serde(default)]
+    struct SettingsOnly {
+        settings: AppSettings,
+    }
+
+    std::fs::read_to_string(CONFIG_FILE)
+        .ok()
+        .and_then(|content| toml::from_str::<SettingsOnly>(&content).ok())
+        .map(|config| config.settings)
+        .unwrap_or_default()
+}
+
+// 内置任务覆盖：按ID或名称匹配，只替换填写了的字段
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(crate) struct TaskOverride {
+    pub(crate) target: String,
+    pub(crate) name: Option<LocalizedText>,
+    pub(crate) description: Option<LocalizedText>,
+    pub(crate) category: Option<CleanCategory>,
+    pub(crate) command: Option<String>,
+    pub(crate) path_check: Option<String>,
+    pub(crate) requires_confirmation: Option<bool>,
+    pub(crate) risk: Option<RiskLevel>,
+    pub(crate) dangerous: Option<bool>, // 旧版字段，等同于 risk = "High" / "Low"
+    pub(crate) requires_admin: Option<bool>,
+    pub(crate) estimated_size: Option<EstimatedSize>,
+    pub(crate) icon: Option<String>,
+    pub(crate) working_dir: Option<String>,
+    pub(crate) env: Option<BTreeMap<String, String>>,
+    pub(crate) minimal_env: Option<bool>,
+    pub(crate) skip_if_missing: Option<bool>,
+    pub(crate) requires_closed: Option<Vec<String>>,
+    pub(crate) process_check: Option<String>,
+    pub(crate) patterns: Option<Vec<String>>,
+    pub(crate) older_than_days: Option<u64>,
+    pub(crate) min_file_size: Option<String>,
+    pub(crate) delete_mode: Option<DeleteMode>,
+    pub(crate) success_codes: Option<String>,
+    pub(crate) exit_messages: Option<BTreeMap<String, String>>,
+    pub(crate) notes: Option<String>,
+    pub(crate) tags: Option<Vec<String>>,
+    pub(crate) app_exe: Option<String>,
+    pub(crate) schedule: Option<TaskSchedule>,
+    pub(crate) run_after: Option<Vec<String>>,
+}
+
+impl TaskOverride {
+    // 应用覆盖，返回被修改的字段名
+    pub(crate) fn apply(&self, task: &mut CleanTask) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+
+        macro_rules! override_field {
+            ($field:ident) => {
+                if let Some(value) = &self.$field {
+                    task.$field = value.clone();
+                    changed.push(stringify!($field));
+                }
+            };
+            ($field:ident, optional) => {
+                if let Some(value) = &self.$field {
+                    task.$field = Some(value.clone());
+                    changed.push(stringify!($field));
+                }
+            };
+        }
+
+        if let Some(name) = &self.name {
+            task.name = name.resolve(&LOCALE);
+            changed.push("name");
+        }
+        if let Some(description) = &self.description {
+            task.description = description.resolve(&LOCALE);
+            changed.push("description");
+        }
+        override_field!(category);
+        override_field!(command);
+        override_field!(path_check, optional);
+        override_field!(requires_confirmation);
+        override_field!(risk);
+        if let Some(dangerous) = self.dangerous {
+            task.risk = if dangerous { RiskLevel::High } else { RiskLevel::Low };
+            changed.push("dangerous");
+        }
+        override_field!(requires_admin);
+        override_field!(estimated_size);
+        override_field!(icon, optional);
+        override_field!(working_dir, optional);
+        override_field!(env);
+        override_field!(minimal_env);
+        override_field!(skip_if_missing);
+        override_field!(requires_closed);
+        override_field!(process_check, optional);
+        override_field!(patterns);
+        override_field!(older_than_days, optional);
+        override_field!(min_file_size, optional);
+        override_field!(delete_mode, optional);
+        override_field!(success_codes, optional);
+        override_field!(exit_messages);
+        override_field!(notes, optional);
+        override_field!(tags);
+        override_field!(app_exe, optional);
+        override_field!(schedule, optional);
+        override_field!(run_after);
+
+        changed
+    }
+}
+
+// 将配置中的覆盖合并到内置任务
+fn apply_task_overrides(tasks: &mut [CleanTask], overrides: &[TaskOverride]) {
+    for task_override in overrides {
+        let Some(task) = tasks
+            .iter_mut()
+            .find(|task| task.id == task_override.target || task.name == task_override.target)
+        else {
+            log(&format!("配置警告: 覆盖目标不存在: {}", task_override.target));
+            continue;
+        };
+
+        let changed = task_override.apply(task);
+        if !changed.is_empty() {
+            task.override_source = Some(format!("{} 覆盖: {}", CONFIG_FILE, changed.join(", ")));
+        }
+    }
+}
+
+// 加载用户配置（自定义清理规则与内置任务覆盖）
+pub(crate) fn load_user_config() -> UserConfig {
+    match std::fs::read_to_string(CONFIG_FILE) {
+        Ok(content) => {
+            // 解析为配置结构体
+            match toml::from_str::<UserConfig>(&content) {
+                Ok(mut config) => {
+                    // 此时全局设置可能尚未初始化，直接使用本文件中的语言设置
+                    let locale = resolve_locale(&config.settings.language);
+                    for definition in &config.task_definitions {
+                        match definition.resolve(&locale) {
+                            Ok(mut task) => {
+                                task.apply_legacy_dangerous();
+                                let mut portable = task.clone();
+                                if generalize_task(&mut portable) {
+                                    log(&format!(
+                                        "配置提示: 规则 {} 使用了本机绝对路径，建议改为 {}",
+                                        task.name,
+                                        portable.path_check.as_deref().unwrap_or(&portable.command)
+                                    ));
+                                }
+                                config.task.push(task);
+                            }
+                            Err(e) => log(&format!("配置警告: 自定义规则格式错误，已跳过: {}", e)),
+                        }
+                    }
+                    log(&format!(
+                        "加载了 {} 个自定义清理规则，{} 个内置任务覆盖",
+                        config.task.len(),
+                        config.overrides.len()
+                    ));
+                    config
+                }
+                Err(e) => {
+                    log(&format!("配置文件格式错误: {}", e));
+                    UserConfig::default()
+                }
+            }
+        },
+        Err(_) => {
+            // 配置文件不存在，创建示例配置
+            let example_tasks = vec![CleanTask {
+                id: String::new(),
+                name: "示例: 清理临时文件".to_string(),
+                description: "清理用户临时文件夹".to_string(),
+                category: CleanCategory::Custom,
+                command: "del /q %TEMP%\\*.tmp".to_string(),
+                path_check: Some("%TEMP%".to_string()),
+                requires_confirmation: true,
+                requires_admin: false,
+                estimated_size: EstimatedSize::Fixed(100 * 1024 * 1024),
+                icon: Some("📝".to_string()),
+                ..Default::default()
+            }];
+            
+            // 创建符合 TOML 格式的配置内容
+            let config_str = format!(
+                "# WinCleaner 自定义清理规则配置\n# 警告：请谨慎配置，错误的命令可能导致系统问题\n\n[[task]]\n{}\n[[task]]\nname = \"清理 VSCode 工作区缓存\"\ndescription = \"清理 VSCode 工作区缓存文件\"\ncategory = \"Custom\"\ncommand = \"rmdir /s /q %APPDATA%\\\\Code\\\\User\\\\workspaceStorage\"\npath_check = \"%APPDATA%\\\\Code\\\\User\\\\workspaceStorage\"\nrequires_confirmation = true\nestimated_size = \"auto\"\nicon = \"💻\"\nitem_selection = true",
+                example_tasks.iter().map(|task| toml::to_string_pretty(task).unwrap()).collect::<Vec<_>>().join("\n").replace("[", "").replace("]", "")
+            );
+            
+            let _ = std::fs::write(CONFIG_FILE, &config_str);
+            log("创建示例配置文件");
+            UserConfig::default()
+        }
+    }
+}
+
+// 将任务作为新的 [[task]] 追加到配置文件末尾，保留用户原有的内容和注释
+pub(crate) fn append_custom_task(task: &CleanTask) -> Result<(), String> {
+    use std::io::Write;
+
+    if *AUDIT_MODE {
+        return Err(AUDIT_BLOCKED.to_string());
+    }
+
+    // 写入与加载时相同方式分配的ID（追加在现有规则之后），之后调整配置文件中规则的顺序也不会改变
+    let mut all = builtin_tasks();
+    all.extend(load_user_config().task);
+    all.push(task.clone());
+    assign_task_ids(&mut all);
+    let mut saved = task.clone();
+    saved.id = all.pop().map(|resolved| resolved.id).unwrap_or_default();
+
+    let definition = toml::Table::try_from(&saved).map_err(|e| format!("规则序列化失败: {}", e))?;
+    let mut document = toml::Table::new();
+    document.insert("task".to_string(), toml::Value::Array(vec![toml::Value::Table(definition)]));
+    let text = toml::to_string(&document).map_err(|e| format!("规则序列化失败: {}", e))?;
+
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(CONFIG_FILE)
+        .and_then(|mut file| write!(file, "\n{}", text))
+        .map_err(|e| format!("配置文件写入失败: {}", e))?;
+    log(&format!("添加自定义规则: {} ({})", saved.name, saved.id));
+    record_feature("保存自定义规则");
+    Ok(())
+}
+
+// 由任务名称生成ID（slug）。名称含非ASCII字符（如中文）时只靠其中的 ASCII 部分无法区分规则，
+// 追加名称的哈希，使ID只取决于名称本身而不是规则在配置文件中的位置
+pub(crate) fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    let slug = if slug.is_empty() { "task" } else { slug };
+    if name.is_ascii() {
+        slug.to_string()
+    } else {
+        format!("{}-{:08x}", slug, name_hash(name))
+    }
+}
+
+// FNV-1a，结果不随 Rust 版本变化，可用于持久保存的ID
+fn name_hash(name: &str) -> u32 {
+    name.bytes()
+        .fold(0x811c_9dc5u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
+// 内置任务应用配置覆盖后与自定义任务合并，并分配任务ID
+pub(crate) fn merge_tasks(user_config: UserConfig) -> Vec<CleanTask> {
+    let mut all = builtin_tasks();
+    apply_task_overrides(&mut all, &user_config.overrides);
+    all.extend(user_config.task);
+    assign_task_ids(&mut all);
+    check_run_after(&mut all);
+    check_schedules(&mut all);
+    all
+}
+
+// 需要确认的任务在定期清理中总会被跳过，忽略其 schedule，不为其注册计划任务
+fn check_schedules(tasks: &mut [CleanTask]) {
+    for task in tasks.iter_mut().filter(|task| task.schedule.is_some()) {
+        if task.always_needs_confirmation() {
+            log(&format!("配置警告: {} 需要确认后才能执行，不能定期清理，已忽略 schedule", task.name));
+            task.schedule = None;
+        }
+    }
+}
+
+// 检查 run_after：引用不存在的任务时记录警告；存在循环依赖时记录整条循环，
+// 并忽略环上各任务的 run_after，批量清理中按原顺序执行
+fn check_run_after(tasks: &mut [CleanTask]) {
+    // 0 未访问，1 正在访问（在当前路径上），2 已完成
+    fn visit(node: usize, edges: &[Vec<usize>], state: &mut [u8], path: &mut Vec<usize>, cycles: &mut Vec<Vec<usize>>) {
+        state[node] = 1;
+        path.push(node);
+        for &next in &edges[node] {
+            match state[next] {
+                0 => visit(next, edges, state, path, cycles),
+                1 => {
+                    let start = path.iter().position(|&step| step == next).unwrap_or(0);
+                    cycles.push(path[start..].to_vec());
+                }
+                _ => {}
+            }
+        }
+        path.pop();
+        state[node] = 2;
+    }
+
+    for task in tasks.iter() {
+        for dependency in &task.run_after {
+            if !tasks.iter().any(|other| other.id == *dependency || other.name == *dependency) {
+                log(&format!("配置警告: {} 的 run_after 引用了不存在的任务: {}", task.name, dependency));
+            }
+        }
+    }
+
+    let edges = tasks
+        .iter()
+        .map(|task| {
+            tasks
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| task.runs_after(other))
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut state = vec![0u8; tasks.len()];
+    let mut cycles = Vec::new();
+    for node in 0..tasks.len() {
+        if state[node] == 0 {
+            visit(node, &edges, &mut state, &mut Vec::new(), &mut cycles);
+        }
+    }
+
+    for cycle in cycles {
+        let names = cycle
+            .iter()
+            .chain(cycle.first())
+            .map(|&index| tasks[index].name.as_str())
+            .collect::<Vec<_>>()
+            .join(" → ");
+        log(&format!("配置警告: run_after 存在循环依赖: {}，已忽略这些任务的 run_after", names));
+        for index in cycle {
+            tasks[index].run_after.clear();
+        }
+    }
+}
+
+// 为缺少ID的任务生成ID，并解决ID/名称冲突（内置任务优先，后出现的追加序号）
+fn assign_task_ids(tasks: &mut [CleanTask]) {
+    let mut used_ids = HashSet::new();
+    let mut used_names = HashSet::new();
+
+    for task in tasks.iter_mut() {
+        if !used_names.insert(task.name.clone()) {
+            log(&format!("配置警告: 任务名称重复: {}", task.name));
+        }
+
+        let base = if task.id.is_empty() {
+            slugify(&task.name)
+        } else {
+            task.id.clone()
+        };
+
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while used_ids.contains(&id) {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+
+        if id != base {
+            log(&format!("配置警告: 任务ID冲突: {} ({})，已重命名为 {}", base, task.name, id));
+        }
+
+        used_ids.insert(id.clone());
+        task.id = id;
+    }
+}
+
+// 信息亭模式 - 以 --kiosk 启动、在设置中开启或由组策略注册表项启用，用于机房和公用电脑：
+// 只显示一个固定的清理按钮和结果，不显示自定义规则、设置和有风险的任务
+const KIOSK_POLICY_KEY: &str = "HKLM\\SOFTWARE\\Policies\\WinCleaner";
+
+pub(crate) static KIOSK_MODE: Lazy<bool> = Lazy::new(|| {
+    SETTINGS.kiosk_mode || std::env::args().any(|arg| arg == "--kiosk") || kiosk_policy_enabled()
+});
+
+// 策略值 Kiosk 为 REG_DWORD 1 时启用，普通用户无法修改 HKLM 下的策略
+fn kiosk_policy_enabled() -> bool {
+    run_query_command(&format!("reg query \"{}\" /v Kiosk", KIOSK_POLICY_KEY))
+        .and_then(|output| output.split_whitespace().last().map(str::to_string))
+        .is_some_and(|value| value == "0x1")
+}
+
+// 信息亭按钮执行的任务：只取内置任务，且必须是无需确认、无需管理员权限的低风险任务；
+// 设置了 kiosk_tasks 时再按其中的 ID 筛选
+pub(crate) fn kiosk_task_list(overrides: &[TaskOverride]) -> Vec<CleanTask> {
+    let mut builtin = builtin_tasks();
+    apply_task_overrides(&mut builtin, overrides);
+    assign_task_ids(&mut builtin);
+    builtin
+        .into_iter()
+        .filter(|task| {
+            task.risk == RiskLevel::Low
+                && !task.needs_confirmation()
+                && !task.is_item_only()
+                && (!task.requires_admin || *IS_ELEVATED)
+        })
+        .filter(|task| SETTINGS.kiosk_tasks.is_empty() || SETTINGS.kiosk_tasks.contains(&task.id))
+        .collect()
+}
+
+// 审计模式 - 以 --audit 启动或在设置中开启后禁用所有清理和写入规则的操作，只扫描并导出报告
+pub(crate) static AUDIT_MODE: Lazy<bool> =
+    Lazy::new(|| SETTINGS.audit_mode || std::env::args().any(|arg| arg == "--audit"));
+pub(crate) const AUDIT_BLOCKED: &str = "审计模式为只读，不执行任何清理";
+
+// 审计报告：本机概况、各任务的可清理大小以及当前的提醒
+fn audit_report(tasks: &[CleanTask]) -> String {
+    let mut lines = vec![
+        "WinCleaner 审计报告".to_string(),
+        format!("生成时间: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")),
+        format!(
+            "计算机: {}  用户: {}  管理员权限: {}",
+            std::env::var("COMPUTERNAME").unwrap_or_default(),
+            std::env::var("USERNAME").unwrap_or_default(),
+            if *IS_ELEVATED { "是" } else { "否" }
+        ),
+        String::new(),
+        "磁盘可用空间:".to_string(),
+    ];
+    for (root, bytes) in free_space_snapshot(tasks) {
+        lines.push(format!("  {}  {}", root, format_size(bytes)));
+    }
+
+    let mut sized = tasks
+        .iter()
+        .map(|task| (task, task.get_estimated_bytes()))
+        .collect::<Vec<_>>();
+    sized.sort_by_key(|(_, bytes)| std::cmp::Reverse(*bytes));
+    lines.push(String::new());
+    lines.push("可清理项目（按大小排序）:".to_string());
+    for (task, _) in &sized {
+        lines.push(format!(
+            "  [{}] {} - {}（{}）{}",
+            task.category.label(),
+            task.name,
+            task.get_actual_size().unwrap_or_else(|| "未知".to_string()),
+            task.risk.label(),
+            task.get_expanded_path().map(|path| format!("  {}", path)).unwrap_or_default()
+        ));
+    }
+    let total = sized.iter().filter_map(|(_, bytes)| *bytes).sum::<u64>();
+    lines.push(format!("合计: {}", format_size(total)));
+
+    let alerts = growth_alerts();
+    if !alerts.is_empty() {
+        lines.push(String::new());
+        lines.push("增长提醒:".to_string());
+        lines.extend(alerts.iter().map(|alert| format!("  {} - {}", alert.path, alert.summary())));
+    }
+    if let Some(hint) = current_av_hint() {
+        lines.push(String::new());
+        lines.push(format!("杀毒软件: {}", hint.message()));
+    }
+
+    lines.join("\r\n")
+}
+
+// 将审计报告写入程序目录，返回文件路径
+pub(crate) fn export_audit_report(tasks: &[CleanTask]) -> Result<String, String> {
+    let file = format!("wincleaner-audit-{}.txt", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    fs::write(&file, audit_report(tasks)).map_err(|e| format!("报告写入失败: {}", e))?;
+    let path = fs::canonicalize(&file).map_or(file, |path| path.to_string_lossy().to_string());
+    log(&format!("导出审计报告: {}", path));
+    record_feature("审计报告");
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(name: &str, id: &str) -> CleanTask {
+        CleanTask {
+            id: id.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn slugify_keeps_ascii_names_readable() {
+        assert_eq!(slugify("Clean npm Cache!"), "clean-npm-cache");
+        assert_eq!(slugify("--"), "task");
+    }
+
+    #[test]
+    fn slugify_distinguishes_non_ascii_names() {
+        let first = slugify("清理下载文件夹");
+        let second = slugify("清理日志");
+        assert!(first.starts_with("task-"));
+        assert_ne!(first, second);
+        assert_eq!(first, slugify("清理下载文件夹"));
+        assert!(slugify("清理 VSCode 缓存").starts_with("vscode-"));
+    }
+
+    #[test]
+    fn assign_task_ids_does_not_depend_on_order() {
+        let mut tasks = vec![task("清理下载文件夹", ""), task("清理日志", "")];
+        assign_task_ids(&mut tasks);
+        let mut reordered = vec![task("清理日志", "")];
+        assign_task_ids(&mut reordered);
+        assert_eq!(reordered[0].id, tasks[1].id);
+    }
+
+    #[test]
+    fn assign_task_ids_resolves_collisions() {
+        let mut tasks = vec![task("Temp", "temp"), task("Temp files", "temp"), task("temp", "")];
+        assign_task_ids(&mut tasks);
+        let ids = tasks.iter().map(|task| task.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["temp", "temp-2", "temp-3"]);
+    }
+
+    #[test]
+    fn schedules_are_dropped_from_tasks_needing_confirmation() {
+        let scheduled = |risk| CleanTask {
+            risk,
+            schedule: Some(TaskSchedule::Weekly),
+            ..task("Temp", "temp")
+        };
+        let mut tasks = vec![scheduled(RiskLevel::Low), scheduled(RiskLevel::High)];
+        tasks.push(CleanTask {
+            patterns: vec![r"%TEMP%\*.log".to_string()],
+            ..scheduled(RiskLevel::Low)
+        });
+        check_schedules(&mut tasks);
+        let schedules = tasks.iter().map(|task| task.schedule).collect::<Vec<_>>();
+        assert_eq!(schedules, [Some(TaskSchedule::Weekly), None, None]);
+    }
+}
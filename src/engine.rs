@@ -0,0 +1,4739 @@
+// 清理引擎 - 执行单项、批量和逐项清理，管理清理队列、取消、撤销区和按需提权，
+// 以及子项分析、定期清理、开机清理和性能基准
+use crate::cleaner;
+use crate::config::{
+    load_user_config, merge_tasks, CompletionFeedback, AUDIT_BLOCKED, AUDIT_MODE, CONFIG_FILE, KIOSK_MODE,
+    SETTINGS,
+};
+use crate::glob;
+use crate::logging::{
+    append_history, csv_field, last_clean, log, record_error, record_feature, reload_history_since,
+    EnvironmentSnapshot, HistoryEntry, TaskLog, HISTORY,
+};
+use crate::quarantine;
+use crate::sizing::{
+    drive_kind, format_size, free_space_snapshot, get_directory_size, is_scan_running, load_acknowledgements,
+    new_content_since, parse_size, peek_size_bytes, run_query_command, scan_directory_size, scan_tuning,
+    snapshot_directory, system_drive_root, volume_free_space, volume_root, DriveKind, NewContent, ScanPermit,
+    ScanThrottle, SCAN_POLL_INTERVAL,
+};
+use crate::tasks::{
+    builtin_tasks, check_target_sanity, command_path_arguments, expand_environment_variables,
+    find_unresolved_variables, never_touch_rule, normalize_path_for_compare, rule_task, Browser,
+    CleanCategory, CleanTask, DeleteMode, DeleteStrategy, RiskLevel,
+};
+use crate::trash;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct CleanupStats {
+    pub(crate) total_tasks: usize,
+    pub(crate) successful_tasks: usize,
+    pub(crate) failed_tasks: usize,
+    pub(crate) skipped_tasks: usize, // 未经确认、限时结束或无需清理而跳过的任务
+    pub(crate) total_space_freed: Option<u64>, // in bytes
+    pub(crate) errors: Vec<(String, String)>,  // (task id, error)
+    pub(crate) skipped: Vec<(String, String)>, // (task id, 跳过原因)
+    pub(crate) volume_freed: Vec<(String, u64)>, // (卷根目录, 可用空间增加的字节数)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum AppState {
+    Idle,
+    Running(String),
+    Success,
+    SuccessWithStats(CleanupStats),
+    // 用户取消，统计中只包含取消前已完成的部分
+    Cancelled(CleanupStats),
+    Error(String),
+}
+
+// 清理过程中由引擎写入、界面显示的状态（界面中由 Signal 实现），引擎本身不依赖界面框架
+pub(crate) trait StateCell<T>: Copy + 'static {
+    fn load(&self) -> T;
+    fn store(&mut self, value: T);
+}
+
+// 清理状态的宿主：除读写状态外，还负责在根作用域中启动清理队列的执行器，
+// 关闭弹窗或切换分类不会中断正在执行的清理
+pub(crate) trait RunHost: StateCell<AppState> {
+    fn spawn_worker(&self, worker: impl std::future::Future<Output = ()> + 'static);
+}
+
+// 目标目录下的直接子项
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TargetItem {
+    pub(crate) name: String,
+    pub(crate) path: std::path::PathBuf,
+    pub(crate) size: u64,
+    pub(crate) detail: Option<String>, // 分析器给出的说明，如对应的项目路径
+    pub(crate) orphaned: bool,         // 分析器判断为可安全清理（默认勾选）
+    pub(crate) files: Vec<std::path::PathBuf>, // 非空时只删除这些文件，保留 path 目录本身
+}
+
+// 子项分析器
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ItemAnalyzer {
+    // VSCode workspaceStorage：解析 workspace.json，项目已不存在的条目视为孤立
+    VscodeWorkspaceStorage,
+    // 卸载残留：在各应用数据目录中查找不属于任何已安装应用的目录，按置信度预选
+    UninstallLeftovers,
+    // %WINDIR%\Installer：通过 MSI API 区分仍被引用、已被取代和孤立的安装包/补丁包
+    WindowsInstaller,
+    // Package Cache：按目录名中的产品/捆绑包代码判断所属产品是否仍已安装
+    PackageCache,
+    // 日志与崩溃转储：按位置汇总早于 log_min_age_days 的 *.log / *.dmp 文件
+    LogFiles,
+    // 回收站：每个磁盘一项，只清空选中磁盘的回收站
+    RecycleBins,
+    // 备用数据流：按目录和流名汇总下载和项目文件夹中带 Zone.Identifier 等数据流的文件，只删除数据流
+    AlternateStreams,
+}
+
+impl ItemAnalyzer {
+    pub(crate) fn analyze(&self, item: &mut TargetItem) {
+        match self {
+            ItemAnalyzer::VscodeWorkspaceStorage => {
+                let Some(uri) = read_vscode_workspace_uri(&item.path) else {
+                    item.detail = Some("无 workspace.json".to_string());
+                    return;
+                };
+
+                match file_uri_to_path(&uri) {
+                    Some(project_path) => {
+                        item.orphaned = !Path::new(&project_path).exists();
+                        item.detail = Some(if item.orphaned {
+                            format!("{}（已不存在）", project_path)
+                        } else {
+                            project_path
+                        });
+                    }
+                    // 远程工作区无法检查，保持未勾选
+                    None => item.detail = Some(format!("远程: {}", uri)),
+                }
+            }
+            // 需要整体信息的分析器在 list_target_items 中处理
+            ItemAnalyzer::UninstallLeftovers
+            | ItemAnalyzer::WindowsInstaller
+            | ItemAnalyzer::PackageCache
+            | ItemAnalyzer::LogFiles
+            | ItemAnalyzer::RecycleBins
+            | ItemAnalyzer::AlternateStreams => {}
+        }
+    }
+
+    // 在选择对话框顶部显示的警告
+    pub(crate) fn warning(&self) -> Option<&'static str> {
+        match self {
+            ItemAnalyzer::WindowsInstaller => Some(
+                "⚠️ 删除仍被引用的安装包会导致相关软件无法修复、更新或卸载。默认只勾选未被任何产品或补丁引用的孤立包，请勿手动勾选标记为必需的项目。",
+            ),
+            ItemAnalyzer::PackageCache => Some(
+                "⚠️ Package Cache 中的安装包用于修复和卸载。默认只勾选所属产品已不存在的目录，无法判断归属的目录会保留。",
+            ),
+            ItemAnalyzer::UninstallLeftovers => Some(
+                "⚠️ 候选目录仅根据名称与已安装应用的匹配情况判断，便携版软件可能被误判，请逐项核对。",
+            ),
+            ItemAnalyzer::AlternateStreams => Some(
+                "⚠️ 删除 Zone.Identifier 会解除文件的\"来自 Internet\"标记，之后打开时不再有 SmartScreen 和受保护视图提示，请只解除确认可信的文件。其他程序写入的数据流默认不勾选。",
+            ),
+            ItemAnalyzer::VscodeWorkspaceStorage | ItemAnalyzer::LogFiles | ItemAnalyzer::RecycleBins => None,
+        }
+    }
+}
+
+// 读取 workspace.json 中的 folder 或 workspace 字段
+fn read_vscode_workspace_uri(dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(dir.join("workspace.json")).ok()?;
+    let value = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+    value["folder"]
+        .as_str()
+        .or_else(|| value["workspace"].as_str())
+        .map(|uri| uri.to_string())
+}
+
+// 将 file:///c%3A/Users/me/proj 转换为 c:\Users\me\proj，非 file 协议返回None
+fn file_uri_to_path(uri: &str) -> Option<String> {
+    let encoded = uri.strip_prefix("file:///")?;
+
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    Some(String::from_utf8_lossy(&decoded).replace('/', "\\"))
+}
+
+// 卸载残留扫描的应用数据根目录
+const LEFTOVER_ROOTS: &[&str] = &["%APPDATA%", "%LOCALAPPDATA%", "%PROGRAMDATA%"];
+
+// 注册表中的卸载列表
+const UNINSTALL_KEYS: &[&str] = &[
+    "HKLM\\SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+    "HKLM\\SOFTWARE\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+    "HKCU\\SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+];
+
+// 系统或共享目录（归一化后的名称），即使未匹配到已安装应用也不作为候选
+const LEFTOVER_IGNORED: &[&str] = &[
+    "microsoft", "packages", "programs", "temp", "packagecache", "windows", "comms",
+    "connecteddevicesplatform", "d3dscache", "crashdumps", "applicationdata", "history",
+    "temporaryinternetfiles", "desktop", "documents", "startmenu", "templates",
+    "virtualstore", "publishers", "identities", "fonts", "peernetworking", "ssh",
+    "usoshared", "usoprivate", "softwaredistribution", "regid19918commicrosoft",
+    "nvidia", "nvidiacorporation", "intel", "amd", "realtek", "wincleaner",
+];
+
+// 归一化应用名称：只保留小写字母和数字，便于目录名与卸载列表互相匹配
+fn normalize_app_name(name: &str) -> String {
+    name.chars()
+        .filter(|ch| ch.is_alphanumeric())
+        .flat_map(|ch| ch.to_lowercase())
+        .collect()
+}
+
+// 已安装应用的归一化名称：注册表卸载列表的名称、发布者、安装目录，以及 Program Files 下的目录
+fn installed_app_names() -> Vec<String> {
+    const PROGRAM_DIRS: &[&str] = &["%PROGRAMFILES%", "%PROGRAMFILES(X86)%", "%LOCALAPPDATA%\\Programs"];
+
+    let mut names = Vec::new();
+    for key in UNINSTALL_KEYS {
+        let Some(output) = run_query_command(&format!("reg query \"{}\" /s", key)) else {
+            continue;
+        };
+
+        // 值行格式: "    DisplayName    REG_SZ    Foo Bar"
+        for line in output.lines() {
+            let mut parts = line.trim().splitn(3, "    ");
+            let (Some(value_name), Some(value_type), Some(value)) = (parts.next(), parts.next(), parts.next()) else {
+                continue;
+            };
+            if !value_type.starts_with("REG_") {
+                continue;
+            }
+
+            let value = value.trim().trim_matches('"');
+            match value_name {
+                "DisplayName" | "Publisher" => names.push(normalize_app_name(value)),
+                "InstallLocation" => {
+                    let dir_name = Path::new(value.trim_end_matches('\\'))
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    names.push(normalize_app_name(&dir_name));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for dir in PROGRAM_DIRS {
+        let Ok(entries) = fs::read_dir(expand_environment_variables(dir)) else {
+            continue;
+        };
+        names.extend(
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| normalize_app_name(&entry.file_name().to_string_lossy())),
+        );
+    }
+
+    names.retain(|name| name.len() >= 3);
+    names.sort();
+    names.dedup();
+    names
+}
+
+// 扫描应用数据目录中的卸载残留，按置信度评分：长期未修改的目录置信度更高
+fn list_uninstall_leftovers() -> Vec<TargetItem> {
+    let installed = installed_app_names();
+    if installed.is_empty() {
+        // 读不到卸载列表时无法判断，宁可不给出任何候选
+        log("卸载残留扫描: 未能读取已安装应用列表，跳过");
+        return Vec::new();
+    }
+
+    let mut items = Vec::new();
+    for root in LEFTOVER_ROOTS {
+        let Ok(entries) = fs::read_dir(expand_environment_variables(root)) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if !file_type.is_dir() || file_type.is_symlink() {
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            let key = normalize_app_name(&name);
+            if key.len() < 3 || name.starts_with('.') || LEFTOVER_IGNORED.contains(&key.as_str()) {
+                continue;
+            }
+            if installed.iter().any(|app| app.contains(&key) || key.contains(app.as_str())) {
+                continue;
+            }
+
+            let path = entry.path();
+            let idle_days = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .map(|elapsed| elapsed.as_secs() / 86400)
+                .unwrap_or(0);
+            let (confidence, level) = match idle_days {
+                180.. => (90, "高"),
+                30..=179 => (70, "中"),
+                _ => (40, "低"),
+            };
+
+            items.push(TargetItem {
+                name: format!("{}\\{}", root, name),
+                size: get_directory_size(&path.to_string_lossy()).unwrap_or(0),
+                path,
+                detail: Some(format!(
+                    "置信度 {} ({}%) · 未匹配到已安装应用 · {} 天未修改",
+                    level, confidence, idle_days
+                )),
+                orphaned: confidence >= 80,
+                files: Vec::new(),
+            });
+        }
+    }
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.size));
+    items
+}
+
+// Windows Installer 注册信息：仍被引用的本地安装包、已被取代的补丁包和已安装的产品代码
+#[derive(Debug, Default)]
+struct InstallerRegistry {
+    pub(crate) referenced: HashSet<String>,    // 小写完整路径
+    pub(crate) superseded: HashSet<String>,    // 小写完整路径
+    pub(crate) product_codes: HashSet<String>, // 大写 {GUID}，含卸载列表中的捆绑包代码
+}
+
+impl InstallerRegistry {
+    // 标注 %WINDIR%\Installer 下的子项
+    pub(crate) fn classify_installer_item(&self, item: &mut TargetItem) {
+        let key = item.path.to_string_lossy().to_lowercase();
+        let extension = item
+            .path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if item.path.is_dir() {
+            match leading_guid(&item.name) {
+                Some(code) if !self.product_codes.contains(&code) => {
+                    item.detail = Some("孤立: 所属产品已卸载的资源目录".to_string());
+                    item.orphaned = true;
+                }
+                _ => item.detail = Some("必需: 产品资源或补丁基线缓存".to_string()),
+            }
+        } else if extension != "msi" && extension != "msp" {
+            item.detail = Some("必需: 非安装包文件，保留".to_string());
+        } else if self.superseded.contains(&key) {
+            item.detail = Some("已被取代的补丁: 通常可删除，但之后无法单独卸载该补丁".to_string());
+        } else if self.referenced.contains(&key) {
+            item.detail = Some("必需: 已注册产品或补丁的本地安装包".to_string());
+        } else {
+            item.detail = Some("孤立: 未被任何已安装产品或补丁引用".to_string());
+            item.orphaned = true;
+        }
+    }
+
+    // 标注 Package Cache 下的子项，目录名形如 {GUID} 或 {GUID}v1.2.3
+    pub(crate) fn classify_package_cache_item(&self, item: &mut TargetItem) {
+        match leading_guid(&item.name) {
+            Some(code) if self.product_codes.contains(&code) => {
+                item.detail = Some("必需: 所属产品仍已安装".to_string());
+            }
+            Some(_) => {
+                item.detail = Some("孤立: 所属产品或捆绑包已卸载".to_string());
+                item.orphaned = true;
+            }
+            None => item.detail = Some("未知: 无法判断所属产品，保留".to_string()),
+        }
+    }
+}
+
+// 名称开头的 {GUID}（大写），不是GUID时返回None
+fn leading_guid(name: &str) -> Option<String> {
+    let guid = name.get(..38)?;
+    let is_guid = guid.starts_with('{')
+        && guid.ends_with('}')
+        && guid.chars().skip(1).take(36).all(|ch| ch.is_ascii_hexdigit() || ch == '-');
+    is_guid.then(|| guid.to_uppercase())
+}
+
+// 卸载列表中以 {GUID} 命名的子键（MSI 产品代码或 Burn 捆绑包代码）
+fn registered_uninstall_codes() -> HashSet<String> {
+    UNINSTALL_KEYS
+        .iter()
+        .filter_map(|key| run_query_command(&format!("reg query \"{}\"", key)))
+        .flat_map(|output| {
+            output
+                .lines()
+                .filter_map(|line| line.trim().rsplit('\\').next().and_then(leading_guid))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+pub(crate) fn to_wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+// 通过 MSI API 枚举所有产品和补丁的本地安装包，失败时返回None
+#[cfg(windows)]
+fn query_installer_registry() -> Option<InstallerRegistry> {
+    use windows_sys::Win32::Foundation::{ERROR_NO_MORE_ITEMS, ERROR_SUCCESS};
+    use windows_sys::Win32::System::ApplicationInstallationAndServicing::{
+        MsiEnumPatchesExW, MsiEnumProductsExW, MsiGetPatchInfoExW, MsiGetProductInfoExW,
+        MSIINSTALLCONTEXT_ALL, MSIPATCHSTATE_ALL, MSIPATCHSTATE_OBSOLETED, MSIPATCHSTATE_SUPERSEDED,
+    };
+
+    // 读取字符串属性，缓冲区由调用方提供
+    fn read_msi_string(query: impl FnOnce(*mut u16, *mut u32) -> u32) -> Option<String> {
+        let mut buffer = vec![0u16; 1024];
+        let mut len = buffer.len() as u32;
+        (query(buffer.as_mut_ptr(), &mut len) == ERROR_SUCCESS)
+            .then(|| String::from_utf16_lossy(&buffer[..len as usize]))
+    }
+
+    let everyone = to_wide("s-1-1-0");
+    let local_package = to_wide("LocalPackage");
+    let mut registry = InstallerRegistry::default();
+
+    // 产品
+    let mut index = 0;
+    loop {
+        let mut product_code = [0u16; 39];
+        let mut context = 0;
+        let mut sid = [0u16; 256];
+        let mut sid_len = sid.len() as u32;
+        let result = unsafe {
+            MsiEnumProductsExW(
+                std::ptr::null(),
+                everyone.as_ptr(),
+                MSIINSTALLCONTEXT_ALL as _,
+                index,
+                product_code.as_mut_ptr(),
+                &mut context,
+                sid.as_mut_ptr(),
+                &mut sid_len,
+            )
+        };
+        if result == ERROR_NO_MORE_ITEMS {
+            break;
+        }
+        if result != ERROR_SUCCESS {
+            log(&format!("枚举 MSI 产品失败: 错误码 {}", result));
+            return None;
+        }
+        index += 1;
+
+        // 机器级安装没有SID，此时必须传空指针
+        let sid_ptr = if sid_len == 0 { std::ptr::null() } else { sid.as_ptr() };
+        let package = read_msi_string(|buffer, len| unsafe {
+            MsiGetProductInfoExW(product_code.as_ptr(), sid_ptr, context, local_package.as_ptr(), buffer, len)
+        });
+        if let Some(package) = package {
+            registry.referenced.insert(package.to_lowercase());
+        }
+        registry
+            .product_codes
+            .insert(String::from_utf16_lossy(&product_code[..38]).to_uppercase());
+    }
+
+    // 补丁：先记录全部补丁的本地包，再单独标记已被取代或已过时的补丁
+    for (filter, superseded) in [
+        (MSIPATCHSTATE_ALL, false),
+        (MSIPATCHSTATE_SUPERSEDED | MSIPATCHSTATE_OBSOLETED, true),
+    ] {
+        let mut index = 0;
+        loop {
+            let mut patch_code = [0u16; 39];
+            let mut product_code = [0u16; 39];
+            let mut context = 0;
+            let mut sid = [0u16; 256];
+            let mut sid_len = sid.len() as u32;
+            let result = unsafe {
+                MsiEnumPatchesExW(
+                    std::ptr::null(),
+                    everyone.as_ptr(),
+                    MSIINSTALLCONTEXT_ALL as _,
+                    filter as _,
+                    index,
+                    patch_code.as_mut_ptr(),
+                    product_code.as_mut_ptr(),
+                    &mut context,
+                    sid.as_mut_ptr(),
+                    &mut sid_len,
+                )
+            };
+            if result != ERROR_SUCCESS {
+                break;
+            }
+            index += 1;
+
+            let sid_ptr = if sid_len == 0 { std::ptr::null() } else { sid.as_ptr() };
+            let package = read_msi_string(|buffer, len| unsafe {
+                MsiGetPatchInfoExW(
+                    patch_code.as_ptr(),
+                    product_code.as_ptr(),
+                    sid_ptr,
+                    context,
+                    local_package.as_ptr(),
+                    buffer,
+                    len,
+                )
+            });
+            if let Some(package) = package {
+                let package = package.to_lowercase();
+                if superseded {
+                    registry.referenced.remove(&package);
+                    registry.superseded.insert(package);
+                } else {
+                    registry.referenced.insert(package);
+                }
+            }
+        }
+    }
+
+    registry.product_codes.extend(registered_uninstall_codes());
+    Some(registry)
+}
+
+#[cfg(not(windows))]
+fn query_installer_registry() -> Option<InstallerRegistry> {
+    None
+}
+
+// 固定的日志与崩溃转储位置
+const LOG_LOCATIONS: &[&str] = &[
+    "%LOCALAPPDATA%\\CrashDumps",
+    "%WINDIR%\\Minidump",
+    "%WINDIR%\\LiveKernelReports",
+    "%SYSTEMDRIVE%\\inetpub\\logs\\LogFiles",
+];
+
+// 在这些目录的前两级中查找名为 logs / log 的应用日志目录
+const LOG_SEARCH_ROOTS: &[&str] = &["%APPDATA%", "%LOCALAPPDATA%", "%PROGRAMDATA%"];
+
+// 日志或转储文件：*.log、轮转的 *.log.1、*.dmp、*.mdmp
+fn is_log_file(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.ends_with(".log") || name.contains(".log.") || name.ends_with(".dmp") || name.ends_with(".mdmp")
+}
+
+// 递归收集早于 min_age 的日志文件
+fn collect_log_files(dir: &Path, min_age: Duration, files: &mut Vec<(std::path::PathBuf, u64)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            if never_touch_rule(&entry.path()).is_none() {
+                collect_log_files(&entry.path(), min_age, files);
+            }
+            continue;
+        }
+        if !is_log_file(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let old_enough = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= min_age);
+        if old_enough {
+            files.push((entry.path(), metadata.len()));
+        }
+    }
+}
+
+// 启发式查找应用日志目录：<根>\<应用>\logs 或 <根>\<厂商>\<应用>\logs
+fn discover_log_dirs() -> Vec<std::path::PathBuf> {
+    fn is_log_dir_name(name: &str) -> bool {
+        name.eq_ignore_ascii_case("logs") || name.eq_ignore_ascii_case("log")
+    }
+
+    fn child_dirs(dir: &Path) -> Vec<std::path::PathBuf> {
+        fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir() && !kind.is_symlink()))
+                    .map(|entry| entry.path())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    let mut dirs = Vec::new();
+    for root in LOG_SEARCH_ROOTS {
+        for app_dir in child_dirs(Path::new(&expand_environment_variables(root))) {
+            for child in child_dirs(&app_dir) {
+                let name = child.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                if is_log_dir_name(&name) {
+                    dirs.push(child);
+                    continue;
+                }
+                dirs.extend(child_dirs(&child).into_iter().filter(|grandchild| {
+                    grandchild
+                        .file_name()
+                        .is_some_and(|name| is_log_dir_name(&name.to_string_lossy()))
+                }));
+            }
+        }
+    }
+    dirs
+}
+
+// 按位置汇总早于 min_age_days 的日志与转储文件，每个位置一项，默认全部勾选
+fn list_log_files(min_age_days: u64) -> Vec<TargetItem> {
+    let min_age = Duration::from_secs(min_age_days * 86400);
+    let locations = LOG_LOCATIONS
+        .iter()
+        .map(|location| std::path::PathBuf::from(expand_environment_variables(location)))
+        .chain(discover_log_dirs());
+
+    let mut items = Vec::new();
+    for location in locations {
+        let mut files = Vec::new();
+        collect_log_files(&location, min_age, &mut files);
+        if files.is_empty() {
+            continue;
+        }
+
+        items.push(TargetItem {
+            name: location.to_string_lossy().to_string(),
+            size: files.iter().map(|(_, size)| size).sum(),
+            detail: Some(format!("{} 个文件，均早于 {} 天", files.len(), min_age_days)),
+            orphaned: true,
+            files: files.into_iter().map(|(path, _)| path).collect(),
+            path: location,
+        });
+    }
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.size));
+    items
+}
+
+// 各磁盘上的回收站，每个磁盘一项，只默认勾选系统盘；path 为该盘的 $Recycle.Bin 目录，清空时通过系统接口按盘进行
+fn list_recycle_bins() -> Vec<TargetItem> {
+    let system_root = system_drive_root();
+    trash::recycle_bins()
+        .into_iter()
+        .filter(|bin| bin.items > 0)
+        .map(|bin| TargetItem {
+            name: format!("{} 回收站", bin.root.trim_end_matches('\\')),
+            path: Path::new(&bin.root).join("$Recycle.Bin"),
+            size: bin.bytes,
+            detail: Some(format!("{} 个项目", bin.items)),
+            orphaned: bin.root.eq_ignore_ascii_case(&system_root),
+            files: Vec::new(),
+        })
+        .collect()
+}
+
+// 清空子项所在磁盘的回收站，返回清空前的大小
+fn empty_recycle_bin_item(item: &TargetItem) -> Result<u64, String> {
+    if *AUDIT_MODE {
+        return Err(AUDIT_BLOCKED.to_string());
+    }
+    let root = volume_root(&item.path.to_string_lossy())
+        .ok_or_else(|| format!("无法确定回收站所在磁盘: {}", item.path.display()))?;
+    trash::empty_recycle_bin(&root)
+        .map(|()| item.size)
+        .map_err(|e| format!("{}: {}", item.name, e))
+}
+
+// 下载标记：浏览器和聊天软件下载文件时写入，删除即"解除锁定"
+const MARK_OF_THE_WEB_STREAMS: &[&str] = &["Zone.Identifier", "SmartScreen"];
+// 扫描备用数据流时跳过的目录：依赖和构建输出文件数量多，且不会带下载标记
+const STREAM_SCAN_SKIP_DIRS: &[&str] = &["node_modules", ".git", ".vs", "target", "bin", "obj"];
+// 每项详情中预览的文件名个数
+const STREAM_PREVIEW_FILES: usize = 5;
+
+// 递归收集带备用数据流的文件，每个数据流一条
+fn collect_stream_files(dir: &Path, found: &mut Vec<(std::path::PathBuf, cleaner::AlternateStream)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            continue;
+        }
+        let path = entry.path();
+        if file_type.is_dir() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let skipped = STREAM_SCAN_SKIP_DIRS.iter().any(|skip| skip.eq_ignore_ascii_case(&name));
+            if !skipped && never_touch_rule(&path).is_none() {
+                collect_stream_files(&path, found);
+            }
+            continue;
+        }
+
+        // 被独占打开的文件无法读取，直接跳过
+        if let Ok(streams) = cleaner::alternate_streams(&path) {
+            found.extend(streams.into_iter().map(|stream| (path.clone(), stream)));
+        }
+    }
+}
+
+// 按所在目录和流名汇总 stream_scan_dirs 中带备用数据流的文件，每组一项，files 为各数据流的完整路径；
+// 只默认勾选下载标记，其他程序写入的数据流（如同步软件的属性）需手动勾选
+fn list_alternate_streams() -> Vec<TargetItem> {
+    let mut roots = SETTINGS
+        .stream_scan_dirs
+        .iter()
+        .map(|dir| std::path::PathBuf::from(expand_environment_variables(dir)))
+        .collect::<Vec<_>>();
+    roots.sort();
+    roots.dedup();
+    // 已包含在其他扫描目录中的目录不重复扫描
+    let nested = roots
+        .iter()
+        .filter(|root| roots.iter().any(|other| other != *root && root.starts_with(other)))
+        .cloned()
+        .collect::<Vec<_>>();
+    roots.retain(|root| !nested.contains(root));
+
+    let mut groups: HashMap<(std::path::PathBuf, String), Vec<(std::path::PathBuf, u64)>> = HashMap::new();
+    for root in roots {
+        let mut found = Vec::new();
+        collect_stream_files(&root, &mut found);
+        for (file, stream) in found {
+            let dir = file.parent().map(Path::to_path_buf).unwrap_or_else(|| root.clone());
+            groups.entry((dir, stream.name)).or_default().push((file, stream.size));
+        }
+    }
+
+    let mut items = groups
+        .into_iter()
+        .map(|((dir, stream), mut files)| {
+            files.sort();
+            let preview = files
+                .iter()
+                .take(STREAM_PREVIEW_FILES)
+                .filter_map(|(file, _)| file.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("、");
+            let more = if files.len() > STREAM_PREVIEW_FILES { " 等" } else { "" };
+            TargetItem {
+                name: format!("{} ({})", dir.display(), stream),
+                size: files.iter().map(|(_, size)| size).sum(),
+                detail: Some(format!("{} 个文件: {}{}", files.len(), preview, more)),
+                orphaned: MARK_OF_THE_WEB_STREAMS
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(&stream)),
+                files: files
+                    .iter()
+                    .map(|(file, _)| cleaner::stream_path(file, &stream))
+                    .collect(),
+                path: dir,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    items.sort_by(|a, b| b.files.len().cmp(&a.files.len()).then_with(|| a.name.cmp(&b.name)));
+    items
+}
+
+// 删除选中组中各文件的备用数据流，文件本身保留；返回删除的数据流大小
+fn remove_stream_item(item: &TargetItem) -> Result<u64, String> {
+    if *AUDIT_MODE {
+        return Err(AUDIT_BLOCKED.to_string());
+    }
+
+    let mut failures = Vec::new();
+    for stream in &item.files {
+        match fs::remove_file(stream) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => failures.push(format!("{}: {}", stream.display(), e)),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(item.size)
+    } else {
+        Err(format!(
+            "{}: {} 个数据流删除失败\n{}",
+            item.path.display(),
+            failures.len(),
+            failures.join("\n")
+        ))
+    }
+}
+
+// 大文件查找 - 在用户目录中查找超过阈值的单个文件（虚拟机镜像、ISO 等），
+// 跳过由程序自行管理、不应手动删除的位置
+const LARGE_FILE_EXCLUDES: &[&str] = &[
+    "%LOCALAPPDATA%\\Packages",           // 应用商店应用和 WSL 的虚拟磁盘
+    "%LOCALAPPDATA%\\Microsoft\\Outlook", // Outlook 数据文件
+    "%LOCALAPPDATA%\\Microsoft\\WindowsApps",
+    "%LOCALAPPDATA%\\Docker", // Docker Desktop 的虚拟磁盘，应在 Docker 中清理
+    "%LOCALAPPDATA%\\Temp",   // 由临时文件任务清理
+];
+const LARGE_FILE_MAX_RESULTS: usize = 500;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LargeFile {
+    pub(crate) path: std::path::PathBuf,
+    pub(crate) size: u64,
+    pub(crate) modified: i64, // Unix时间戳（秒）
+    pub(crate) kind: String,  // 小写扩展名，没有扩展名时为空
+}
+
+impl LargeFile {
+    pub(crate) fn kind_label(&self) -> String {
+        if self.kind.is_empty() {
+            "无扩展名".to_string()
+        } else {
+            format!(".{}", self.kind)
+        }
+    }
+
+    pub(crate) fn modified_text(&self) -> String {
+        chrono::DateTime::from_timestamp(self.modified, 0)
+            .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn as_target_item(&self) -> TargetItem {
+        TargetItem {
+            name: self.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+            path: self.path.clone(),
+            size: self.size,
+            detail: None,
+            orphaned: false,
+            files: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum LargeFileSort {
+    Size, // 从大到小
+    Age,  // 最久未修改的在前
+    Kind, // 按扩展名分组，组内从大到小
+}
+
+impl LargeFileSort {
+    pub(crate) const ALL: [LargeFileSort; 3] = [LargeFileSort::Size, LargeFileSort::Age, LargeFileSort::Kind];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            LargeFileSort::Size => "大小",
+            LargeFileSort::Age => "时间",
+            LargeFileSort::Kind => "类型",
+        }
+    }
+
+    pub(crate) fn sort(&self, files: &mut [LargeFile]) {
+        match self {
+            LargeFileSort::Size => files.sort_by_key(|file| std::cmp::Reverse(file.size)),
+            LargeFileSort::Age => files.sort_by_key(|file| file.modified),
+            LargeFileSort::Kind => files.sort_by(|a, b| a.kind.cmp(&b.kind).then(b.size.cmp(&a.size))),
+        }
+    }
+}
+
+// 扫描用户目录，scanned 记录已检查的文件数供界面显示进度
+pub(crate) fn find_large_files(min_size: u64, scanned: &AtomicU64) -> Vec<LargeFile> {
+    fn walk(dir: &Path, min_size: u64, excludes: &[String], throttle: &mut ScanThrottle, scanned: &AtomicU64, found: &mut Vec<LargeFile>) {
+        if excludes.contains(&normalize_path_for_compare(&dir.to_string_lossy())) {
+            return;
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            throttle.tick();
+            let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            // 不进入符号链接和目录联接（如"我的文档"兼容联接）
+            if metadata.file_type().is_symlink() {
+                continue;
+            }
+            if metadata.is_dir() {
+                walk(&entry.path(), min_size, excludes, throttle, scanned, found);
+                continue;
+            }
+            scanned.fetch_add(1, Ordering::Relaxed);
+            if metadata.len() < min_size {
+                continue;
+            }
+            let path = entry.path();
+            found.push(LargeFile {
+                kind: path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default(),
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map_or(0, |elapsed| elapsed.as_secs() as i64),
+                size: metadata.len(),
+                path,
+            });
+        }
+    }
+
+    let profile = expand_environment_variables("%USERPROFILE%");
+    if profile.is_empty() || profile.contains('%') {
+        return Vec::new();
+    }
+    let excludes = LARGE_FILE_EXCLUDES
+        .iter()
+        .map(|path| normalize_path_for_compare(&expand_environment_variables(path)))
+        .collect::<Vec<_>>();
+    let Ok(entries) = fs::read_dir(&profile) else {
+        return Vec::new();
+    };
+
+    let tuning = scan_tuning(&profile);
+    let _permit = ScanPermit::acquire(&profile, tuning.max_workers);
+    let mut throttle = ScanThrottle::new(tuning);
+    let mut found = Vec::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        // OneDrive 中的文件可能只是云端占位符，删除会同步删除云端副本
+        if name.starts_with("onedrive") {
+            continue;
+        }
+        walk(&entry.path(), min_size, &excludes, &mut throttle, scanned, &mut found);
+    }
+    found.retain(|file| never_touch_rule(&file.path).is_none());
+    found.sort_by_key(|file| std::cmp::Reverse(file.size));
+    found.truncate(LARGE_FILE_MAX_RESULTS);
+    log(&format!("大文件查找: 检查 {} 个文件，找到 {} 个超过 {} 的文件", scanned.load(Ordering::Relaxed), found.len(), format_size(min_size)));
+    found
+}
+
+// 将文件移到归档文件夹，同名时追加序号；跨磁盘时先复制再删除原文件
+pub(crate) fn archive_file(path: &Path, archive_dir: &str) -> Result<std::path::PathBuf, String> {
+    if *AUDIT_MODE {
+        return Err(AUDIT_BLOCKED.to_string());
+    }
+    check_target_sanity(path)?;
+    let archive_dir = expand_environment_variables(archive_dir.trim());
+    if archive_dir.is_empty() {
+        return Err("请先填写归档文件夹".to_string());
+    }
+    let archive_dir = Path::new(&archive_dir);
+    if !archive_dir.is_absolute() {
+        return Err(format!("归档文件夹必须是绝对路径: {}", archive_dir.display()));
+    }
+    fs::create_dir_all(archive_dir).map_err(|e| format!("无法创建归档文件夹: {} ({})", archive_dir.display(), e))?;
+
+    let file_name = path.file_name().ok_or_else(|| format!("无效的文件路径: {}", path.display()))?;
+    let stem = path.file_stem().unwrap_or(file_name).to_string_lossy().to_string();
+    let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    let mut destination = archive_dir.join(file_name);
+    let mut suffix = 2;
+    while destination.exists() {
+        destination = archive_dir.join(format!("{} ({}){}", stem, suffix, extension));
+        suffix += 1;
+    }
+
+    if fs::rename(path, &destination).is_err() {
+        fs::copy(path, &destination).map_err(|e| format!("{}: 复制到归档文件夹失败 ({})", path.display(), e))?;
+        if let Err(e) = fs::remove_file(path) {
+            let _ = fs::remove_file(&destination);
+            return Err(format!("{}: 已复制但无法删除原文件 ({})", path.display(), e));
+        }
+    }
+    log(&format!("归档文件: {} -> {}", path.display(), destination.display()));
+    Ok(destination)
+}
+
+// 列出目标目录的直接子项及其大小，按大小降序；内置禁区中的项目不会出现
+pub(crate) fn list_target_items(target: &str, analyzer: Option<ItemAnalyzer>) -> Vec<TargetItem> {
+    let mut items = analyze_target_items(target, analyzer);
+    items.retain(|item| match never_touch_rule(&item.path) {
+        Some(rule) => {
+            log(&format!("内置禁区: 跳过 {}（{}）", item.path.display(), rule));
+            false
+        }
+        None => true,
+    });
+    items
+}
+
+fn analyze_target_items(target: &str, analyzer: Option<ItemAnalyzer>) -> Vec<TargetItem> {
+    match analyzer {
+        Some(ItemAnalyzer::UninstallLeftovers) => list_uninstall_leftovers(),
+        Some(ItemAnalyzer::LogFiles) => list_log_files(SETTINGS.log_min_age_days),
+        Some(ItemAnalyzer::RecycleBins) => list_recycle_bins(),
+        Some(ItemAnalyzer::AlternateStreams) => list_alternate_streams(),
+        Some(analyzer @ (ItemAnalyzer::WindowsInstaller | ItemAnalyzer::PackageCache)) => {
+            let mut items = list_directory_items(target, None);
+            let Some(registry) = query_installer_registry() else {
+                // 无法读取注册信息时一律视为必需，不给出任何预选
+                log("安装包缓存分析: 无法读取 Windows Installer 注册信息");
+                for item in items.iter_mut() {
+                    item.detail = Some("未知: 无法读取 Windows Installer 注册信息，保留".to_string());
+                }
+                return items;
+            };
+
+            for item in items.iter_mut() {
+                if analyzer == ItemAnalyzer::WindowsInstaller {
+                    registry.classify_installer_item(item);
+                } else {
+                    registry.classify_package_cache_item(item);
+                }
+            }
+            items
+        }
+        analyzer => list_directory_items(target, analyzer),
+    }
+}
+
+// 列出目录的直接子项，按大小降序，可选逐项分析
+fn list_directory_items(target: &str, analyzer: Option<ItemAnalyzer>) -> Vec<TargetItem> {
+    let Ok(entries) = fs::read_dir(target) else {
+        return Vec::new();
+    };
+
+    let mut items = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let path = entry.path();
+            let size = if path.is_dir() {
+                get_directory_size(&path.to_string_lossy()).unwrap_or(0)
+            } else {
+                entry.metadata().map(|metadata| metadata.len()).unwrap_or(0)
+            };
+            let mut item = TargetItem {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path,
+                size,
+                detail: None,
+                orphaned: false,
+                files: Vec::new(),
+            };
+            if let Some(analyzer) = analyzer {
+                analyzer.analyze(&mut item);
+            }
+            item
+        })
+        .collect::<Vec<_>>();
+    items.sort_by_key(|item| std::cmp::Reverse(item.size));
+    items
+}
+
+// 直接删除文件或目录（不经过cmd），返回释放的字节数
+fn delete_path(path: &Path) -> Result<u64, String> {
+    if *AUDIT_MODE {
+        return Err(AUDIT_BLOCKED.to_string());
+    }
+    check_target_sanity(path)?;
+
+    let size = path_size(path);
+    if quarantine_path(path, size) {
+        return Ok(size);
+    }
+    let result = if path.is_dir() {
+        match fs::remove_dir_all(path) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                log(&format!("常规删除失败，改用清除策略: {} - {}", path.display(), e));
+                purge_directory(path).map_err(|purge_error| format!("{}；{}", e, purge_error))
+            }
+        }
+    } else {
+        fs::remove_file(path).map_err(|e| e.to_string())
+    };
+
+    match result {
+        Ok(()) => Ok(size),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+// 撤销区 - 启用后删除改为移入撤销区，按清理批次存放，可以整体还原
+static QUARANTINE: Lazy<quarantine::Quarantine> = Lazy::new(|| {
+    quarantine::Quarantine::new(
+        Path::new(&expand_environment_variables("%LOCALAPPDATA%"))
+            .join("WinCleaner")
+            .join("quarantine"),
+    )
+});
+
+// 当前清理批次：(批次ID, 已移入的大小)，未启用撤销区时为 None
+static QUARANTINE_RUN: Lazy<Mutex<Option<(String, u64)>>> = Lazy::new(|| Mutex::new(None));
+
+fn quarantine_max_bytes() -> u64 {
+    parse_size(&SETTINGS.quarantine_max_size).unwrap_or(10 * 1024 * 1024 * 1024)
+}
+
+// 删除过期的批次，以及超出大小上限的最旧批次
+pub(crate) fn prune_quarantine() {
+    let expire_before =
+        chrono::Local::now().timestamp() - SETTINGS.quarantine_days as i64 * 24 * 60 * 60;
+    let keep = QUARANTINE_RUN.lock().unwrap().as_ref().map(|(id, _)| id.clone());
+    for run_id in QUARANTINE.prune(quarantine_max_bytes(), expire_before, keep.as_deref()) {
+        log(&format!("撤销区批次已过期，已删除: {}", run_id));
+    }
+}
+
+// 一次单项、批量或定期清理开始：之后移入撤销区的文件属于同一批次，撤销时一起还原
+fn begin_quarantine_run() {
+    if !SETTINGS.quarantine_enabled || *AUDIT_MODE {
+        return;
+    }
+    let run_id = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    *QUARANTINE_RUN.lock().unwrap() = Some((run_id, 0));
+    prune_quarantine();
+}
+
+// 将要删除的路径移入当前批次，成功返回 true。超出大小上限、与撤销区不在同一磁盘
+// 或文件被占用时返回 false，由调用方直接删除
+fn quarantine_path(path: &Path, size: u64) -> bool {
+    let mut current = QUARANTINE_RUN.lock().unwrap();
+    let Some((run_id, staged)) = current.as_mut() else {
+        return false;
+    };
+    if *staged + size > quarantine_max_bytes() {
+        log(&format!("超出撤销区大小上限，直接删除: {}", path.display()));
+        return false;
+    }
+    match QUARANTINE.stage(run_id, path, size, chrono::Local::now().timestamp()) {
+        Ok(()) => {
+            *staged += size;
+            true
+        }
+        Err(e) => {
+            log(&format!("无法移入撤销区，直接删除: {}", e));
+            false
+        }
+    }
+}
+
+// 最近一次移入撤销区的清理的说明
+pub(crate) fn last_quarantine_summary() -> Option<String> {
+    let run = QUARANTINE.latest()?;
+    let time = chrono::DateTime::from_timestamp(run.created_at(), 0)
+        .map(|time| time.with_timezone(&chrono::Local).format("%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+    Some(format!(
+        "上次清理（{}）有 {} 项、{} 在撤销区",
+        time,
+        run.entries.len(),
+        format_size(run.total_bytes())
+    ))
+}
+
+// 撤销上次清理：将最近一个批次中的文件移回原位置
+pub(crate) fn undo_last_cleanup() -> Result<String, String> {
+    if *AUDIT_MODE {
+        return Err(AUDIT_BLOCKED.to_string());
+    }
+    let run = QUARANTINE.latest().ok_or("撤销区中没有可还原的清理")?;
+    let report = QUARANTINE.restore(&run.id)?;
+    log(&format!(
+        "撤销清理 {}: 还原 {} 项（{}），{} 项未能还原",
+        run.id,
+        report.restored,
+        format_size(report.restored_bytes),
+        report.failures.len()
+    ));
+    for (path, error) in &report.failures {
+        log(&format!("还原失败: {} - {}", path.display(), error));
+    }
+
+    let mut message = format!("已还原 {} 项（{}）", report.restored, format_size(report.restored_bytes));
+    if let Some((path, error)) = report.failures.first() {
+        message.push_str(&format!(
+            "，{} 项未能还原，仍保留在撤销区\n{}: {}",
+            report.failures.len(),
+            path.display(),
+            error
+        ));
+    }
+    Ok(message)
+}
+
+fn path_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        get_directory_size(&path.to_string_lossy()).unwrap_or(0)
+    } else {
+        fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0)
+    }
+}
+
+// 顽固目录清除：路径超过 MAX_PATH 或文件名畸形导致常规删除失败时使用
+// 先用 \\?\ 长路径前缀重试，仍失败则用 robocopy 将空目录镜像到目标后再删除
+fn purge_directory(path: &Path) -> Result<(), String> {
+    // Windows 上 canonicalize 返回带 \\?\ 前缀的路径，可绕过 MAX_PATH 限制
+    if let Ok(verbatim) = fs::canonicalize(path) {
+        if fs::remove_dir_all(&verbatim).is_ok() {
+            return Ok(());
+        }
+    }
+
+    let empty_dir = std::env::temp_dir().join(format!("wincleaner-empty-{}", std::process::id()));
+    fs::create_dir_all(&empty_dir).map_err(|e| format!("无法创建临时空目录: {}", e))?;
+
+    let mut cmd = Command::new("robocopy");
+    cmd.arg(&empty_dir)
+        .arg(path)
+        .args(["/MIR", "/R:0", "/W:0", "/NFL", "/NDL", "/NJH", "/NJS", "/NP"]);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let status = cmd.status();
+    let _ = fs::remove_dir(&empty_dir);
+
+    // robocopy 退出码 0-7 表示成功，8 及以上表示有文件未能处理
+    match status {
+        Ok(status) if status.code().is_some_and(|code| code < 8) => {}
+        Ok(status) => return Err(format!("robocopy 清除失败（退出码 {:?}）", status.code())),
+        Err(e) => return Err(format!("robocopy 无法执行: {}", e)),
+    }
+
+    fs::remove_dir_all(path).map_err(|e| format!("清除后删除目录失败: {}", e))
+}
+
+// 接管所有权：将路径及其全部内容的所有者改为 Administrators 并重置为完全控制，
+// 用于删除 TrustedInstaller 或其他用户遗留的文件。返回处理的条目数
+fn take_ownership_recursive(path: &Path) -> Result<usize, String> {
+    check_target_sanity(path)?;
+    log(&format!("接管所有权: {}", path.display()));
+
+    fn walk(path: &Path) -> Result<usize, String> {
+        // 先检查是否为符号链接或目录联接：SetNamedSecurityInfoW 会跟随重解析点，
+        // 修改的是链接目标（可能在清理范围之外）的所有者和权限。链接本身不接管，
+        // 接管其所在目录后即可凭删除子项的权限删除链接
+        let metadata = fs::symlink_metadata(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        // 目录联接在 symlink_metadata 中同样报告为符号链接
+        if metadata.file_type().is_symlink() {
+            log(&format!("接管所有权: 跳过符号链接或目录联接 {}", path.display()));
+            return Ok(0);
+        }
+
+        take_ownership(path)?;
+        let mut count = 1;
+
+        // 目录需要先接管才能列出内容
+        if metadata.is_dir() {
+            let entries = fs::read_dir(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            for entry in entries {
+                let entry = entry.map_err(|e| format!("{}: 无法列出目录内容 ({})", path.display(), e))?;
+                count += walk(&entry.path())?;
+            }
+        }
+        Ok(count)
+    }
+
+    walk(path)
+}
+
+#[cfg(windows)]
+fn take_ownership(path: &Path) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::{LocalFree, ERROR_SUCCESS};
+    use windows_sys::Win32::Security::Authorization::{
+        SetEntriesInAclW, SetNamedSecurityInfoW, EXPLICIT_ACCESS_W, NO_MULTIPLE_TRUSTEE, SET_ACCESS,
+        SE_FILE_OBJECT, TRUSTEE_IS_SID, TRUSTEE_IS_WELL_KNOWN_GROUP, TRUSTEE_W,
+    };
+    use windows_sys::Win32::Security::{
+        CreateWellKnownSid, WinBuiltinAdministratorsSid, ACL, DACL_SECURITY_INFORMATION,
+        OWNER_SECURITY_INFORMATION, SECURITY_MAX_SID_SIZE, SUB_CONTAINERS_AND_OBJECTS_INHERIT,
+        UNPROTECTED_DACL_SECURITY_INFORMATION,
+    };
+    use windows_sys::Win32::Storage::FileSystem::FILE_ALL_ACCESS;
+
+    enable_ownership_privileges();
+
+    let wide_path = to_wide(&path.to_string_lossy());
+    let mut sid = vec![0u8; SECURITY_MAX_SID_SIZE as usize];
+    let mut sid_len = sid.len() as u32;
+
+    unsafe {
+        let sid_ptr = sid.as_mut_ptr() as *mut core::ffi::c_void;
+        if CreateWellKnownSid(WinBuiltinAdministratorsSid, std::ptr::null_mut(), sid_ptr, &mut sid_len) == 0 {
+            return Err("无法创建 Administrators SID".to_string());
+        }
+
+        // 先设置所有者，之后才有权限修改 DACL
+        let result = SetNamedSecurityInfoW(
+            wide_path.as_ptr() as _,
+            SE_FILE_OBJECT,
+            OWNER_SECURITY_INFORMATION,
+            sid_ptr,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            std::ptr::null(),
+        );
+        if result != ERROR_SUCCESS {
+            return Err(format!("{}: 设置所有者失败（错误码 {}）", path.display(), result));
+        }
+
+        let access = EXPLICIT_ACCESS_W {
+            grfAccessPermissions: FILE_ALL_ACCESS,
+            grfAccessMode: SET_ACCESS,
+            grfInheritance: SUB_CONTAINERS_AND_OBJECTS_INHERIT,
+            Trustee: TRUSTEE_W {
+                pMultipleTrustee: std::ptr::null_mut(),
+                MultipleTrusteeOperation: NO_MULTIPLE_TRUSTEE,
+                TrusteeForm: TRUSTEE_IS_SID,
+                TrusteeType: TRUSTEE_IS_WELL_KNOWN_GROUP,
+                ptstrName: sid_ptr as *mut u16,
+            },
+        };
+        let mut acl: *mut ACL = std::ptr::null_mut();
+        let result = SetEntriesInAclW(1, &access, std::ptr::null(), &mut acl);
+        if result != ERROR_SUCCESS {
+            return Err(format!("{}: 创建访问控制列表失败（错误码 {}）", path.display(), result));
+        }
+
+        let result = SetNamedSecurityInfoW(
+            wide_path.as_ptr() as _,
+            SE_FILE_OBJECT,
+            DACL_SECURITY_INFORMATION | UNPROTECTED_DACL_SECURITY_INFORMATION,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            acl,
+            std::ptr::null(),
+        );
+        LocalFree(acl as _);
+        if result != ERROR_SUCCESS {
+            return Err(format!("{}: 重置权限失败（错误码 {}）", path.display(), result));
+        }
+    }
+
+    Ok(())
+}
+
+// 为当前进程启用接管所有权和还原权限（管理员令牌默认存在但未启用）
+#[cfg(windows)]
+fn enable_ownership_privileges() {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, LUID};
+    use windows_sys::Win32::Security::{
+        AdjustTokenPrivileges, LookupPrivilegeValueW, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED,
+        TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES, TOKEN_QUERY,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    unsafe {
+        let mut token: HANDLE = std::ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY, &mut token) == 0 {
+            return;
+        }
+
+        for name in ["SeTakeOwnershipPrivilege", "SeRestorePrivilege"] {
+            let wide_name = to_wide(name);
+            let mut luid = LUID { LowPart: 0, HighPart: 0 };
+            if LookupPrivilegeValueW(std::ptr::null(), wide_name.as_ptr(), &mut luid) == 0 {
+                continue;
+            }
+
+            let privileges = TOKEN_PRIVILEGES {
+                PrivilegeCount: 1,
+                Privileges: [LUID_AND_ATTRIBUTES { Luid: luid, Attributes: SE_PRIVILEGE_ENABLED }],
+            };
+            AdjustTokenPrivileges(token, 0, &privileges, 0, std::ptr::null_mut(), std::ptr::null_mut());
+        }
+        CloseHandle(token);
+    }
+}
+
+#[cfg(not(windows))]
+fn take_ownership(path: &Path) -> Result<(), String> {
+    Err(format!("{}: 接管所有权仅支持 Windows", path.display()))
+}
+
+// 将选中的子项（或其文件集合）一次性移入回收站，返回移走的大小
+fn recycle_target_item(item: &TargetItem) -> Result<u64, String> {
+    if *AUDIT_MODE {
+        return Err(AUDIT_BLOCKED.to_string());
+    }
+
+    let paths = if item.files.is_empty() {
+        vec![item.path.as_path()]
+    } else {
+        item.files.iter().map(std::path::PathBuf::as_path).collect()
+    };
+    let mut size = 0;
+    for path in &paths {
+        check_target_sanity(path)?;
+        size += path_size(path);
+    }
+
+    trash::move_to_recycle_bin(&paths)
+        .map(|()| size)
+        .map_err(|e| format!("{}: {}", item.path.display(), e))
+}
+
+// rmdir 类命令失败后目标目录仍存在时，返回可改用清除策略的目录
+fn stubborn_rmdir_target(task: &CleanTask) -> Option<std::path::PathBuf> {
+    if !task.command.trim_start().to_lowercase().starts_with("rmdir") {
+        return None;
+    }
+    let path = std::path::PathBuf::from(task.get_expanded_path()?);
+    path.is_dir().then_some(path)
+}
+
+// 删除选中的子项：文件集合逐个删除，否则删除整个路径
+pub(crate) fn delete_target_item(item: &TargetItem, mode: DeleteMode) -> Result<u64, String> {
+    if mode == DeleteMode::RecycleBin {
+        return recycle_target_item(item);
+    }
+    if item.files.is_empty() {
+        return delete_path(&item.path);
+    }
+
+    let mut freed = 0;
+    let mut failures = Vec::new();
+    for file in &item.files {
+        match delete_path(file) {
+            Ok(bytes) => freed += bytes,
+            Err(e) => failures.push(e),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(freed)
+    } else {
+        Err(format!(
+            "{}: {} 个文件删除失败（已释放 {}）\n{}",
+            item.path.display(),
+            failures.len(),
+            format_size(freed),
+            failures.join("\n")
+        ))
+    }
+}
+
+// 检测当前进程是否以管理员身份（已提升权限）运行，进程生命周期内不变
+pub(crate) static IS_ELEVATED: Lazy<bool> = Lazy::new(is_process_elevated);
+
+#[cfg(windows)]
+fn is_process_elevated() -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    unsafe {
+        let mut token: HANDLE = std::ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            return false;
+        }
+
+        let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+        let mut returned = 0u32;
+        let ok = GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut _ as *mut core::ffi::c_void,
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned,
+        );
+        CloseHandle(token);
+
+        ok != 0 && elevation.TokenIsElevated != 0
+    }
+}
+
+#[cfg(not(windows))]
+fn is_process_elevated() -> bool {
+    false
+}
+
+// 按需提权 - 未以管理员身份运行时，需要管理员权限的任务通过 UAC（ShellExecute 的 runas）
+// 以 --run-task <任务ID> 单独启动一个提升权限的进程执行，界面本身不必以管理员身份重启
+pub(crate) const RUN_TASK_ARG: &str = "--run-task";
+
+// 正在执行的提升权限任务数，这些任务无法从界面取消
+pub(crate) static ELEVATED_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+// 以管理员身份启动自身执行单个任务并等待其结束，返回进程退出码；用户在 UAC 提示中拒绝时返回错误
+#[cfg(windows)]
+fn run_task_elevated(task_id: &str) -> Result<u32, String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{GetExitCodeProcess, WaitForSingleObject, INFINITE};
+    use windows_sys::Win32::UI::Shell::{ShellExecuteExW, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW};
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_HIDE;
+    // 用户在 UAC 提示中选择"否"
+    const ERROR_CANCELLED: i32 = 1223;
+
+    let wide = |text: &std::ffi::OsStr| text.encode_wide().chain(std::iter::once(0)).collect::<Vec<u16>>();
+    let exe = std::env::current_exe().map_err(|e| format!("无法获取程序路径: {}", e))?;
+    let verb = wide(std::ffi::OsStr::new("runas"));
+    let file = wide(exe.as_os_str());
+    let parameters = wide(std::ffi::OsStr::new(&format!("{} \"{}\"", RUN_TASK_ARG, task_id)));
+    let directory = std::env::current_dir().map(|dir| wide(dir.as_os_str())).ok();
+
+    let mut info: SHELLEXECUTEINFOW = unsafe { std::mem::zeroed() };
+    info.cbSize = std::mem::size_of::<SHELLEXECUTEINFOW>() as u32;
+    info.fMask = SEE_MASK_NOCLOSEPROCESS;
+    info.lpVerb = verb.as_ptr();
+    info.lpFile = file.as_ptr();
+    info.lpParameters = parameters.as_ptr();
+    info.lpDirectory = directory.as_ref().map_or(std::ptr::null(), |dir| dir.as_ptr());
+    info.nShow = SW_HIDE;
+    if unsafe { ShellExecuteExW(&mut info) } == 0 {
+        let error = std::io::Error::last_os_error();
+        return Err(if error.raw_os_error() == Some(ERROR_CANCELLED) {
+            "已取消管理员授权".to_string()
+        } else {
+            format!("无法以管理员身份启动: {}", error)
+        });
+    }
+    if info.hProcess.is_null() {
+        return Err("无法以管理员身份启动: 未获得进程句柄".to_string());
+    }
+
+    let mut code = 1u32;
+    unsafe {
+        WaitForSingleObject(info.hProcess, INFINITE);
+        GetExitCodeProcess(info.hProcess, &mut code);
+        CloseHandle(info.hProcess);
+    }
+    Ok(code)
+}
+
+#[cfg(not(windows))]
+fn run_task_elevated(_task_id: &str) -> Result<u32, String> {
+    Err("以管理员身份运行仅支持 Windows".to_string())
+}
+
+// 提升权限的进程入口：执行指定任务并写入清理历史，退出码 0 表示成功或无需清理，1 表示失败
+pub(crate) fn run_task_cli(task_id: &str) -> i32 {
+    enter_program_dir();
+    let Some(task) = merge_tasks(load_user_config()).into_iter().find(|task| task.id == task_id) else {
+        log(&format!("找不到要执行的任务: {}", task_id));
+        return 1;
+    };
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            log(&format!("无法启动任务 {}: {}", task.name, e));
+            return 1;
+        }
+    };
+
+    log(&format!("以管理员身份执行任务: {}", task.name));
+    begin_quarantine_run();
+    let (result, _) = runtime.block_on(run_recorded_task(&task, &CancelToken::default()));
+    match result {
+        Ok(()) => 0,
+        Err(e) if task.skipped_by(&e) => 0,
+        Err(e) => {
+            log(&format!("任务失败: {} - {}", task.name, e));
+            1
+        }
+    }
+}
+
+// 杀毒软件干扰检测 - 扫描或删除异常缓慢时，测量文件打开延迟并检查常见杀毒软件进程
+// (进程名, 产品名)
+const AV_PROCESSES: &[(&str, &str)] = &[
+    ("MsMpEng.exe", "Microsoft Defender"),
+    ("360tray.exe", "360安全卫士"),
+    ("ZhuDongFangYu.exe", "360安全卫士"),
+    ("QQPCRTP.exe", "腾讯电脑管家"),
+    ("HipsDaemon.exe", "火绒安全"),
+    ("avp.exe", "Kaspersky"),
+    ("ekrn.exe", "ESET"),
+    ("AvastSvc.exe", "Avast"),
+    ("AVGSvc.exe", "AVG"),
+    ("mcshield.exe", "McAfee"),
+    ("vsserv.exe", "Bitdefender"),
+    ("ccSvcHst.exe", "Norton"),
+    ("SophosFileScanner.exe", "Sophos"),
+];
+pub(crate) const SLOW_SCAN_MIN_ENTRIES: usize = 5000; // 条目太少时速度不具参考性
+pub(crate) const SLOW_SCAN_RATE: f64 = 2000.0; // 条目/秒
+const SLOW_DELETE_MIN_FILES: u64 = 1000;
+const SLOW_DELETE_RATE: f64 = 300.0; // 文件/秒
+const AV_PROBE_ROUNDS: u32 = 50;
+const AV_LATENCY_THRESHOLD: Duration = Duration::from_millis(1);
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct AvHint {
+    pub(crate) products: Vec<String>,
+    pub(crate) open_latency: Duration,
+    pub(crate) trigger: String, // 触发检测的缓慢操作
+}
+
+impl AvHint {
+    // 在已忽略列表中使用的标识
+    pub(crate) fn key(&self) -> String {
+        format!("av:{}", self.products.join(","))
+    }
+
+    pub(crate) fn message(&self) -> String {
+        let products = if self.products.is_empty() {
+            "实时防护软件".to_string()
+        } else {
+            self.products.join("、")
+        };
+        format!(
+            "{}。文件打开平均耗时 {:.2} ms，可能是 {} 正在扫描被访问的文件。可在其中将 {} 和要清理的缓存目录加入排除列表。",
+            self.trigger,
+            self.open_latency.as_secs_f64() * 1000.0,
+            products,
+            std::env::temp_dir().display()
+        )
+    }
+}
+
+static AV_CHECKED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+static AV_HINT: Lazy<Mutex<Option<AvHint>>> = Lazy::new(|| Mutex::new(None));
+
+// 记录一次缓慢的扫描或删除并检测原因；每次启动只检测一次
+pub(crate) fn report_slow_operation(trigger: String) {
+    if AV_CHECKED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log(&format!("操作异常缓慢，检测杀毒软件干扰: {}", trigger));
+
+    let Some(open_latency) = file_open_latency(&std::env::temp_dir()) else {
+        return;
+    };
+    let products = running_av_products();
+    log(&format!(
+        "文件打开平均耗时 {:?}，运行中的杀毒软件: {}",
+        open_latency,
+        products.join(", ")
+    ));
+    if open_latency >= AV_LATENCY_THRESHOLD {
+        *AV_HINT.lock().unwrap() = Some(AvHint {
+            products,
+            open_latency,
+            trigger,
+        });
+    }
+}
+
+// 未被忽略的杀毒软件提示
+pub(crate) fn current_av_hint() -> Option<AvHint> {
+    let hint = AV_HINT.lock().unwrap().clone()?;
+    let key = hint.key();
+    (!load_acknowledgements().iter().any(|ack| ack.key == key)).then_some(hint)
+}
+
+// 反复打开同一个新文件，实时防护通常会在每次打开时扫描
+fn file_open_latency(dir: &Path) -> Option<Duration> {
+    let probe = dir.join(format!("wincleaner-probe-{}.tmp", std::process::id()));
+    fs::write(&probe, b"wincleaner").ok()?;
+
+    let start = std::time::Instant::now();
+    let mut opened = 0;
+    for _ in 0..AV_PROBE_ROUNDS {
+        if fs::File::open(&probe).is_ok() {
+            opened += 1;
+        }
+    }
+    let elapsed = start.elapsed();
+    let _ = fs::remove_file(&probe);
+
+    (opened > 0).then(|| elapsed / opened)
+}
+
+// 正在运行的进程名（小写）
+pub(crate) fn running_process_names() -> HashSet<String> {
+    let Some(output) = run_query_command("tasklist /fo csv /nh") else {
+        return HashSet::new();
+    };
+    output
+        .lines()
+        .filter_map(|line| line.split(',').next())
+        .map(|name| name.trim_matches('"').to_lowercase())
+        .collect()
+}
+
+// 残留的构建守护进程 - 构建结束后仍在后台运行，占用内存并锁住缓存目录中的文件
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BuildDaemon {
+    Gradle,
+    Kotlin,
+    Adb,
+    Node, // 脚本所在的工作区已被删除
+}
+
+impl BuildDaemon {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            BuildDaemon::Gradle => "Gradle 守护进程",
+            BuildDaemon::Kotlin => "Kotlin 编译守护进程",
+            BuildDaemon::Adb => "adb 服务",
+            BuildDaemon::Node => "Node.js（工作区已删除）",
+        }
+    }
+
+    // 按进程名和命令行识别，普通的 java / node 进程不算
+    pub(crate) fn classify(name: &str, command_line: &str) -> Option<BuildDaemon> {
+        match name.to_lowercase().as_str() {
+            "java.exe" | "javaw.exe" if command_line.contains("org.gradle.launcher.daemon.bootstrap.GradleDaemon") => {
+                Some(BuildDaemon::Gradle)
+            }
+            "java.exe" | "javaw.exe" if command_line.contains("KotlinCompileDaemon") => Some(BuildDaemon::Kotlin),
+            "adb.exe" if command_line.contains("fork-server") => Some(BuildDaemon::Adb),
+            "node.exe" if node_workspace_missing(command_line) => Some(BuildDaemon::Node),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct DaemonProcess {
+    pub(crate) kind: BuildDaemon,
+    pub(crate) pid: u32,
+    pub(crate) command_line: String,
+}
+
+// 按 Windows 规则拆分命令行：空白分隔，双引号内的空白不分隔
+fn split_command_line(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for ch in line.chars() {
+        match ch {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            ' ' | '\t' if !quoted => {
+                if started {
+                    args.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            _ => {
+                current.push(ch);
+                started = true;
+            }
+        }
+    }
+    if started {
+        args.push(current);
+    }
+    args
+}
+
+// 后面跟一个取值参数的 node 选项，如 node -r ts-node/register script.js
+const NODE_VALUE_OPTIONS: &[&str] = &[
+    "-r",
+    "--require",
+    "--import",
+    "--loader",
+    "--experimental-loader",
+    "-C",
+    "--conditions",
+    "--input-type",
+    "--title",
+];
+
+// node 命令行中的脚本：跳过选项及其取值后的第一个参数；-e / -p 执行内联代码，没有脚本
+fn node_script(command_line: &str) -> Option<String> {
+    let args = split_command_line(command_line);
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-e" | "--eval" | "-p" | "--print" => return None,
+            "--" => return rest.next().cloned(),
+            option if NODE_VALUE_OPTIONS.contains(&option) => {
+                rest.next();
+            }
+            option if option.starts_with('-') => {}
+            script => return Some(script.to_string()),
+        }
+    }
+    None
+}
+
+// node 进程的脚本是绝对路径且已不存在，说明其工作区已被删除；
+// 脚本所在的卷不存在时（如移动硬盘未连接）无法判断，不算
+fn node_workspace_missing(command_line: &str) -> bool {
+    node_script(command_line).map(std::path::PathBuf::from).is_some_and(|script| {
+        script.is_absolute() && !script.exists() && script.ancestors().last().is_some_and(Path::exists)
+    })
+}
+
+// 通过 WMI 查询可能是守护进程的进程及其命令行（tasklist 不提供命令行）
+fn find_build_daemons() -> Vec<DaemonProcess> {
+    // 输出改为 UTF-8，否则命令行中的中文路径会乱码，被误判为已不存在
+    const QUERY: &str = "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+        Get-CimInstance Win32_Process -Filter \"Name='java.exe' or Name='javaw.exe' or Name='adb.exe' or Name='node.exe'\" | \
+        Select-Object ProcessId,Name,CommandLine | ConvertTo-Json -Compress";
+
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", QUERY]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    let output = match cmd.output() {
+        Ok(output) => output,
+        Err(e) => {
+            log(&format!("无法查询进程列表: {}", e));
+            return Vec::new();
+        }
+    };
+
+    // 没有匹配的进程时输出为空；只有一个时 ConvertTo-Json 输出对象而不是数组
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(&output.stdout) else {
+        return Vec::new();
+    };
+    let processes = match value {
+        serde_json::Value::Array(processes) => processes,
+        process => vec![process],
+    };
+    processes
+        .iter()
+        .filter_map(|process| {
+            let pid = process["ProcessId"].as_u64()? as u32;
+            let name = process["Name"].as_str()?;
+            let command_line = process["CommandLine"].as_str().unwrap_or_default();
+            Some(DaemonProcess {
+                kind: BuildDaemon::classify(name, command_line)?,
+                pid,
+                command_line: command_line.to_string(),
+            })
+        })
+        .collect()
+}
+
+// 按类型汇总，如 "Gradle 守护进程 ×2、adb 服务"
+fn summarize_daemons(kinds: &[BuildDaemon]) -> String {
+    let mut counts: Vec<(BuildDaemon, usize)> = Vec::new();
+    for kind in kinds {
+        match counts.iter_mut().find(|(known, _)| known == kind) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((*kind, 1)),
+        }
+    }
+    counts
+        .iter()
+        .map(|(kind, count)| {
+            if *count > 1 {
+                format!("{} ×{}", kind.label(), count)
+            } else {
+                kind.label().to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("、")
+}
+
+// 守护进程不响应关闭窗口的请求，直接结束；它们的状态都可以在下次构建时重建
+fn stop_process(pid: u32) -> Result<(), String> {
+    let mut taskkill = Command::new("taskkill");
+    taskkill.args(["/F", "/PID", &pid.to_string()]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        taskkill.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    match taskkill.output() {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn running_av_products() -> Vec<String> {
+    let running = running_process_names();
+    let mut products = Vec::new();
+    for (process, product) in AV_PROCESSES {
+        if running.contains(&process.to_lowercase()) && !products.iter().any(|known| known == product) {
+            products.push(product.to_string());
+        }
+    }
+    products
+}
+
+// 按规则定期执行 - 规则中的 schedule 按频率汇总为计划任务，每个频率一个，以当前用户身份运行
+// 计划任务以 --run-schedule <频率> 启动程序，不显示窗口，依次执行该频率下的全部规则
+pub(crate) const SCHEDULE_ARG: &str = "--run-schedule";
+pub(crate) const SCHEDULE_HOUR: u32 = 3; // 计划任务的执行时间（凌晨 3 点）
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSchedule {
+    Daily,
+    Weekly,  // 每周日
+    Monthly, // 每月 1 日
+}
+
+impl TaskSchedule {
+    pub(crate) const ALL: [TaskSchedule; 3] = [TaskSchedule::Daily, TaskSchedule::Weekly, TaskSchedule::Monthly];
+
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            TaskSchedule::Daily => "daily",
+            TaskSchedule::Weekly => "weekly",
+            TaskSchedule::Monthly => "monthly",
+        }
+    }
+
+    pub(crate) fn parse(key: &str) -> Option<TaskSchedule> {
+        TaskSchedule::ALL.into_iter().find(|schedule| schedule.key() == key)
+    }
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            TaskSchedule::Daily => "每天",
+            TaskSchedule::Weekly => "每周日",
+            TaskSchedule::Monthly => "每月 1 日",
+        }
+    }
+
+    pub(crate) fn job_name(&self) -> String {
+        format!("WinCleaner\\Schedule-{}", self.key())
+    }
+
+    pub(crate) fn schtasks_trigger(&self) -> &'static str {
+        match self {
+            TaskSchedule::Daily => "/sc daily",
+            TaskSchedule::Weekly => "/sc weekly /d SUN",
+            TaskSchedule::Monthly => "/sc monthly /d 1",
+        }
+    }
+
+    // 下一次计划执行的时间
+    pub(crate) fn next_run(&self, now: chrono::NaiveDateTime) -> chrono::NaiveDateTime {
+        use chrono::Datelike;
+
+        let at = |date: chrono::NaiveDate| date.and_hms_opt(SCHEDULE_HOUR, 0, 0).unwrap_or_default();
+        let today = now.date();
+        match self {
+            TaskSchedule::Daily => {
+                let run = at(today);
+                if run > now { run } else { at(today + chrono::Days::new(1)) }
+            }
+            TaskSchedule::Weekly => {
+                let days = (7 - today.weekday().num_days_from_sunday()) % 7;
+                let run = at(today + chrono::Days::new(days as u64));
+                if run > now { run } else { at(today + chrono::Days::new(days as u64 + 7)) }
+            }
+            TaskSchedule::Monthly => {
+                let first = today.with_day(1).unwrap_or(today);
+                let run = at(first);
+                if run > now { run } else { at(first + chrono::Months::new(1)) }
+            }
+        }
+    }
+
+    pub(crate) fn next_run_text(&self) -> String {
+        self.next_run(chrono::Local::now().naive_local())
+            .format("%m-%d %H:%M")
+            .to_string()
+    }
+}
+
+// 按规则中的频率创建或删除对应的计划任务；频率下已没有规则时删除其计划任务
+pub(crate) fn sync_schedule_jobs(tasks: &[CleanTask]) {
+    let exe = std::env::current_exe()
+        .map(|exe| exe.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "wincleaner.exe".to_string());
+    for schedule in TaskSchedule::ALL {
+        let count = tasks.iter().filter(|task| task.schedule == Some(schedule)).count();
+        let exists = run_query_command(&format!("schtasks /query /tn \"{}\"", schedule.job_name())).is_some();
+        if count > 0 && !exists {
+            let command = format!(
+                "schtasks /create /tn \"{}\" /tr \"\\\"{}\\\" {} {}\" {} /st {:02}:00 /f",
+                schedule.job_name(),
+                exe,
+                SCHEDULE_ARG,
+                schedule.key(),
+                schedule.schtasks_trigger(),
+                SCHEDULE_HOUR
+            );
+            match run_query_command(&command) {
+                Some(_) => log(&format!("已创建计划任务: {}（{} 个规则）", schedule.job_name(), count)),
+                None => log(&format!("创建计划任务失败: {}", schedule.job_name())),
+            }
+        } else if count == 0 && exists {
+            let _ = run_query_command(&format!("schtasks /delete /tn \"{}\" /f", schedule.job_name()));
+            log(&format!("已删除计划任务: {}", schedule.job_name()));
+        }
+    }
+}
+
+// 按交互运行或计划运行的设置给出完成提示；声音同步播放，调用方不应在界面线程中等待
+pub(crate) fn completion_feedback(scheduled: bool, success: bool, message: &str) {
+    let mode = if scheduled {
+        SETTINGS.scheduled_completion_feedback
+    } else {
+        SETTINGS.completion_feedback
+    };
+
+    match mode {
+        CompletionFeedback::SystemSound => play_system_sound(success),
+        CompletionFeedback::CustomSound => {
+            let file = expand_environment_variables(&SETTINGS.completion_sound_file);
+            if file.trim().is_empty() || !play_sound_file(&file) {
+                log(&format!("提示音文件无法播放: {}", file));
+                play_system_sound(success);
+            }
+        }
+        CompletionFeedback::Toast => show_toast(message),
+        CompletionFeedback::Silent => {}
+    }
+}
+
+#[cfg(windows)]
+fn play_system_sound(success: bool) {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{MessageBeep, MB_ICONASTERISK, MB_ICONHAND};
+
+    unsafe {
+        MessageBeep(if success { MB_ICONASTERISK } else { MB_ICONHAND });
+    }
+}
+
+#[cfg(not(windows))]
+fn play_system_sound(_success: bool) {}
+
+#[cfg(windows)]
+fn play_sound_file(path: &str) -> bool {
+    use windows_sys::Win32::Media::Audio::{PlaySoundW, SND_FILENAME, SND_NODEFAULT, SND_SYNC};
+
+    if !Path::new(path).is_file() {
+        return false;
+    }
+    let wide_path = to_wide(path);
+    unsafe { PlaySoundW(wide_path.as_ptr(), std::ptr::null_mut(), SND_FILENAME | SND_NODEFAULT | SND_SYNC) != 0 }
+}
+
+#[cfg(not(windows))]
+fn play_sound_file(_path: &str) -> bool {
+    false
+}
+
+// 通过 PowerShell 调用 WinRT 通知接口，借用 PowerShell 的应用标识，无需注册快捷方式
+fn show_toast(message: &str) {
+    const POWERSHELL_APP_ID: &str = "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
+
+    let quote = |text: &str| format!("'{}'", text.replace('\'', "''"));
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+         $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $texts = $xml.GetElementsByTagName('text'); \
+         $texts.Item(0).AppendChild($xml.CreateTextNode('WinCleaner')) > $null; \
+         $texts.Item(1).AppendChild($xml.CreateTextNode({})) > $null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier({}).Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        quote(message),
+        quote(POWERSHELL_APP_ID)
+    );
+
+    let mut cmd = Command::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    if let Err(e) = cmd.output() {
+        log(&format!("通知显示失败: {}", e));
+    }
+}
+
+// 清理结束时的提示文字
+pub(crate) fn completion_message(state: &AppState) -> Option<(bool, String)> {
+    match state {
+        AppState::Success => Some((true, "清理完成".to_string())),
+        AppState::SuccessWithStats(stats) => {
+            let mut message = format!("清理完成：成功 {}/{}", stats.successful_tasks, stats.total_tasks);
+            if stats.skipped_tasks > 0 {
+                message.push_str(&format!("，跳过 {}", stats.skipped_tasks));
+            }
+            if let Some(bytes) = stats.total_space_freed {
+                message.push_str(&format!("，释放 {}", format_size(bytes)));
+            }
+            Some((stats.failed_tasks == 0, message))
+        }
+        AppState::Cancelled(stats) => Some((
+            true,
+            format!(
+                "清理已取消：完成 {}/{}，释放 {}",
+                stats.successful_tasks,
+                stats.total_tasks,
+                format_size(stats.total_space_freed.unwrap_or(0))
+            ),
+        )),
+        AppState::Error(e) => Some((false, format!("清理失败：{}", e))),
+        AppState::Idle | AppState::Running(_) => None,
+    }
+}
+
+// 计划任务入口：执行该频率下可无人值守运行的规则，结束后按 scheduled_completion_feedback 提示
+// 定期清理中跳过任务的原因：逐项选择的规则和未提升权限时的管理员规则无法无人值守执行；
+// 需要确认的任务（包括全部中高风险任务）不静默执行
+fn scheduled_skip_reason(task: &CleanTask, elevated: bool) -> Option<&'static str> {
+    if task.is_item_only() {
+        Some("只能逐项选择清理")
+    } else if task.requires_admin && !elevated {
+        Some("需要管理员权限")
+    } else if task.needs_confirmation() {
+        Some("需要确认")
+    } else {
+        None
+    }
+}
+
+pub(crate) fn run_scheduled_tasks(schedule: TaskSchedule) {
+    enter_program_dir();
+    log(&format!("定期清理开始: {}", schedule.label()));
+
+    let scheduled = order_by_dependencies(
+        merge_tasks(load_user_config())
+            .into_iter()
+            .filter(|task| task.schedule == Some(schedule))
+            .collect(),
+    );
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            log(&format!("定期清理无法启动: {}", e));
+            return;
+        }
+    };
+
+    let (mut succeeded, mut failed, mut freed) = (0, 0, 0u64);
+    begin_quarantine_run();
+    runtime.block_on(async {
+        for task in &scheduled {
+            if let Some(reason) = scheduled_skip_reason(task, *IS_ELEVATED) {
+                log(&format!("定期清理跳过: {}（{}）", task.name, reason));
+                continue;
+            }
+            let (result, bytes_freed) = run_recorded_task(task, &CancelToken::default()).await;
+            match result {
+                Ok(()) => succeeded += 1,
+                Err(e) if task.skipped_by(&e) => log(&format!("定期清理无需执行: {}", task.name)),
+                Err(e) => {
+                    failed += 1;
+                    log(&format!("定期清理失败: {} - {}", task.name, e));
+                }
+            }
+            freed += bytes_freed.unwrap_or(0);
+        }
+    });
+
+    log(&format!(
+        "定期清理完成: 成功 {}，失败 {}，释放 {}",
+        succeeded,
+        failed,
+        format_size(freed)
+    ));
+    completion_feedback(
+        true,
+        failed == 0,
+        &format!("定期清理（{}）完成，释放 {}", schedule.label(), format_size(freed)),
+    );
+}
+
+// 计划建议 - 最近经常手动清理的任务，建议改为每周自动执行
+const SCHEDULE_OFFER_WINDOW: i64 = 30 * 86400; // 统计的时间范围（秒）
+const SCHEDULE_OFFER_MIN_RUNS: usize = 3; // 范围内至少成功执行的次数
+pub(crate) const SCHEDULE_OFFER_KEY: &str = "schedule:每周计划建议"; // 在已忽略列表中的标识
+
+// 计划通过 [[override]] 写入，只适用于内置任务
+static BUILTIN_TASK_IDS: Lazy<HashSet<String>> =
+    Lazy::new(|| builtin_tasks().into_iter().map(|task| task.id).collect());
+
+// 可以无人值守执行、尚未设置计划且最近经常手动清理的内置任务，按执行次数降序
+pub(crate) fn recommended_schedule_tasks(tasks: &[CleanTask]) -> Vec<CleanTask> {
+    if *AUDIT_MODE || *KIOSK_MODE || load_acknowledgements().iter().any(|ack| ack.key == SCHEDULE_OFFER_KEY) {
+        return Vec::new();
+    }
+
+    let since = chrono::Local::now().timestamp() - SCHEDULE_OFFER_WINDOW;
+    let mut runs = HashMap::<String, usize>::new();
+    for entry in HISTORY.lock().unwrap().iter() {
+        if entry.finished_at >= since && entry.success && entry.skipped.is_none() {
+            *runs.entry(entry.task_id.clone()).or_default() += 1;
+        }
+    }
+
+    let mut recommended = tasks
+        .iter()
+        .filter(|task| {
+            task.schedule.is_none()
+                && BUILTIN_TASK_IDS.contains(&task.id)
+                && runs.get(&task.id).is_some_and(|&count| count >= SCHEDULE_OFFER_MIN_RUNS)
+                // 与定期清理相同的跳过条件，另外排除需要先关闭程序的任务
+                && scheduled_skip_reason(task, *IS_ELEVATED).is_none()
+                && task.requires_closed.is_empty()
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    recommended.sort_by_key(|task| std::cmp::Reverse(runs[&task.id]));
+    recommended
+}
+
+// 为任务设置计划：以 [[override]] 追加到配置文件，保留用户原有的内容和注释
+pub(crate) fn append_schedule_overrides(task_ids: &[String], schedule: TaskSchedule) -> Result<(), String> {
+    use std::io::Write;
+
+    if *AUDIT_MODE {
+        return Err(AUDIT_BLOCKED.to_string());
+    }
+
+    let overrides = task_ids
+        .iter()
+        .map(|id| {
+            let mut table = toml::Table::new();
+            table.insert("target".to_string(), toml::Value::String(id.clone()));
+            table.insert("schedule".to_string(), toml::Value::String(schedule.key().to_string()));
+            toml::Value::Table(table)
+        })
+        .collect();
+    let mut document = toml::Table::new();
+    document.insert("override".to_string(), toml::Value::Array(overrides));
+    let text = toml::to_string(&document).map_err(|e| format!("计划序列化失败: {}", e))?;
+
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(CONFIG_FILE)
+        .and_then(|mut file| write!(file, "\n# 按使用习惯创建的{}计划\n{}", schedule.label(), text))
+        .map_err(|e| format!("配置文件写入失败: {}", e))?;
+    log(&format!("设置计划 {}: {}", schedule.key(), task_ids.join(", ")));
+    record_feature("按使用习惯创建计划");
+    Ok(())
+}
+
+// 开机清理 - 注册一次性的开机计划任务（SYSTEM 身份），在用户程序启动前清理运行时被占用的临时目录
+const BOOT_CLEAN_TASK_NAME: &str = "WinCleaner\\BootClean";
+pub(crate) const BOOT_CLEAN_ARG: &str = "--boot-clean";
+const BOOT_CLEAN_REPORT_FILE: &str = "wincleaner-boot-report.json";
+const BOOT_CLEAN_MAX_FAILURES: usize = 10; // 报告中保留的失败明细条数
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BootCleanReport {
+    pub(crate) finished_at: i64, // Unix时间戳（秒）
+    pub(crate) deleted: usize,
+    pub(crate) failed: usize,
+    pub(crate) freed: u64,
+    pub(crate) failures: Vec<String>,
+}
+
+impl BootCleanReport {
+    // 转换为清理结果，在下次启动界面时显示
+    pub(crate) fn stats(&self) -> CleanupStats {
+        CleanupStats {
+            total_tasks: self.deleted + self.failed,
+            successful_tasks: self.deleted,
+            failed_tasks: self.failed,
+            skipped_tasks: 0,
+            total_space_freed: Some(self.freed),
+            errors: self
+                .failures
+                .iter()
+                .map(|failure| ("boot-clean".to_string(), failure.clone()))
+                .collect(),
+            skipped: Vec::new(),
+            volume_freed: Vec::new(),
+        }
+    }
+}
+
+// 注册开机任务的命令；计划任务运行后会删除自身
+pub(crate) fn boot_clean_command() -> String {
+    let exe = std::env::current_exe()
+        .map(|exe| exe.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "wincleaner.exe".to_string());
+    format!(
+        "schtasks /create /tn \"{}\" /tr \"\\\"{}\\\" {}\" /sc onstart /ru SYSTEM /rl highest /f",
+        BOOT_CLEAN_TASK_NAME, exe, BOOT_CLEAN_ARG
+    )
+}
+
+// Windows\Temp 和每个用户配置文件下的临时目录
+fn boot_clean_targets() -> Vec<String> {
+    let mut targets = vec![expand_environment_variables("%WINDIR%\\Temp")];
+    let users = format!("{}\\Users", expand_environment_variables("%SYSTEMDRIVE%"));
+    if let Ok(entries) = fs::read_dir(users) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let temp = entry.path().join("AppData\\Local\\Temp");
+            if temp.is_dir() {
+                targets.push(temp.to_string_lossy().to_string());
+            }
+        }
+    }
+    targets
+}
+
+// 计划任务的工作目录是 System32，切换到程序目录以便读取配置、写入日志和报告
+fn enter_program_dir() {
+    if let Some(dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        let _ = std::env::set_current_dir(dir);
+    }
+}
+
+// 开机任务入口：逐项删除临时目录中的内容，写入报告后删除计划任务
+pub(crate) fn run_boot_clean() {
+    enter_program_dir();
+    log("开机清理开始");
+
+    let mut report = BootCleanReport::default();
+    for target in boot_clean_targets() {
+        for item in list_target_items(&target, None) {
+            match delete_target_item(&item, DeleteMode::Permanent) {
+                Ok(bytes) => {
+                    report.deleted += 1;
+                    report.freed += bytes;
+                }
+                Err(e) => {
+                    report.failed += 1;
+                    if report.failures.len() < BOOT_CLEAN_MAX_FAILURES {
+                        report.failures.push(e);
+                    }
+                }
+            }
+        }
+    }
+    report.finished_at = chrono::Local::now().timestamp();
+
+    match serde_json::to_string(&report) {
+        Ok(content) => {
+            if let Err(e) = fs::write(BOOT_CLEAN_REPORT_FILE, content) {
+                log(&format!("开机清理报告写入失败: {}", e));
+            }
+        }
+        Err(e) => log(&format!("开机清理报告序列化失败: {}", e)),
+    }
+    log(&format!(
+        "开机清理完成: 删除 {} 项，失败 {} 项，释放 {}",
+        report.deleted,
+        report.failed,
+        format_size(report.freed)
+    ));
+
+    let _ = run_query_command(&format!("schtasks /delete /tn \"{}\" /f", BOOT_CLEAN_TASK_NAME));
+    completion_feedback(
+        true,
+        report.failed == 0,
+        &format!("开机清理完成，释放 {}", format_size(report.freed)),
+    );
+}
+
+// 读取并移除上次开机清理的报告，同时写入清理历史
+pub(crate) fn take_boot_clean_report() -> Option<BootCleanReport> {
+    let content = fs::read_to_string(BOOT_CLEAN_REPORT_FILE).ok()?;
+    let _ = fs::remove_file(BOOT_CLEAN_REPORT_FILE);
+    let report = serde_json::from_str::<BootCleanReport>(&content).ok()?;
+
+    append_history(HistoryEntry {
+        task_id: "boot-clean".to_string(),
+        task_name: format!("开机清理（释放 {}）", format_size(report.freed)),
+        command: None,
+        started_at: None,
+        finished_at: report.finished_at,
+        success: report.failed == 0,
+        error: (report.failed > 0).then(|| format!("{} 项删除失败", report.failed)),
+        before: None,
+        after: None,
+        duration_ms: None,
+        skipped: None,
+        log_file: None,
+        freed: Some(report.freed),
+        environment: None,
+    });
+    Some(report)
+}
+
+// 基准测试 - 隐藏的 --bench <路径> 模式，在指定位置生成测试目录树，测量扫描和删除吞吐量
+// 用于发布前检查性能回退，以及排查网络驱动器、杀毒软件等特殊环境下的慢速问题
+pub(crate) const BENCH_ARG: &str = "--bench";
+const BENCH_DIRS: usize = 50;
+const BENCH_FILES_PER_DIR: usize = 200;
+const BENCH_FILE_SIZE: usize = 4096;
+const BENCH_REPORT_FILE: &str = "wincleaner-bench.txt";
+
+fn run_benchmark(base: &str) -> Result<String, String> {
+    use std::time::Instant;
+
+    let base = expand_environment_variables(base);
+    let root = Path::new(&base).join(format!("wincleaner-bench-{}", chrono::Local::now().timestamp()));
+    // 测试树放在下一级目录，基准路径为驱动器根目录时也能通过删除前的层级检查
+    let tree = root.join("tree");
+    let tree_text = tree.to_string_lossy().to_string();
+    let file_count = BENCH_DIRS * BENCH_FILES_PER_DIR;
+    let total_bytes = (file_count * BENCH_FILE_SIZE) as u64;
+    let rate = |count: f64, elapsed: Duration| count / elapsed.as_secs_f64().max(0.001);
+
+    let start = Instant::now();
+    let payload = vec![0x5a_u8; BENCH_FILE_SIZE];
+    for dir in 0..BENCH_DIRS {
+        let dir_path = tree.join(format!("dir{:03}", dir));
+        fs::create_dir_all(&dir_path).map_err(|e| format!("无法创建测试目录 {}: {}", dir_path.display(), e))?;
+        for file in 0..BENCH_FILES_PER_DIR {
+            fs::write(dir_path.join(format!("file{:04}.bin", file)), &payload)
+                .map_err(|e| format!("无法写入测试文件: {}", e))?;
+        }
+    }
+    let generate = start.elapsed();
+
+    let start = Instant::now();
+    let scanned = scan_directory_size(&tree_text, &AtomicU64::new(0));
+    let scan = start.elapsed();
+
+    // 删除与清理任务走同一路径：执行前检查 + cmd 命令
+    let task = rule_task(
+        &tree_text,
+        "基准测试",
+        DeleteStrategy::RemoveFolder,
+        RiskLevel::Low,
+        "⏱",
+        CleanCategory::Custom,
+    );
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| format!("无法创建运行时: {}", e))?;
+    let start = Instant::now();
+    let deleted = runtime.block_on(run_clean_task_impl(task, CancelToken::default(), TaskLog::default()));
+    let delete = start.elapsed();
+    let leftover = tree.exists();
+    let _ = fs::remove_dir_all(&root);
+
+    let tuning = scan_tuning(&tree_text);
+    let drive = volume_root(&tree_text).map_or(DriveKind::Unknown, |root| drive_kind(&root));
+    let mut report = vec![
+        format!("WinCleaner 基准测试 {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")),
+        format!("路径: {}", root.display()),
+        format!(
+            "磁盘类型: {:?}，扫描并发 {}，批大小 {}，暂停 {} ms",
+            drive,
+            tuning.max_workers,
+            tuning.batch_size,
+            tuning.pause.as_millis()
+        ),
+        format!("测试树: {} 个目录，{} 个文件，{}", BENCH_DIRS, file_count, format_size(total_bytes)),
+        format!(
+            "生成: {:.2} 秒，{:.0} 文件/秒",
+            generate.as_secs_f64(),
+            rate(file_count as f64, generate)
+        ),
+        format!(
+            "扫描: {:.2} 秒，{:.0} 文件/秒，{:.1} MB/秒",
+            scan.as_secs_f64(),
+            rate(file_count as f64, scan),
+            rate(total_bytes as f64 / 1048576.0, scan)
+        ),
+    ];
+    if scanned != Some(total_bytes) {
+        report.push(format!("⚠️ 扫描结果 {:?} 与生成的 {} 字节不一致", scanned, total_bytes));
+    }
+    match deleted {
+        Ok(()) if !leftover => report.push(format!(
+            "删除: {:.2} 秒，{:.0} 文件/秒",
+            delete.as_secs_f64(),
+            rate(file_count as f64, delete)
+        )),
+        Ok(()) => report.push(format!("⚠️ 删除命令完成但目录仍存在（{:.2} 秒）", delete.as_secs_f64())),
+        Err(e) => report.push(format!("⚠️ 删除失败: {}", e.replace('\n', " "))),
+    }
+
+    Ok(report.join("\n"))
+}
+
+// 命令行入口：结果输出到控制台并写入报告文件（双击运行时没有控制台）
+pub(crate) fn run_benchmark_cli(base: &str) {
+    attach_parent_console();
+    let report = run_benchmark(base).unwrap_or_else(|e| format!("基准测试失败: {}", e));
+    println!("{}", report);
+    if let Err(e) = fs::write(BENCH_REPORT_FILE, &report) {
+        eprintln!("报告写入失败: {}", e);
+    }
+    log(&format!("基准测试完成:\n{}", report));
+}
+
+// 发布版为窗口程序，从终端启动时连接到父进程的控制台以便输出
+#[cfg(windows)]
+pub(crate) fn attach_parent_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+pub(crate) fn attach_parent_console() {}
+
+// 预扫描单个任务的大小，等待后台目录扫描结束后返回
+pub(crate) async fn prescan_size(task: CleanTask) {
+    let Some(key) = task.size_cache_key() else {
+        return;
+    };
+
+    let _ = tokio::task::spawn_blocking(move || task.get_size_entry()).await;
+    while is_scan_running(&key) {
+        tokio::time::sleep(SCAN_POLL_INTERVAL).await;
+    }
+}
+
+// 决定是否需要确认前，先扫描尚无缓存大小（或仍在扫描中）的任务，
+// large_delete_size 和 needs_confirmation 只读取已缓存的大小
+pub(crate) async fn prescan_uncached(tasks: &[CleanTask]) {
+    for task in tasks {
+        let uncached = task
+            .size_cache_key()
+            .is_some_and(|key| peek_size_bytes(&key).is_none() || is_scan_running(&key));
+        if uncached {
+            prescan_size(task.clone()).await;
+        }
+    }
+}
+
+// 只清理目标目录下勾选的子项
+pub(crate) async fn run_item_cleanup(
+    task: CleanTask,
+    items: Vec<TargetItem>,
+    take_ownership: bool,
+    mut app_state: impl RunHost,
+) {
+    if !begin_run() {
+        reject_concurrent_run("逐项清理");
+        return;
+    }
+    record_feature(if take_ownership { "逐项清理（接管所有权）" } else { "逐项清理" });
+    log(&format!(
+        "逐项清理: {} - {} 项{}",
+        task.name,
+        items.len(),
+        if take_ownership { "（接管所有权）" } else { "" }
+    ));
+    app_state.store(AppState::Running(format!("正在清理 {} 项: {}", items.len(), task.name)));
+
+    begin_quarantine_run();
+    let cancel = begin_cancellable_run();
+    let item_cancel = cancel.clone();
+    let total = items.len();
+    let mode = task.delete_mode();
+    let analyzer = task.item_analyzer;
+    let results = tokio::task::spawn_blocking(move || {
+        items
+            .iter()
+            .map(|item| {
+                if item_cancel.is_cancelled() {
+                    return Err(CANCELLED.to_string());
+                }
+                if analyzer == Some(ItemAnalyzer::RecycleBins) {
+                    return empty_recycle_bin_item(item);
+                }
+                if analyzer == Some(ItemAnalyzer::AlternateStreams) {
+                    return remove_stream_item(item);
+                }
+                if take_ownership {
+                    take_ownership_recursive(&item.path)?;
+                }
+                delete_target_item(item, mode)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+    task.invalidate_size();
+
+    let mut stats = CleanupStats {
+        total_tasks: total,
+        successful_tasks: 0,
+        failed_tasks: 0,
+        skipped_tasks: 0,
+        total_space_freed: None,
+        errors: Vec::new(),
+        skipped: Vec::new(),
+        volume_freed: Vec::new(),
+    };
+    let mut freed = 0;
+    for result in results {
+        match result {
+            Ok(bytes) => {
+                stats.successful_tasks += 1;
+                freed += bytes;
+            }
+            Err(e) if e == CANCELLED => stats.skipped_tasks += 1,
+            Err(e) => {
+                log(&format!("逐项清理失败: {}", e));
+                stats.failed_tasks += 1;
+                stats.errors.push((task.id.clone(), e));
+            }
+        }
+    }
+    stats.total_space_freed = if freed > 0 { Some(freed) } else { None };
+
+    if cancel.is_cancelled() {
+        log(&format!("逐项清理已取消: {}", task.name));
+        app_state.store(AppState::Cancelled(stats));
+    } else {
+        app_state.store(AppState::SuccessWithStats(stats));
+    }
+    end_run(app_state);
+}
+
+// 路径不存在或目录为空时错误信息的结尾，用于识别"无需清理"
+pub(crate) const NOTHING_TO_CLEAN: &str = "无需清理，跳过此任务";
+
+// 跳过原因只取错误信息的第一行，如 "清理路径不存在: …"
+fn skip_reason(error: &str) -> String {
+    error.lines().next().unwrap_or(error).trim().to_string()
+}
+
+// 执行前检查：变量、路径、禁区和工作目录，通过时返回展开后的命令
+// 清理执行与模拟运行共用，保证模拟结果与实际执行一致
+fn preflight_task(task: &CleanTask) -> Result<String, String> {
+    log(&format!("检查任务: {} - 命令: {}", task.name, task.command));
+
+    if task.is_item_only() {
+        return Err("此任务没有整体清理命令，请通过\"选择…\"逐项清理".to_string());
+    }
+
+    // 程序运行时缓存文件被占用，删除一半会让程序状态错乱
+    let blockers = task.running_blockers();
+    if !blockers.is_empty() {
+        let msg = format!("{} 正在运行，缓存文件被占用\n请先退出该程序后重试", blockers.join("、"));
+        log(&format!("程序运行中: {} - {}", task.name, msg));
+        return Err(msg);
+    }
+
+    // 环境变量为空或无法解析时，命令的目标会悄悄改变，直接拒绝
+    let mut unresolved = find_unresolved_variables(&task.command);
+    if let Some(path_check) = &task.path_check {
+        unresolved.extend(find_unresolved_variables(path_check));
+    }
+    if let Some(working_dir) = &task.working_dir {
+        unresolved.extend(find_unresolved_variables(working_dir));
+    }
+    for value in task.env.values() {
+        unresolved.extend(find_unresolved_variables(value));
+    }
+    for pattern in &task.patterns {
+        unresolved.extend(find_unresolved_variables(pattern));
+    }
+    if !unresolved.is_empty() {
+        let msg = format!(
+            "环境变量未定义或为空: {}\n出于安全考虑，此操作被拒绝",
+            unresolved.join(", ")
+        );
+        log(&format!("安全拦截: {}", msg));
+        return Err(msg);
+    }
+
+    // 按文件年龄或大小筛选只能由内置删除引擎在遍历时执行
+    let filter = task.file_filter()?;
+    if filter.is_active() && task.patterns.is_empty() {
+        let target = delete_command_target(&expand_environment_variables(&task.command));
+        if task.browser.is_some() || task.stop_daemons || target.is_none() {
+            return Err("older_than_days 和 min_file_size 只适用于 patterns 或 rmdir /s /q <目录>、清空目录的删除命令".to_string());
+        }
+        if task.delete_mode() == DeleteMode::RecycleBin {
+            return Err("按文件年龄或大小筛选时不支持移入回收站，请将 delete_mode 设为 \"Permanent\"".to_string());
+        }
+    }
+
+    // 浏览器缓存任务没有命令，检查各配置文件中的缓存目录
+    if let Some(browser) = task.browser {
+        let dirs = browser.cache_dirs();
+        if dirs.is_empty() {
+            let msg = format!("未找到 {} 的缓存目录\n{}", browser.label(), NOTHING_TO_CLEAN);
+            log(&format!("路径检查失败: {}", msg));
+            return Err(msg);
+        }
+        for dir in &dirs {
+            if let Err(msg) = check_target_sanity(dir) {
+                log(&format!("安全拦截: {}", msg));
+                return Err(msg);
+            }
+        }
+        return Ok(format!(
+            "清空 {} 个配置文件中的 {} 个缓存目录",
+            browser.profiles().len(),
+            dirs.len()
+        ));
+    }
+    
+    // 通配符任务没有命令，检查各模式的起点目录并统计匹配的文件
+    if !task.patterns.is_empty() {
+        for pattern in &task.patterns {
+            let base = glob::pattern_base(&expand_environment_variables(pattern));
+            if !base.is_absolute() {
+                let msg = format!("通配符模式必须是绝对路径: {}\n出于安全考虑，此操作被拒绝", pattern);
+                log(&format!("安全拦截: {}", msg));
+                return Err(msg);
+            }
+            if base.exists() {
+                if let Err(msg) = check_target_sanity(&base) {
+                    log(&format!("安全拦截: {}", msg));
+                    return Err(msg);
+                }
+            }
+        }
+        let matches = task.pattern_matches(&AtomicBool::new(false));
+        let count = matches.iter().map(|matched| matched.files.len()).sum::<usize>();
+        if count == 0 {
+            let msg = format!("没有与模式匹配的文件\n{}", NOTHING_TO_CLEAN);
+            log(&format!("路径检查失败: {}", msg));
+            return Err(msg);
+        }
+        return Ok(format!("删除 {} 个模式匹配的 {} 个文件", matches.len(), count));
+    }
+
+    // 守护进程任务没有命令，查询进程较慢，在执行时再查找
+    if task.stop_daemons {
+        return Ok("停止残留的构建守护进程".to_string());
+    }
+
+    // 检查路径是否存在（如果有路径检查）
+    if let Some(path_check) = &task.path_check {
+        let expanded_path = expand_environment_variables(path_check);
+        let path = Path::new(&expanded_path);
+
+        if !path.exists() {
+            let msg = format!("清理路径不存在: {}\n{}", expanded_path, NOTHING_TO_CLEAN);
+            log(&format!("路径检查失败: {}", msg));
+            return Err(msg);
+        }
+
+        if path.is_dir() {
+            // 检查目录是否为空
+            if let Ok(entries) = fs::read_dir(path) {
+                let entry_count = entries.count();
+                if entry_count == 0 {
+                    let msg = format!("目录为空: {}\n{}", expanded_path, NOTHING_TO_CLEAN);
+                    log(&format!("目录为空: {}", msg));
+                    return Err(msg);
+                }
+            }
+        }
+        
+        if let Err(msg) = check_target_sanity(path) {
+            log(&format!("安全拦截: {}", msg));
+            return Err(msg);
+        }
+        
+        log(&format!("路径检查通过: {}", expanded_path));
+    }
+
+    let expanded_command = expand_environment_variables(&task.command);
+
+    // 检查命令中的每个路径参数是否触及内置禁区
+    for argument in command_path_arguments(&expanded_command) {
+        if let Some(rule) = never_touch_rule(Path::new(&argument)) {
+            let msg = format!(
+                "命令涉及系统关键目录: {}（内置禁区 {}）\n出于安全考虑，此操作被拒绝",
+                argument, rule
+            );
+            log(&format!("内置禁区拦截: {} - 任务 {}", msg, task.id));
+            return Err(msg);
+        }
+    }
+
+    if let Some(working_dir) = &task.working_dir {
+        let working_dir = expand_environment_variables(working_dir);
+        if !Path::new(&working_dir).is_dir() {
+            let msg = format!("工作目录不存在: {}", working_dir);
+            log(&format!("路径检查失败: {}", msg));
+            return Err(msg);
+        }
+    }
+
+    Ok(expanded_command)
+}
+
+// 取消 - "取消"按钮置位当前清理的令牌：正在执行的命令连同其子进程被终止，
+// 内置删除引擎在下一个条目前停止，批量和逐项清理不再开始新的任务
+const CANCELLED: &str = "已取消";
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// 清理队列 - 单项清理按提交顺序排队，同时执行的数量由 max_concurrent_tasks 决定；
+// 批量和逐项清理独占运行，有清理在执行时被拒绝，其间提交的单项清理等它结束后再执行
+#[derive(Default)]
+struct RunQueue {
+    // 批量或逐项清理正在运行
+    pub(crate) exclusive: bool,
+    // 从队列中取任务执行的执行器数量
+    pub(crate) workers: usize,
+    // 正在执行的单项清理任务
+    pub(crate) running: Vec<CleanTask>,
+    pub(crate) pending: VecDeque<CleanTask>,
+    // 最近完成的单项清理，最新的在前
+    pub(crate) finished: VecDeque<FinishedRun>,
+}
+
+static RUN_QUEUE: Lazy<Mutex<RunQueue>> = Lazy::new(|| Mutex::new(RunQueue::default()));
+
+const MAX_CONCURRENT_TASKS: usize = 4;
+// 队列面板保留的已完成任务数
+const FINISHED_RUNS_LIMIT: usize = 20;
+
+fn max_concurrent_runs() -> usize {
+    SETTINGS.max_concurrent_tasks.clamp(1, MAX_CONCURRENT_TASKS)
+}
+
+// 单项清理的结果，显示在队列面板中
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum RunOutcome {
+    Succeeded,
+    Skipped,
+    Failed,
+    Cancelled,
+}
+
+impl RunOutcome {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            RunOutcome::Succeeded => "✓ 成功",
+            RunOutcome::Skipped => "⏭ 已跳过",
+            RunOutcome::Failed => "✗ 失败",
+            RunOutcome::Cancelled => "已取消",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct FinishedRun {
+    pub(crate) task_name: String,
+    pub(crate) outcome: RunOutcome,
+    pub(crate) finished_at: i64,
+}
+
+impl FinishedRun {
+    pub(crate) fn time_text(&self) -> String {
+        chrono::DateTime::from_timestamp(self.finished_at, 0)
+            .map(|time| time.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+            .unwrap_or_default()
+    }
+}
+
+// 开始一次批量或逐项清理；已有清理在运行或排队时返回 false
+fn begin_run() -> bool {
+    let mut queue = RUN_QUEUE.lock().unwrap();
+    if queue.exclusive || queue.workers > 0 {
+        return false;
+    }
+    queue.exclusive = true;
+    // 结果通知只对应最近一次清理，上次批量清理的报告不再提供导出
+    *LAST_BATCH_REPORT.lock().unwrap() = None;
+    true
+}
+
+// 批量或逐项清理结束，开始执行期间排队的单项清理
+fn end_run(app_state: impl RunHost) {
+    RUN_QUEUE.lock().unwrap().exclusive = false;
+    start_run_workers(app_state);
+}
+
+// 提交单项清理：加入队列末尾，有空闲的执行位置时立即开始。
+// 正在执行或已在排队的任务不重复排队
+pub(crate) fn submit_clean_task(task: CleanTask, mut app_state: impl RunHost) {
+    {
+        let mut queue = RUN_QUEUE.lock().unwrap();
+        if queue.running.iter().any(|running| running.id == task.id)
+            || queue.pending.iter().any(|queued| queued.id == task.id)
+        {
+            return;
+        }
+        if queue.exclusive || queue.running.len() >= max_concurrent_runs() {
+            log(&format!("已加入清理队列: {}（排队 {} 个）", task.name, queue.pending.len() + 1));
+        }
+        queue.pending.push_back(task);
+    }
+    start_run_workers(app_state);
+    // 重新写入当前状态，刷新任务卡片和状态栏中的排队状态
+    let state = app_state.load();
+    app_state.store(state);
+}
+
+// 按并发上限补足执行器，每个执行器依次取出排队的任务执行，队列为空时结束。
+// 执行器运行在根作用域中，关闭弹窗或切换分类不会中断正在执行的清理
+fn start_run_workers(app_state: impl RunHost) {
+    let idle = {
+        let mut queue = RUN_QUEUE.lock().unwrap();
+        if queue.exclusive {
+            return;
+        }
+        let idle = max_concurrent_runs()
+            .saturating_sub(queue.workers)
+            .min(queue.pending.len());
+        queue.workers += idle;
+        idle
+    };
+    for _ in 0..idle {
+        app_state.spawn_worker(run_worker(app_state));
+    }
+}
+
+async fn run_worker(mut app_state: impl RunHost) {
+    while let Some(task) = next_queued_run() {
+        let outcome = execute_single_task(task.clone(), app_state).await;
+        let still_running = finish_run(&task, outcome);
+        // 其他任务仍在执行时状态栏继续显示执行中，各任务的结果见清理队列
+        if !still_running.is_empty() {
+            app_state.store(AppState::Running(format!("正在执行: {}", still_running.join("、"))));
+        }
+    }
+}
+
+// 取出下一个排队的任务并记为正在执行；队列为空时结束当前执行器
+fn next_queued_run() -> Option<CleanTask> {
+    let mut queue = RUN_QUEUE.lock().unwrap();
+    match queue.pending.pop_front() {
+        Some(task) => {
+            queue.running.push(task.clone());
+            *LAST_BATCH_REPORT.lock().unwrap() = None;
+            Some(task)
+        }
+        None => {
+            queue.workers -= 1;
+            None
+        }
+    }
+}
+
+// 记录完成的任务，返回仍在执行的任务名称
+fn finish_run(task: &CleanTask, outcome: RunOutcome) -> Vec<String> {
+    let mut queue = RUN_QUEUE.lock().unwrap();
+    queue.running.retain(|running| running.id != task.id);
+    queue.finished.push_front(FinishedRun {
+        task_name: task.name.clone(),
+        outcome,
+        finished_at: chrono::Local::now().timestamp(),
+    });
+    queue.finished.truncate(FINISHED_RUNS_LIMIT);
+    queue.running.iter().map(|running| running.name.clone()).collect()
+}
+
+// 正在执行的单项清理任务名称
+fn running_task_names() -> Vec<String> {
+    RUN_QUEUE
+        .lock()
+        .unwrap()
+        .running
+        .iter()
+        .map(|task| task.name.clone())
+        .collect()
+}
+
+// 单项清理任务在队列中的状态
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum QueueSlot {
+    Idle,
+    Running,
+    Queued,
+}
+
+pub(crate) fn queue_slot(task_id: &str) -> QueueSlot {
+    let queue = RUN_QUEUE.lock().unwrap();
+    if queue.running.iter().any(|task| task.id == task_id) {
+        QueueSlot::Running
+    } else if queue.pending.iter().any(|task| task.id == task_id) {
+        QueueSlot::Queued
+    } else {
+        QueueSlot::Idle
+    }
+}
+
+pub(crate) fn queued_run_count() -> usize {
+    RUN_QUEUE.lock().unwrap().pending.len()
+}
+
+// 没有空闲的执行位置，新提交的单项清理需要排队
+pub(crate) fn run_slots_full() -> bool {
+    let queue = RUN_QUEUE.lock().unwrap();
+    queue.exclusive || queue.running.len() + queue.pending.len() >= max_concurrent_runs()
+}
+
+// 队列面板显示的内容：(执行中, 排队中, 最近完成)
+pub(crate) fn run_queue_snapshot() -> (Vec<CleanTask>, Vec<CleanTask>, Vec<FinishedRun>) {
+    let queue = RUN_QUEUE.lock().unwrap();
+    (
+        queue.running.clone(),
+        queue.pending.iter().cloned().collect(),
+        queue.finished.iter().cloned().collect(),
+    )
+}
+
+// 将排队的任务前移（offset 为负）或后移
+pub(crate) fn move_queued_run(task_id: &str, offset: isize) {
+    let mut queue = RUN_QUEUE.lock().unwrap();
+    let Some(index) = queue.pending.iter().position(|task| task.id == task_id) else {
+        return;
+    };
+    let target = index.saturating_add_signed(offset).min(queue.pending.len() - 1);
+    if let Some(task) = queue.pending.remove(index) {
+        queue.pending.insert(target, task);
+    }
+}
+
+pub(crate) fn remove_queued_run(task_id: &str) {
+    let mut queue = RUN_QUEUE.lock().unwrap();
+    if let Some(index) = queue.pending.iter().position(|task| task.id == task_id) {
+        if let Some(task) = queue.pending.remove(index) {
+            log(&format!("已从清理队列移除: {}", task.name));
+        }
+    }
+}
+
+pub(crate) fn clear_finished_runs() {
+    RUN_QUEUE.lock().unwrap().finished.clear();
+}
+
+// 批量或逐项清理在运行中被拒绝
+fn reject_concurrent_run(what: &str) {
+    let message = format!("已有清理正在进行，{}未开始，请等待完成后再试", what);
+    log(&message);
+    std::thread::spawn(move || show_toast(&message));
+}
+
+// 当前单项、批量或逐项清理的令牌
+static RUN_CANCEL: Lazy<Mutex<CancelToken>> = Lazy::new(|| Mutex::new(CancelToken::default()));
+
+// 清理开始时换用新的令牌，返回给清理引擎
+fn begin_cancellable_run() -> CancelToken {
+    let token = CancelToken::default();
+    *RUN_CANCEL.lock().unwrap() = token.clone();
+    token
+}
+
+// 同时执行的单项清理共用一个令牌，取消时一起停止；只有一个任务在执行时换用新的令牌
+fn join_cancellable_run() -> CancelToken {
+    let concurrent = running_task_names().len() > 1;
+    let mut current = RUN_CANCEL.lock().unwrap();
+    if !concurrent || current.is_cancelled() {
+        *current = CancelToken::default();
+    }
+    current.clone()
+}
+
+pub(crate) fn cancel_running_clean() {
+    log("用户取消清理");
+    RUN_CANCEL.lock().unwrap().cancel();
+    // 取消时排队的任务一并放弃
+    RUN_QUEUE.lock().unwrap().pending.clear();
+}
+
+pub(crate) fn cancel_requested() -> bool {
+    RUN_CANCEL.lock().unwrap().is_cancelled()
+}
+
+// 执行命令并收集输出；取消时终止命令及其启动的子进程，返回 None
+fn output_cancellable(cmd: &mut Command, cancel: &CancelToken) -> std::io::Result<Option<std::process::Output>> {
+    use std::process::Stdio;
+
+    fn read_pipe<R: std::io::Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<Vec<u8>> {
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buffer);
+            }
+            buffer
+        })
+    }
+
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // 输出较多时管道会写满，需要边执行边读取
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(std::process::Output {
+                status,
+                stdout: stdout.join().unwrap_or_default(),
+                stderr: stderr.join().unwrap_or_default(),
+            }));
+        }
+        if cancel.is_cancelled() {
+            kill_process_tree(&mut child);
+            let _ = child.wait();
+            return Ok(None);
+        }
+        std::thread::sleep(CANCEL_POLL_INTERVAL);
+    }
+}
+
+// cmd /C 启动的 powershell、docker 等不会随 cmd 一起结束，用 taskkill /T 终止整个进程树
+fn kill_process_tree(child: &mut std::process::Child) {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        let mut taskkill = Command::new("taskkill");
+        taskkill.args(["/F", "/T", "/PID", &child.id().to_string()]);
+        taskkill.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        let _ = taskkill.output();
+    }
+    let _ = child.kill();
+}
+
+// 关闭程序后等待其退出的最长时间
+const PROCESS_CLOSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 请求程序正常退出（taskkill 不带 /F，相当于关闭窗口，程序有机会保存数据）并等待其退出；
+// 超时仍在运行时返回错误，不强制结束
+pub(crate) fn close_processes(names: &[String]) -> Result<(), String> {
+    if *AUDIT_MODE {
+        return Err(AUDIT_BLOCKED.to_string());
+    }
+    for name in names {
+        log(&format!("请求关闭程序: {}", name));
+        let mut taskkill = Command::new("taskkill");
+        taskkill.args(["/IM", name.as_str()]);
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            taskkill.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+        let _ = taskkill.output();
+    }
+
+    let deadline = std::time::Instant::now() + PROCESS_CLOSE_TIMEOUT;
+    loop {
+        let running = running_process_names();
+        let remaining = names
+            .iter()
+            .filter(|name| running.contains(&name.to_lowercase()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if remaining.is_empty() {
+            return Ok(());
+        }
+        if std::time::Instant::now() >= deadline {
+            return Err(format!(
+                "{} 未能在 {} 秒内退出，请手动关闭后重试",
+                remaining.join("、"),
+                PROCESS_CLOSE_TIMEOUT.as_secs()
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+pub async fn run_clean_task_impl(task: CleanTask, cancel: CancelToken, task_log: TaskLog) -> Result<(), String> {
+    if *AUDIT_MODE {
+        return Err(AUDIT_BLOCKED.to_string());
+    }
+    if cancel.is_cancelled() {
+        return Err(CANCELLED.to_string());
+    }
+
+    // 执行命令
+    let expanded_command = preflight_task(&task)?;
+    // 所属程序仍在运行时只记录警告（界面中已提示并可先关闭程序），被占用的文件会逐个报告删除失败
+    if task.process_check.is_some() {
+        let check_task = task.clone();
+        let running = tokio::task::spawn_blocking(move || check_task.running_owner_processes())
+            .await
+            .unwrap_or_default();
+        if !running.is_empty() {
+            let msg = format!("{} 正在运行，部分缓存文件可能被占用", running.join("、"));
+            log(&format!("程序运行中: {} - {}", task.name, msg));
+            task_log.log(&msg);
+        }
+    }
+    if let Some(browser) = task.browser {
+        return run_browser_clean(&task, browser, cancel, &task_log).await;
+    }
+    if !task.patterns.is_empty() {
+        return run_pattern_clean(&task, cancel, &task_log).await;
+    }
+    if task.stop_daemons {
+        return run_daemon_stop(cancel, &task_log).await;
+    }
+    if let Some((target, scope)) = delete_command_target(&expanded_command) {
+        if task.delete_mode() == DeleteMode::RecycleBin {
+            return run_recycle(&task, target, scope, &task_log).await;
+        }
+        // cmd 无法按年龄或大小筛选，设置了筛选条件时总是使用内置删除引擎
+        if SETTINGS.native_delete || task.file_filter()?.is_active() {
+            return run_native_delete(&task, target, scope, cancel, &task_log).await;
+        }
+    } else if task.delete_mode == Some(DeleteMode::RecycleBin) {
+        return Err("移入回收站只支持 rmdir /s /q <目录> 或清空目录的删除命令".to_string());
+    }
+    task_log.log(&format!("执行命令: {}", expanded_command));
+
+    // 使用spawn方式执行命令，避免UI阻塞和命令窗口弹出
+    let command_task = task.clone();
+    let command_cancel = cancel.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let task = command_task;
+        let mut cmd = if task.command.starts_with("rmdir") {
+            let mut cmd = Command::new("cmd");
+            cmd.args(&["/C", &expanded_command]);
+            cmd
+        } else {
+            let mut cmd = Command::new("cmd");
+            cmd.args(&["/C", &expanded_command]);
+            cmd
+        };
+        task.configure_command(&mut cmd);
+
+        // 隐藏窗口，防止UI卡顿
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        output_cancellable(&mut cmd, &command_cancel)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(None)) => {
+            task_log.log(&format!("命令已取消: {}", task.name));
+            Err(CANCELLED.to_string())
+        }
+        Ok(Ok(Some(output))) => {
+            let exit_code = output.status.code();
+            task_log.line(&format!(
+                "命令结束，退出码 {}",
+                exit_code.map_or_else(|| "未知".to_string(), |code| code.to_string())
+            ));
+            task_log.output("stdout", &output.stdout);
+            task_log.output("stderr", &output.stderr);
+            let exit_message = exit_code.and_then(|code| task.exit_messages.get(&code.to_string()));
+
+            if exit_code.is_some_and(|code| task.is_success_code(code)) {
+                if let (Some(code), Some(message)) = (exit_code, exit_message) {
+                    task_log.log(&format!("命令完成: {} - 退出码 {}: {}", task.name, code, message));
+                }
+                Ok(())
+            } else if let (Some(code), Some(message)) = (exit_code, exit_message) {
+                // 已知的失败退出码直接给出友好说明
+                task_log.log(&format!("命令执行失败: {} - 退出码 {}: {}", task.name, code, message));
+                Err(format!("{}（退出码 {}）", message, code))
+            } else if let Some(target) = stubborn_rmdir_target(&task) {
+                // rmdir 无法处理超长路径或畸形文件名时自动改用清除策略
+                task_log.log(&format!(
+                    "rmdir 失败，改用清除策略: {} - {}",
+                    target.display(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+                match tokio::task::spawn_blocking(move || purge_directory(&target)).await {
+                    Ok(Ok(())) => Ok(()),
+                    Ok(Err(e)) => {
+                        task_log.log(&format!("清除策略失败: {}", e));
+                        Err(format!("删除失败，清除策略也未成功: {}", e))
+                    }
+                    Err(e) => Err(format!("异步执行任务失败: {}", e)),
+                }
+            } else {
+                let error_msg = String::from_utf8_lossy(&output.stderr);
+                let stdout_msg = String::from_utf8_lossy(&output.stdout);
+
+                // 提供更详细的错误信息
+                let detailed_error = if error_msg.contains("拒绝访问") {
+                    format!("权限不足: {}\n请尝试以管理员身份运行程序", error_msg.trim())
+                } else if error_msg.contains("找不到文件") {
+                    format!(
+                        "文件或目录不存在: {}\n可能已被其他程序清理",
+                        error_msg.trim()
+                    )
+                } else if error_msg.contains("正在使用") {
+                    format!("文件正在被使用: {}\n请关闭相关程序后重试", error_msg.trim())
+                } else if !stdout_msg.is_empty() {
+                    format!(
+                        "执行失败: {}\n详细信息: {}",
+                        error_msg.trim(),
+                        stdout_msg.trim()
+                    )
+                } else {
+                    format!("执行失败: {}", error_msg.trim())
+                };
+
+                task_log.log(&format!("命令执行失败: {} - stderr: {} - stdout: {}", detailed_error, error_msg.trim(), stdout_msg.trim()));
+                Err(detailed_error)
+            }
+        }
+        Ok(Err(e)) => {
+            // 区分不同类型的执行错误
+            let error_detail = if e.to_string().contains("找不到指定的文件") {
+                "系统命令执行失败: 找不到指定的命令或程序"
+            } else if e.to_string().contains("拒绝访问") {
+                "系统命令执行失败: 权限不足，请以管理员身份运行"
+            } else {
+                &format!("系统命令执行错误: {}", e)
+            };
+
+            task_log.log(&format!("命令创建失败: {} - {}", error_detail, e));
+            Err(error_detail.to_string())
+        }
+        Err(e) => {
+            // tokio任务执行错误
+            let msg = format!("异步执行任务失败: {}", e);
+            task_log.log(&format!("tokio任务失败: {}", msg));
+            Err(msg)
+        }
+    }
+}
+
+// 可由内置删除引擎执行或移入回收站的命令返回目标目录和删除范围，只接受绝对路径（相对路径依赖工作目录）
+fn delete_command_target(expanded_command: &str) -> Option<(std::path::PathBuf, cleaner::DeleteScope)> {
+    let (target, scope) = cleaner::parse_delete_command(expanded_command)?;
+    let target = std::path::PathBuf::from(target);
+    target.is_absolute().then_some((target, scope))
+}
+
+// 浏览器缓存：逐个清空各配置文件的缓存目录，删除方式与目录删除命令相同
+async fn run_browser_clean(
+    task: &CleanTask,
+    browser: Browser,
+    cancel: CancelToken,
+    task_log: &TaskLog,
+) -> Result<(), String> {
+    let dirs = browser.cache_dirs();
+    task_log.log(&format!("清理 {} 缓存: {} 个目录", browser.label(), dirs.len()));
+    let mut errors = Vec::new();
+    for dir in dirs {
+        if cancel.is_cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        let result = if task.delete_mode() == DeleteMode::RecycleBin {
+            run_recycle(task, dir, cleaner::DeleteScope::EmptyContents, task_log).await
+        } else {
+            run_native_delete(task, dir, cleaner::DeleteScope::EmptyContents, cancel.clone(), task_log).await
+        };
+        match result {
+            Err(e) if e == CANCELLED => return Err(e),
+            Err(e) => errors.push(e),
+            Ok(()) => {}
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+// 停止残留的构建守护进程，逐个记录停止了哪些进程
+async fn run_daemon_stop(cancel: CancelToken, task_log: &TaskLog) -> Result<(), String> {
+    let stop_log = task_log.clone();
+    let (found, stopped, failures) = tokio::task::spawn_blocking(move || {
+        let daemons = find_build_daemons();
+        let mut stopped = Vec::new();
+        let mut failures = Vec::new();
+        for daemon in &daemons {
+            if cancel.is_cancelled() {
+                break;
+            }
+            match stop_process(daemon.pid) {
+                Ok(()) => {
+                    stop_log.log(&format!("已停止 {} (PID {})", daemon.kind.label(), daemon.pid));
+                    stop_log.line(&daemon.command_line);
+                    stopped.push(daemon.kind);
+                }
+                Err(e) => {
+                    let msg = format!("无法停止 {} (PID {}): {}", daemon.kind.label(), daemon.pid, e);
+                    stop_log.log(&msg);
+                    failures.push(msg);
+                }
+            }
+        }
+        (daemons.len(), stopped, failures)
+    })
+    .await
+    .map_err(|e| format!("异步执行任务失败: {}", e))?;
+
+    if found == 0 {
+        return Err(format!("没有残留的构建守护进程\n{}", NOTHING_TO_CLEAN));
+    }
+    if !stopped.is_empty() {
+        log(&format!("已停止构建守护进程: {}", summarize_daemons(&stopped)));
+    }
+    if stopped.len() + failures.len() < found {
+        return Err(CANCELLED.to_string());
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures.join("\n"))
+    }
+}
+
+// 删除各通配符模式匹配的文件，按模式记录匹配数和释放的空间；回收站模式下每个模式一次性移入
+async fn run_pattern_clean(task: &CleanTask, cancel: CancelToken, task_log: &TaskLog) -> Result<(), String> {
+    const SHOWN_FAILURES: usize = 5;
+
+    let pattern_task = task.clone();
+    let mode = task.delete_mode();
+    let delete_log = task_log.clone();
+    let failures = tokio::task::spawn_blocking(move || {
+        let mut failures = Vec::new();
+        for matched in pattern_task.pattern_matches(&cancel.0) {
+            delete_log.log(&format!(
+                "模式 {}: 匹配 {} 个文件，{}",
+                matched.pattern,
+                matched.files.len(),
+                format_size(matched.total_bytes())
+            ));
+            if matched.files.is_empty() {
+                continue;
+            }
+
+            if mode == DeleteMode::RecycleBin {
+                let item = TargetItem {
+                    name: matched.pattern.clone(),
+                    path: glob::pattern_base(&expand_environment_variables(&matched.pattern)),
+                    size: matched.total_bytes(),
+                    detail: None,
+                    orphaned: true,
+                    files: matched.files.iter().map(|(file, _)| file.clone()).collect(),
+                };
+                match recycle_target_item(&item) {
+                    Ok(bytes) => delete_log.record_freed(bytes),
+                    Err(e) => {
+                        delete_log.line(&format!("移入回收站失败: {}", e));
+                        failures.push(e);
+                    }
+                }
+                continue;
+            }
+
+            let mut freed = 0;
+            for (file, _) in &matched.files {
+                if cancel.is_cancelled() {
+                    delete_log.record_freed(freed);
+                    return Err(CANCELLED.to_string());
+                }
+                match delete_path(file) {
+                    Ok(bytes) => freed += bytes,
+                    Err(e) => {
+                        delete_log.line(&format!("删除失败: {}", e));
+                        failures.push(e);
+                    }
+                }
+            }
+            delete_log.record_freed(freed);
+        }
+        Ok(failures)
+    })
+    .await
+    .map_err(|e| format!("异步执行任务失败: {}", e))??;
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let mut lines = failures.iter().take(SHOWN_FAILURES).cloned().collect::<Vec<_>>();
+    if failures.len() > SHOWN_FAILURES {
+        lines.push(format!("……另有 {} 项，详见任务日志", failures.len() - SHOWN_FAILURES));
+    }
+    Err(format!("{} 个文件删除失败:\n{}", failures.len(), lines.join("\n")))
+}
+
+// 将删除命令的目标移入回收站，清空目录时移入其中的各个子项
+async fn run_recycle(
+    task: &CleanTask,
+    target: std::path::PathBuf,
+    scope: cleaner::DeleteScope,
+    task_log: &TaskLog,
+) -> Result<(), String> {
+    if let Err(msg) = check_target_sanity(&target) {
+        task_log.log(&format!("安全拦截: {}", msg));
+        return Err(msg);
+    }
+    task_log.log(&format!("移入回收站: {} ({:?})", target.display(), scope));
+
+    let recycle_log = task_log.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let paths = match scope {
+            cleaner::DeleteScope::Remove => vec![target],
+            cleaner::DeleteScope::EmptyContents => fs::read_dir(&target)
+                .map_err(|e| format!("无法读取目录: {} ({})", target.display(), e))?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect(),
+        };
+        for path in &paths {
+            recycle_log.line(&format!("移入回收站: {}", path.display()));
+        }
+        trash::move_to_recycle_bin(&paths.iter().map(std::path::PathBuf::as_path).collect::<Vec<_>>())
+    })
+    .await
+    .map_err(|e| format!("异步执行任务失败: {}", e))?;
+
+    match &result {
+        Ok(()) => task_log.log(&format!("已移入回收站: {}", task.name)),
+        Err(e) => task_log.log(&format!("移入回收站失败: {} - {}", task.name, e)),
+    }
+    result
+}
+
+// 用内置删除引擎代替 cmd 执行删除，失败时逐个列出删除失败的文件和原因
+async fn run_native_delete(
+    task: &CleanTask,
+    target: std::path::PathBuf,
+    scope: cleaner::DeleteScope,
+    cancel: CancelToken,
+    task_log: &TaskLog,
+) -> Result<(), String> {
+    const SHOWN_FAILURES: usize = 5;
+
+    if let Err(msg) = check_target_sanity(&target) {
+        task_log.log(&format!("安全拦截: {}", msg));
+        return Err(msg);
+    }
+    task_log.log(&format!("原生删除: {} ({:?})", target.display(), scope));
+    // 浏览器缓存任务不支持筛选条件，总是清空整个缓存目录
+    let filter = if task.browser.is_some() {
+        cleaner::FileFilter::default()
+    } else {
+        task.file_filter()?
+    };
+    if filter.is_active() {
+        task_log.log(&format!(
+            "只删除符合条件的文件: {}",
+            [
+                task.older_than_days.map(|days| format!("{} 天前修改", days)),
+                filter.min_size.map(|size| format!("不小于 {}", format_size(size))),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("，")
+        ));
+    }
+
+    let delete_log = task_log.clone();
+    let report = tokio::task::spawn_blocking(move || {
+        // 启用撤销区时先移入撤销区，无法移入的部分再由删除引擎删除；
+        // 按条件筛选时只删除部分文件，不整体移入撤销区
+        let mut staged = cleaner::DeleteReport::default();
+        // 目标为目录联接时其中的内容属于链接目标，不移动
+        let target_is_link = fs::symlink_metadata(&target).is_ok_and(|metadata| metadata.file_type().is_symlink());
+        let children = match scope {
+            _ if filter.is_active() => Vec::new(),
+            cleaner::DeleteScope::EmptyContents if target_is_link => Vec::new(),
+            cleaner::DeleteScope::Remove => vec![target.clone()],
+            cleaner::DeleteScope::EmptyContents => fs::read_dir(&target)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .map(|entry| entry.path())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+        for child in children {
+            if cancel.is_cancelled() {
+                staged.cancelled = true;
+                return staged;
+            }
+            let size = path_size(&child);
+            if quarantine_path(&child, size) {
+                delete_log.line(&format!("已移入撤销区: {} ({})", child.display(), format_size(size)));
+                staged.bytes_freed += size;
+                staged.files_removed += 1;
+            }
+        }
+        if staged.files_removed > 0 && !target.exists() {
+            return staged;
+        }
+        let mut report = cleaner::native_delete(&target, scope, &filter, &cancel.0);
+        report.bytes_freed += staged.bytes_freed;
+        report.files_removed += staged.files_removed;
+        report
+    })
+    .await
+    .map_err(|e| format!("异步执行任务失败: {}", e))?;
+    task_log.log(&format!(
+        "原生删除完成: {} - 删除 {} 个文件、{} 个目录，释放 {}，失败 {} 项",
+        task.name,
+        report.files_removed,
+        report.dirs_removed,
+        format_size(report.bytes_freed),
+        report.failures.len()
+    ));
+    for failure in &report.failures {
+        task_log.line(&format!("删除失败: {} - {}", failure.path.display(), failure.error));
+    }
+
+    task_log.record_freed(report.bytes_freed);
+    if report.cancelled {
+        task_log.log(&format!("原生删除已取消: {}", task.name));
+        return Err(CANCELLED.to_string());
+    }
+    if report.failures.is_empty() {
+        return Ok(());
+    }
+    let mut lines = report
+        .failures
+        .iter()
+        .take(SHOWN_FAILURES)
+        .map(|failure| {
+            let path = failure.path.to_string_lossy();
+            format!("{}: {}", path.strip_prefix("\\\\?\\").unwrap_or(&path), failure.error)
+        })
+        .collect::<Vec<_>>();
+    if report.failures.len() > SHOWN_FAILURES {
+        lines.push(format!(
+            "……另有 {} 项，详见任务日志",
+            report.failures.len() - SHOWN_FAILURES
+        ));
+    }
+    if report
+        .failures
+        .iter()
+        .any(|failure| failure.error.starts_with("权限不足"))
+    {
+        lines.push("请尝试以管理员身份运行程序".to_string());
+    } else if report
+        .failures
+        .iter()
+        .any(|failure| failure.error.starts_with("文件正在被使用"))
+    {
+        lines.push("请关闭相关程序后重试".to_string());
+    }
+    Err(format!(
+        "{} 个条目删除失败（已删除 {} 个文件，释放 {}）\n{}",
+        report.failures.len(),
+        report.files_removed,
+        format_size(report.bytes_freed),
+        lines.join("\n")
+    ))
+}
+
+// 批量清理日志 - 执行过程中逐项记录，程序中途被关闭时，下次启动据此显示已完成的任务并继续剩余任务
+const BATCH_JOURNAL_FILE: &str = "wincleaner-batch.json";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum BatchTaskStatus {
+    Succeeded,
+    Failed(String),
+    Skipped,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BatchJournalEntry {
+    pub(crate) task_id: String,
+    pub(crate) task_name: String,
+    pub(crate) status: BatchTaskStatus,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct BatchJournal {
+    pub(crate) started_at: i64, // Unix时间戳（秒）
+    pub(crate) task_ids: Vec<String>,
+    pub(crate) finished: Vec<BatchJournalEntry>,
+    pub(crate) running: Option<String>, // 正在执行的任务，中断时可能只完成了一部分
+}
+
+impl BatchJournal {
+    // 尚未完成的任务，包括中断时正在执行的任务
+    pub(crate) fn remaining(&self) -> Vec<String> {
+        self.task_ids
+            .iter()
+            .filter(|id| !self.finished.iter().any(|entry| &entry.task_id == *id))
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn time_text(&self) -> String {
+        chrono::DateTime::from_timestamp(self.started_at, 0)
+            .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
+}
+
+// 上次未正常结束的批量清理
+pub(crate) fn load_batch_journal() -> Option<BatchJournal> {
+    let content = fs::read_to_string(BATCH_JOURNAL_FILE).ok()?;
+    serde_json::from_str::<BatchJournal>(&content)
+        .ok()
+        .filter(|journal| !journal.remaining().is_empty())
+}
+
+fn save_batch_journal(journal: &BatchJournal) {
+    match serde_json::to_string(journal) {
+        Ok(content) => {
+            if let Err(e) = fs::write(BATCH_JOURNAL_FILE, content) {
+                log(&format!("批量清理日志写入失败: {}", e));
+            }
+        }
+        Err(e) => log(&format!("批量清理日志序列化失败: {}", e)),
+    }
+}
+
+pub(crate) fn clear_batch_journal() {
+    let _ = fs::remove_file(BATCH_JOURNAL_FILE);
+}
+
+// 批量清理的状态机 - 只记录进度、统计和中断日志，不依赖界面和执行方式；
+// 清理引擎逐项驱动状态变化，再把结果写入界面的状态（StateCell）
+#[derive(Clone, Debug, Default, PartialEq)]
+struct BatchRun {
+    pub(crate) completed: usize,
+    pub(crate) freed: u64,
+    pub(crate) stats: CleanupStats,
+    pub(crate) journal: BatchJournal,
+}
+
+impl BatchRun {
+    pub(crate) fn new(tasks: &[CleanTask], started_at: i64) -> Self {
+        BatchRun {
+            stats: CleanupStats {
+                total_tasks: tasks.len(),
+                ..Default::default()
+            },
+            journal: BatchJournal {
+                started_at,
+                task_ids: tasks.iter().map(|task| task.id.clone()).collect(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn progress(&self) -> f32 {
+        if self.stats.total_tasks == 0 {
+            return 1.0;
+        }
+        self.completed as f32 / self.stats.total_tasks as f32
+    }
+
+    // 开始执行任务，返回界面显示的运行状态
+    pub(crate) fn start(&mut self, task: &CleanTask) -> AppState {
+        self.journal.running = Some(task.id.clone());
+        AppState::Running(format!("正在清理: {}", task.name))
+    }
+
+    // 未确认、限时结束或无需清理而跳过的任务，跳过不计为失败
+    pub(crate) fn skip(&mut self, task: &CleanTask, reason: String) {
+        self.stats.skipped_tasks += 1;
+        self.stats.skipped.push((task.id.clone(), format!("{}: {}", task.name, reason)));
+        self.complete(task, BatchTaskStatus::Skipped);
+    }
+
+    pub(crate) fn finish(&mut self, task: &CleanTask, result: Result<(), String>, bytes_freed: Option<u64>) {
+        let status = match result {
+            Ok(()) => {
+                self.stats.successful_tasks += 1;
+                self.freed += bytes_freed.unwrap_or(0);
+                BatchTaskStatus::Succeeded
+            }
+            Err(e) if task.skipped_by(&e) || e == CANCELLED => {
+                // 取消的任务可能已删除了一部分
+                self.freed += bytes_freed.unwrap_or(0);
+                self.stats.skipped_tasks += 1;
+                self.stats.skipped.push((task.id.clone(), format!("{}: {}", task.name, skip_reason(&e))));
+                BatchTaskStatus::Skipped
+            }
+            Err(e) => {
+                self.stats.failed_tasks += 1;
+                self.stats.errors.push((task.id.clone(), format!("{}: {}", task.name, e)));
+                BatchTaskStatus::Failed(e)
+            }
+        };
+        self.complete(task, status);
+    }
+
+    pub(crate) fn complete(&mut self, task: &CleanTask, status: BatchTaskStatus) {
+        self.completed += 1;
+        self.journal.running = None;
+        self.journal.finished.push(BatchJournalEntry {
+            task_id: task.id.clone(),
+            task_name: task.name.clone(),
+            status,
+        });
+    }
+
+    // 全部结束后的界面状态：有失败、跳过或按卷统计时显示详细结果
+    pub(crate) fn outcome(mut self, volume_freed: Vec<(String, u64)>) -> AppState {
+        self.stats.total_space_freed = (self.freed > 0).then_some(self.freed);
+        self.stats.volume_freed = volume_freed;
+        if self.stats.failed_tasks > 0 || self.stats.skipped_tasks > 0 || !self.stats.volume_freed.is_empty() {
+            AppState::SuccessWithStats(self.stats)
+        } else {
+            AppState::Success
+        }
+    }
+
+    // 取消后的界面状态：未执行的任务已计为跳过
+    pub(crate) fn cancelled_outcome(mut self, volume_freed: Vec<(String, u64)>) -> AppState {
+        self.stats.total_space_freed = (self.freed > 0).then_some(self.freed);
+        self.stats.volume_freed = volume_freed;
+        AppState::Cancelled(self.stats)
+    }
+
+    // 单项清理结束后的界面状态和队列中的结果：失败时直接显示错误，跳过时显示统计
+    pub(crate) fn single_outcome(self) -> (AppState, RunOutcome) {
+        match self.journal.finished.last().map(|entry| entry.status.clone()) {
+            Some(BatchTaskStatus::Failed(e)) => (AppState::Error(e), RunOutcome::Failed),
+            Some(BatchTaskStatus::Skipped) => (self.outcome(Vec::new()), RunOutcome::Skipped),
+            _ => (self.outcome(Vec::new()), RunOutcome::Succeeded),
+        }
+    }
+}
+
+// 清理报告 - 批量清理结束后可导出为 HTML 或 CSV，列出各任务的结果、清理前后大小、释放空间和错误，
+// 便于交给 IT 或留作审计记录
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TaskReportRow {
+    pub(crate) task_id: String,
+    pub(crate) task_name: String,
+    pub(crate) status: &'static str,
+    pub(crate) detail: String, // 错误或跳过原因
+    pub(crate) before: Option<u64>,
+    pub(crate) after: Option<u64>,
+    pub(crate) freed: Option<u64>,
+    pub(crate) duration: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct BatchReport {
+    pub(crate) started_at: i64, // Unix时间戳（秒）
+    pub(crate) finished_at: i64,
+    pub(crate) stats: CleanupStats,
+    pub(crate) rows: Vec<TaskReportRow>,
+    pub(crate) environment: EnvironmentSnapshot,
+}
+
+// 最近一次批量清理的报告；开始新的清理时清除
+pub(crate) static LAST_BATCH_REPORT: Lazy<Mutex<Option<BatchReport>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ReportFormat {
+    Html,
+    Csv,
+}
+
+impl ReportFormat {
+    pub(crate) fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "html",
+            ReportFormat::Csv => "csv",
+        }
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl BatchReport {
+    // 按批量日志中的完成顺序汇总各任务，大小和耗时取自本次批量写入的清理历史
+    pub(crate) fn new(journal: &BatchJournal, stats: CleanupStats) -> Self {
+        let history = HISTORY.lock().unwrap();
+        let rows = journal
+            .finished
+            .iter()
+            .map(|finished| {
+                let entry = history
+                    .iter()
+                    .rev()
+                    .find(|entry| entry.task_id == finished.task_id && entry.finished_at >= journal.started_at);
+                let skip_reason = stats
+                    .skipped
+                    .iter()
+                    .find(|(id, _)| *id == finished.task_id)
+                    .map(|(_, reason)| reason.split_once(": ").map_or(reason.as_str(), |(_, reason)| reason));
+                let (status, detail) = match &finished.status {
+                    BatchTaskStatus::Succeeded => ("成功", String::new()),
+                    BatchTaskStatus::Failed(e) => ("失败", e.clone()),
+                    BatchTaskStatus::Skipped => ("跳过", skip_reason.unwrap_or_default().to_string()),
+                };
+                TaskReportRow {
+                    task_id: finished.task_id.clone(),
+                    task_name: finished.task_name.clone(),
+                    status,
+                    detail,
+                    before: entry.and_then(|entry| entry.before.as_ref()).map(|snapshot| snapshot.bytes),
+                    after: entry.and_then(|entry| entry.after.as_ref()).map(|snapshot| snapshot.bytes),
+                    freed: entry.and_then(HistoryEntry::bytes_freed),
+                    duration: entry.and_then(HistoryEntry::duration_text),
+                }
+            })
+            .collect();
+        BatchReport {
+            started_at: journal.started_at,
+            finished_at: chrono::Local::now().timestamp(),
+            stats,
+            rows,
+            environment: EnvironmentSnapshot::capture(),
+        }
+    }
+
+    pub(crate) fn time_text(timestamp: i64) -> String {
+        chrono::DateTime::from_timestamp(timestamp, 0)
+            .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn summary(&self) -> Vec<(String, String)> {
+        let mut summary = vec![
+            ("开始时间".to_string(), Self::time_text(self.started_at)),
+            ("结束时间".to_string(), Self::time_text(self.finished_at)),
+            ("计算机".to_string(), std::env::var("COMPUTERNAME").unwrap_or_default()),
+            ("任务数".to_string(), self.stats.total_tasks.to_string()),
+            ("成功".to_string(), self.stats.successful_tasks.to_string()),
+            ("失败".to_string(), self.stats.failed_tasks.to_string()),
+            ("跳过".to_string(), self.stats.skipped_tasks.to_string()),
+            ("释放空间".to_string(), format_size(self.stats.total_space_freed.unwrap_or(0))),
+        ];
+        summary.extend(
+            self.stats
+                .volume_freed
+                .iter()
+                .map(|(root, bytes)| (format!("磁盘 {}", root.trim_end_matches('\\')), format!("+{}", format_size(*bytes)))),
+        );
+        summary.extend(self.environment.summary());
+        summary
+    }
+
+    pub(crate) fn to_csv(&self) -> String {
+        let size = |bytes: Option<u64>| bytes.map(|bytes| bytes.to_string()).unwrap_or_default();
+        let mut lines = self
+            .summary()
+            .into_iter()
+            .map(|(key, value)| format!("{},{}", csv_field(&key), csv_field(&value)))
+            .collect::<Vec<_>>();
+        lines.push(String::new());
+        lines.push("task_id,task,status,before_bytes,after_bytes,freed_bytes,duration,detail".to_string());
+        lines.extend(self.rows.iter().map(|row| {
+            [
+                row.task_id.clone(),
+                row.task_name.clone(),
+                row.status.to_string(),
+                size(row.before),
+                size(row.after),
+                size(row.freed),
+                row.duration.clone().unwrap_or_default(),
+                row.detail.clone(),
+            ]
+            .map(|field| csv_field(&field))
+            .join(",")
+        }));
+        // 带 BOM 以便 Excel 正确识别中文
+        format!("\u{feff}{}\r\n", lines.join("\r\n"))
+    }
+
+    pub(crate) fn to_html(&self) -> String {
+        let size = |bytes: Option<u64>| bytes.map(format_size).unwrap_or_else(|| "—".to_string());
+        let summary = self
+            .summary()
+            .iter()
+            .map(|(key, value)| format!("<tr><th>{}</th><td>{}</td></tr>", html_escape(key), html_escape(value)))
+            .collect::<String>();
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "<tr class=\"{}\"><td>{}<br><small>{}</small></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    match row.status {
+                        "失败" => "failed",
+                        "跳过" => "skipped",
+                        _ => "ok",
+                    },
+                    html_escape(&row.task_name),
+                    html_escape(&row.task_id),
+                    row.status,
+                    size(row.before),
+                    size(row.after),
+                    size(row.freed),
+                    html_escape(row.duration.as_deref().unwrap_or("—")),
+                    html_escape(&row.detail).replace('\n', "<br>")
+                )
+            })
+            .collect::<String>();
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<title>WinCleaner 清理报告 {time}</title>\n\
+             <style>body{{font-family:\"Segoe UI\",\"Microsoft YaHei\",sans-serif;margin:24px;color:#1d1d1f}}\
+             table{{border-collapse:collapse;margin-bottom:24px}}th,td{{border:1px solid #d2d2d7;padding:6px 10px;text-align:left;vertical-align:top}}\
+             th{{background:#f5f5f7}}small{{color:#86868b}}tr.failed td{{background:#fdecea}}tr.skipped td{{color:#86868b}}</style>\n\
+             </head>\n<body>\n<h1>WinCleaner 清理报告</h1>\n<table>{summary}</table>\n\
+             <table>\n<tr><th>任务</th><th>结果</th><th>清理前</th><th>清理后</th><th>释放</th><th>耗时</th><th>错误或原因</th></tr>\n{rows}\n</table>\n\
+             <p><small>由 WinCleaner {version} 生成</small></p>\n</body>\n</html>\n",
+            time = html_escape(&Self::time_text(self.finished_at)),
+            summary = summary,
+            rows = rows,
+            version = env!("CARGO_PKG_VERSION"),
+        )
+    }
+}
+
+// 将最近一次批量清理的报告写入程序目录，返回文件路径
+pub(crate) fn export_batch_report(format: ReportFormat) -> Result<String, String> {
+    let report = LAST_BATCH_REPORT
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "没有可导出的批量清理结果".to_string())?;
+    let content = match format {
+        ReportFormat::Html => report.to_html(),
+        ReportFormat::Csv => report.to_csv(),
+    };
+    let file = format!(
+        "wincleaner-report-{}.{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    );
+    fs::write(&file, content).map_err(|e| format!("报告写入失败: {}", e))?;
+    let path = fs::canonicalize(&file).map_or(file, |path| path.to_string_lossy().to_string());
+    log(&format!("导出清理报告: {}", path));
+    record_feature("清理报告");
+    Ok(path)
+}
+
+// 限时清理 - 按历史清理速度（字节/秒）排序，在时间预算内尽量多地执行低风险任务
+const TIME_BOX_DEFAULT_RATE: f64 = 20.0 * 1024.0 * 1024.0; // 没有历史记录时假设的删除速度
+const TIME_BOX_DEFAULT_DURATION: Duration = Duration::from_secs(30); // 大小和历史都未知时的耗时估计
+const TIME_BOX_TASK_OVERHEAD: Duration = Duration::from_secs(2); // 启动命令和前后扫描的固定开销
+
+// 任务的历史平均 (释放字节数, 耗时)，只统计记录了耗时的成功执行
+fn task_throughput(task_id: &str) -> Option<(u64, Duration)> {
+    let history = HISTORY.lock().unwrap();
+    let samples = history
+        .iter()
+        .filter(|entry| entry.task_id == task_id && entry.success && entry.skipped.is_none())
+        .filter_map(|entry| Some((entry.bytes_freed().unwrap_or(0), entry.duration_ms?)))
+        .collect::<Vec<_>>();
+    if samples.is_empty() {
+        return None;
+    }
+    let count = samples.len() as u64;
+    let bytes = samples.iter().map(|(bytes, _)| bytes).sum::<u64>() / count;
+    let millis = samples.iter().map(|(_, millis)| millis).sum::<u64>() / count;
+    Some((bytes, Duration::from_millis(millis)))
+}
+
+// 估计任务的 (可释放字节数, 耗时)：当前扫描到的大小优先，耗时按历史速度折算
+fn time_box_estimate(task: &CleanTask) -> (u64, Duration) {
+    let history = task_throughput(&task.id);
+    let bytes = task
+        .get_estimated_bytes()
+        .or_else(|| history.map(|(bytes, _)| bytes))
+        .unwrap_or(0);
+    let duration = match history {
+        Some((history_bytes, history_duration)) if history_bytes > 0 => {
+            let rate = history_bytes as f64 / history_duration.as_secs_f64().max(0.001);
+            Duration::from_secs_f64(bytes as f64 / rate) + TIME_BOX_TASK_OVERHEAD
+        }
+        Some((_, history_duration)) => history_duration,
+        None if bytes > 0 => Duration::from_secs_f64(bytes as f64 / TIME_BOX_DEFAULT_RATE) + TIME_BOX_TASK_OVERHEAD,
+        None => TIME_BOX_DEFAULT_DURATION,
+    };
+    (bytes, duration)
+}
+
+// 按每秒释放字节数从高到低选择任务，累计估计耗时不超过预算
+pub(crate) fn plan_time_box(tasks: Vec<CleanTask>, budget: Duration) -> Vec<CleanTask> {
+    let mut candidates = tasks
+        .into_iter()
+        .map(|task| {
+            let (bytes, duration) = time_box_estimate(&task);
+            (bytes as f64 / duration.as_secs_f64().max(0.001), duration, task)
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut planned = Duration::ZERO;
+    let mut plan = Vec::new();
+    for (_, duration, task) in candidates {
+        if planned + duration <= budget {
+            planned += duration;
+            plan.push(task);
+        }
+    }
+    log(&format!(
+        "限时清理计划: {} 个任务，预计 {} 秒（预算 {} 秒）",
+        plan.len(),
+        planned.as_secs(),
+        budget.as_secs()
+    ));
+    plan
+}
+
+// 磁盘空间告急 - 系统盘可用空间低于 5% 时显示紧急清理横幅。空间所剩无几时完整扫描太慢，
+// 只快速估算低风险任务的可释放空间，按预计释放量从大到小依次执行，可用空间达到 10% 后停止
+const EMERGENCY_FREE_RATIO: f64 = 0.05;
+const EMERGENCY_SAFE_RATIO: f64 = 0.10;
+const EMERGENCY_MIN_BYTES: u64 = 1024 * 1024; // 预计释放不足 1 MB 的任务不列入计划
+const EMERGENCY_PLAN_LIMIT: usize = 8;
+
+pub(crate) fn below_emergency_line(free: u64, total: u64) -> bool {
+    total > 0 && (free as f64) < total as f64 * EMERGENCY_FREE_RATIO
+}
+
+// 紧急清理的目标可用空间
+pub(crate) fn emergency_target(total: u64) -> u64 {
+    (total as f64 * EMERGENCY_SAFE_RATIO) as u64
+}
+
+// 快速估算可释放空间：目录和通配符扫描只等待片刻，取已统计的部分大小；工具查询较慢，只用已有结果；
+// 都没有时按历史平均释放量
+fn emergency_estimate(task: &CleanTask) -> u64 {
+    let scanned = match task.size_cache_key() {
+        Some(key) if task.browser.is_some() || task.size_provider.is_some() => peek_size_bytes(&key),
+        Some(_) => task.get_size_entry().and_then(|entry| entry.bytes),
+        None => None,
+    };
+    scanned
+        .filter(|bytes| *bytes > 0)
+        .or_else(|| task_throughput(&task.id).map(|(bytes, _)| bytes))
+        .unwrap_or(0)
+}
+
+// 紧急清理计划：系统盘上的任务按预计释放量从大到小排列，返回 (任务, 预计释放字节数)
+pub(crate) fn plan_emergency(tasks: Vec<CleanTask>) -> Vec<(CleanTask, u64)> {
+    let system_root = system_drive_root().to_ascii_uppercase();
+    let mut plan = tasks
+        .into_iter()
+        .filter(|task| {
+            task.get_expanded_path()
+                .and_then(|path| volume_root(&path))
+                .is_none_or(|root| root == system_root)
+        })
+        .map(|task| {
+            let bytes = emergency_estimate(&task);
+            (task, bytes)
+        })
+        .filter(|(_, bytes)| *bytes >= EMERGENCY_MIN_BYTES)
+        .collect::<Vec<_>>();
+    plan.sort_by_key(|(_, bytes)| std::cmp::Reverse(*bytes));
+    plan.truncate(EMERGENCY_PLAN_LIMIT);
+    log(&format!(
+        "紧急清理计划: {} 个任务，预计释放 {}",
+        plan.len(),
+        format_size(plan.iter().map(|(_, bytes)| bytes).sum())
+    ));
+    plan
+}
+
+pub(crate) async fn run_batch_clean(
+    tasks: Vec<CleanTask>,
+    confirmed_ids: HashSet<String>,
+    app_state: impl RunHost,
+    progress: impl StateCell<f32>,
+    selected_tasks: impl StateCell<HashSet<String>>,
+) {
+    run_batch_clean_until(tasks, confirmed_ids, None, app_state, progress, selected_tasks).await;
+}
+
+// 批量清理的提前结束条件，满足后不再开始新的任务，剩余任务计为跳过
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum BatchStop {
+    Deadline(std::time::Instant), // 限时清理的截止时间
+    FreeSpace(u64),               // 紧急清理：系统盘可用空间达到该值
+}
+
+impl BatchStop {
+    // 已满足时返回跳过原因
+    pub(crate) fn reached(&self) -> Option<&'static str> {
+        match self {
+            BatchStop::Deadline(deadline) => (std::time::Instant::now() >= *deadline).then_some("限时清理时间已到"),
+            BatchStop::FreeSpace(target) => volume_free_space(&system_drive_root())
+                .is_some_and(|free| free >= *target)
+                .then_some("可用空间已达到安全线"),
+        }
+    }
+}
+
+// 按 run_after 调整批次中的顺序：依次取出第一个依赖都已排好（或不在本批次中）的任务，
+// 没有依赖关系的任务保持原顺序；残留的循环依赖按原顺序追加在最后
+fn order_by_dependencies(tasks: Vec<CleanTask>) -> Vec<CleanTask> {
+    let mut pending = tasks;
+    let mut ordered = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let ready = pending.iter().position(|task| {
+            !pending
+                .iter()
+                .any(|other| other.id != task.id && task.runs_after(other))
+        });
+        match ready {
+            Some(index) => ordered.push(pending.remove(index)),
+            None => {
+                log("批量清理: run_after 存在循环依赖，剩余任务按原顺序执行");
+                ordered.append(&mut pending);
+            }
+        }
+    }
+    ordered
+}
+
+// 批量清理引擎：需要确认的任务必须出现在 confirmed_ids 中，否则跳过；设置结束条件时，满足后不再开始新的任务，剩余任务计为跳过
+pub(crate) async fn run_batch_clean_until(
+    tasks: Vec<CleanTask>,
+    confirmed_ids: HashSet<String>,
+    stop: Option<BatchStop>,
+    mut app_state: impl RunHost,
+    mut progress: impl StateCell<f32>,
+    mut selected_tasks: impl StateCell<HashSet<String>>,
+) {
+    if tasks.is_empty() {
+        selected_tasks.store(HashSet::new());
+        return;
+    }
+    if !begin_run() {
+        reject_concurrent_run("批量清理");
+        return;
+    }
+
+    let tasks = order_by_dependencies(tasks);
+    app_state.store(AppState::Running(format!("批量清理 {} 个任务", tasks.len())));
+    record_feature("批量清理");
+    progress.store(0.0);
+
+    begin_quarantine_run();
+    let cancel = begin_cancellable_run();
+    let free_before = free_space_snapshot(&tasks);
+    let mut run = BatchRun::new(&tasks, chrono::Local::now().timestamp());
+    save_batch_journal(&run.journal);
+
+    for task in &tasks {
+        if cancel.is_cancelled() {
+            run.skip(task, CANCELLED.to_string());
+            progress.store(run.progress());
+            continue;
+        }
+        let stop_reason = stop.and_then(|stop| stop.reached());
+        if stop_reason.is_some() || (task.needs_confirmation() && !confirmed_ids.contains(&task.id)) {
+            let reason = stop_reason.unwrap_or("未确认");
+            log(&format!("批量清理跳过: {} - {}", task.name, reason));
+            run.skip(task, reason.to_string());
+            progress.store(run.progress());
+            save_batch_journal(&run.journal);
+            continue;
+        }
+
+        app_state.store(run.start(task));
+        save_batch_journal(&run.journal);
+
+        let (result, bytes_freed) = run_recorded_task(task, &cancel).await;
+        run.finish(task, result, bytes_freed);
+        progress.store(run.progress());
+        task.invalidate_size();
+        save_batch_journal(&run.journal);
+    }
+    // 取消是主动结束，不作为中断留待下次继续
+    clear_batch_journal();
+
+    let volume_freed = volume_freed(&free_before);
+    let report_stats = CleanupStats {
+        total_space_freed: (run.freed > 0).then_some(run.freed),
+        volume_freed: volume_freed.clone(),
+        ..run.stats.clone()
+    };
+    *LAST_BATCH_REPORT.lock().unwrap() = Some(BatchReport::new(&run.journal, report_stats));
+    app_state.store(if cancel.is_cancelled() {
+        log("批量清理已取消");
+        run.cancelled_outcome(volume_freed)
+    } else {
+        run.outcome(volume_freed)
+    });
+    selected_tasks.store(HashSet::new());
+    end_run(app_state);
+}
+
+// 对比清理前后的可用空间，只保留空间增加的卷
+fn volume_freed(free_before: &[(String, u64)]) -> Vec<(String, u64)> {
+    free_before
+        .iter()
+        .filter_map(|(root, before)| {
+            let after = volume_free_space(root)?;
+            (after > *before).then(|| (root.clone(), after - before))
+        })
+        .collect()
+}
+
+// 模拟运行中单个任务的结论
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum SimulationOutcome {
+    // 将会执行：展开后的命令，以及执行前是否需要确认
+    Run { command: String, needs_confirmation: bool },
+    // 将被跳过及原因
+    Skip(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SimulationEntry {
+    pub(crate) task_name: String,
+    pub(crate) outcome: SimulationOutcome,
+    pub(crate) bytes: Option<u64>,
+    // 上次清理的时间，以及此后新增的内容
+    pub(crate) new_content: Option<(String, NewContent)>,
+}
+
+// 模拟运行：先扫描每个任务的实际大小，再执行与清理相同的执行前检查，不运行任何命令
+pub(crate) async fn simulate_tasks(tasks: Vec<CleanTask>, mut checked: impl StateCell<usize>) -> Vec<SimulationEntry> {
+    record_feature("模拟运行");
+    let mut entries = Vec::new();
+    for task in tasks {
+        prescan_size(task.clone()).await;
+
+        let outcome = if task.requires_admin && !*IS_ELEVATED {
+            SimulationOutcome::Skip("需要以管理员身份运行".to_string())
+        } else {
+            let check_task = task.clone();
+            match tokio::task::spawn_blocking(move || preflight_task(&check_task)).await {
+                Ok(Ok(command)) => SimulationOutcome::Run {
+                    command,
+                    needs_confirmation: task.needs_confirmation(),
+                },
+                Ok(Err(reason)) => SimulationOutcome::Skip(reason),
+                Err(e) => SimulationOutcome::Skip(format!("检查失败: {}", e)),
+            }
+        };
+        let bytes = match outcome {
+            SimulationOutcome::Run { .. } => task.get_estimated_bytes(),
+            SimulationOutcome::Skip(_) => None,
+        };
+        // 区分上次清理后新积累的内容和一直存在的内容
+        let new_content = match (&outcome, last_clean(&task.id), task.get_expanded_path()) {
+            (SimulationOutcome::Run { .. }, Some(last), Some(path)) => {
+                let since = last.finished_at;
+                tokio::task::spawn_blocking(move || new_content_since(Path::new(&path), since))
+                    .await
+                    .ok()
+                    .map(|content| (last.time_text(), content))
+            }
+            _ => None,
+        };
+
+        entries.push(SimulationEntry {
+            task_name: task.name.clone(),
+            outcome,
+            bytes,
+            new_content,
+        });
+        checked.store(checked.load() + 1);
+    }
+    log(&format!("模拟运行完成: {} 个任务", entries.len()));
+    entries
+}
+
+// 执行任务并写入清理历史；有 path_check 的任务在执行前后各拍一次目录快照
+async fn run_recorded_task(task: &CleanTask, cancel: &CancelToken) -> (Result<(), String>, Option<u64>) {
+    let snapshot = |path_check: Option<String>| async move {
+        let path_check = path_check?;
+        tokio::task::spawn_blocking(move || snapshot_directory(&path_check))
+            .await
+            .ok()
+            .flatten()
+    };
+
+    let started_at = chrono::Local::now();
+    let task_log = TaskLog::default();
+    task_log.line(&format!("任务: {} ({})", task.name, task.id));
+    task_log.line(&format!("命令: {}", task.command));
+    let before = snapshot(task.path_check.clone()).await;
+    let start = std::time::Instant::now();
+    let result = run_clean_task_impl(task.clone(), cancel.clone(), task_log.clone()).await;
+    let elapsed = start.elapsed();
+    let after = if before.is_some() {
+        snapshot(task.path_check.clone()).await
+    } else {
+        None
+    };
+    match &result {
+        Ok(()) => task_log.line(&format!("完成，耗时 {:.1} 秒", elapsed.as_secs_f64())),
+        Err(e) => task_log.line(&format!("结束，耗时 {:.1} 秒: {}", elapsed.as_secs_f64(), e)),
+    }
+
+    // 无需清理而跳过的任务不记为失败，避免卡片显示错误徽章
+    let skipped = result.as_ref().err().is_some_and(|e| task.skipped_by(e) || e == CANCELLED);
+    let entry = HistoryEntry {
+        task_id: task.id.clone(),
+        task_name: task.name.clone(),
+        command: Some(task.command.clone()).filter(|command| !command.trim().is_empty()),
+        started_at: Some(started_at.timestamp()),
+        finished_at: chrono::Local::now().timestamp(),
+        success: result.is_ok() || skipped,
+        error: result.as_ref().err().filter(|_| !skipped).cloned(),
+        before,
+        after,
+        duration_ms: Some(elapsed.as_millis() as u64),
+        skipped: result.as_ref().err().filter(|_| skipped).map(|e| skip_reason(e)),
+        log_file: None,
+        freed: task_log.freed(),
+        environment: Some(EnvironmentSnapshot::capture()),
+    };
+    let bytes_freed = entry.bytes_freed();
+    if let Some(e) = &entry.error {
+        record_error(e);
+    }
+    if let Some((before, after)) = entry.comparison() {
+        // 删除大量文件时速度异常缓慢，在后台检测是否有杀毒软件干扰
+        let deleted_files = before.files.saturating_sub(after.files);
+        let rate = deleted_files as f64 / elapsed.as_secs_f64().max(0.001);
+        if deleted_files >= SLOW_DELETE_MIN_FILES && rate < SLOW_DELETE_RATE {
+            let trigger = format!("清理 {} 时每秒只删除 {:.0} 个文件", task.name, rate);
+            std::thread::spawn(move || report_slow_operation(trigger));
+        }
+
+        task_log.log(&format!(
+            "清理对比: {} - {} / {} 个文件 -> {} / {} 个文件",
+            task.name,
+            format_size(before.bytes),
+            before.files,
+            format_size(after.bytes),
+            after.files
+        ));
+    }
+    let entry = HistoryEntry {
+        log_file: task_log.save(&task.id, started_at),
+        ..entry
+    };
+    append_history(entry);
+
+    (result, bytes_freed)
+}
+
+// 通过 UAC 在提升权限的进程中执行任务，结束后从清理历史读取其结果
+async fn run_elevated_task(task: CleanTask, mut app_state: impl StateCell<AppState>) -> RunOutcome {
+    log(&format!("请求以管理员身份执行任务: {}", task.name));
+    record_feature("按需提权");
+    app_state.store(AppState::Running(format!("正在以管理员身份执行: {}", task.name)));
+
+    let started = chrono::Local::now().timestamp();
+    let task_id = task.id.clone();
+    ELEVATED_RUNNING.fetch_add(1, Ordering::Relaxed);
+    let result = tokio::task::spawn_blocking(move || run_task_elevated(&task_id))
+        .await
+        .unwrap_or_else(|e| Err(format!("异步执行任务失败: {}", e)));
+    ELEVATED_RUNNING.fetch_sub(1, Ordering::Relaxed);
+    let entry = tokio::task::spawn_blocking(move || reload_history_since(started))
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .find(|entry| entry.task_id == task.id);
+    task.invalidate_size();
+
+    let mut run = BatchRun::new(std::slice::from_ref(&task), started);
+    match (result, entry) {
+        (Err(e), _) => {
+            log(&format!("以管理员身份执行失败: {} - {}", task.name, e));
+            run.finish(&task, Err(e), None);
+        }
+        (Ok(_), Some(HistoryEntry { skipped: Some(reason), .. })) => {
+            log(&format!("任务无需清理，已跳过: {}", task.name));
+            run.skip(&task, reason);
+        }
+        (Ok(_), Some(entry)) if entry.success => {
+            log(&format!("任务成功: {}", task.name));
+            run.finish(&task, Ok(()), None);
+        }
+        (Ok(_), Some(entry)) => {
+            let e = entry.error.unwrap_or_else(|| "未知错误".to_string());
+            log(&format!("任务失败: {} - {}", task.name, e));
+            run.finish(&task, Err(e), None);
+        }
+        (Ok(code), None) => {
+            log(&format!("管理员进程未记录结果: {} - 退出码 {}", task.name, code));
+            run.finish(&task, Err(format!("管理员进程异常退出（退出码 {}）", code)), None);
+        }
+    }
+    let (state, outcome) = run.single_outcome();
+    app_state.store(state);
+    outcome
+}
+
+// 执行单个任务，由清理队列的执行器调用
+async fn execute_single_task(task: CleanTask, mut app_state: impl StateCell<AppState>) -> RunOutcome {
+    if task.requires_admin && !*IS_ELEVATED {
+        return run_elevated_task(task, app_state).await;
+    }
+    log(&format!("开始执行任务: {}", task.name));
+    record_feature("单项清理");
+    let running = running_task_names();
+    app_state.store(AppState::Running(format!("正在执行: {}", running.join("、"))));
+
+    // 同时执行的任务移入同一个撤销批次，撤销时一起还原
+    if running.len() <= 1 {
+        begin_quarantine_run();
+    }
+    let cancel = join_cancellable_run();
+    let mut run = BatchRun::new(std::slice::from_ref(&task), chrono::Local::now().timestamp());
+    let (result, bytes_freed) = run_recorded_task(&task, &cancel).await;
+    task.invalidate_size();
+
+    match &result {
+        Ok(_) => log(&format!("任务成功: {}", task.name)),
+        Err(e) if e == CANCELLED => log(&format!("任务已取消: {}", task.name)),
+        Err(e) if task.skipped_by(e) => log(&format!("任务无需清理，已跳过: {}", task.name)),
+        Err(e) => log(&format!("任务失败: {} - {}", task.name, e)),
+    }
+    let cancelled = result.as_ref().is_err_and(|e| e == CANCELLED);
+    run.finish(&task, result, bytes_freed);
+    let (state, outcome) = if cancelled {
+        (run.cancelled_outcome(Vec::new()), RunOutcome::Cancelled)
+    } else {
+        run.single_outcome()
+    };
+    app_state.store(state);
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str) -> CleanTask {
+        CleanTask {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            skip_if_missing: true,
+            ..Default::default()
+        }
+    }
+
+    fn statuses(run: &BatchRun) -> Vec<BatchTaskStatus> {
+        run.journal.finished.iter().map(|entry| entry.status.clone()).collect()
+    }
+
+    #[test]
+    fn batch_with_mixed_results_produces_stats_and_journal() {
+        let tasks = vec![task("a"), task("b"), task("c"), task("d")];
+        let mut run = BatchRun::new(&tasks, 100);
+
+        assert_eq!(run.start(&tasks[0]), AppState::Running("正在清理: A".to_string()));
+        assert_eq!(run.journal.running.as_deref(), Some("a"));
+        run.finish(&tasks[0], Ok(()), Some(1000));
+        run.start(&tasks[1]);
+        run.finish(&tasks[1], Err("拒绝访问".to_string()), Some(50));
+        run.start(&tasks[2]);
+        run.finish(&tasks[2], Err(format!("清理路径不存在: X\n{}", NOTHING_TO_CLEAN)), None);
+        run.skip(&tasks[3], "未确认".to_string());
+
+        assert_eq!(run.progress(), 1.0);
+        assert_eq!(run.journal.running, None);
+        assert!(run.journal.remaining().is_empty());
+        assert_eq!(
+            statuses(&run),
+            [
+                BatchTaskStatus::Succeeded,
+                BatchTaskStatus::Failed("拒绝访问".to_string()),
+                BatchTaskStatus::Skipped,
+                BatchTaskStatus::Skipped,
+            ]
+        );
+
+        let volumes = vec![("C:\\".to_string(), 900)];
+        let AppState::SuccessWithStats(stats) = run.outcome(volumes.clone()) else {
+            panic!("有失败和跳过时应显示统计");
+        };
+        assert_eq!(
+            stats,
+            CleanupStats {
+                total_tasks: 4,
+                successful_tasks: 1,
+                failed_tasks: 1,
+                skipped_tasks: 2,
+                // 失败任务释放的空间不计入
+                total_space_freed: Some(1000),
+                errors: vec![("b".to_string(), "B: 拒绝访问".to_string())],
+                skipped: vec![
+                    ("c".to_string(), "C: 清理路径不存在: X".to_string()),
+                    ("d".to_string(), "D: 未确认".to_string()),
+                ],
+                volume_freed: volumes,
+            }
+        );
+    }
+
+    #[test]
+    fn batch_without_problems_is_plain_success() {
+        let tasks = vec![task("a"), task("b")];
+        let mut run = BatchRun::new(&tasks, 100);
+        run.finish(&tasks[0], Ok(()), None);
+        run.finish(&tasks[1], Ok(()), Some(0));
+        assert_eq!(run.outcome(Vec::new()), AppState::Success);
+    }
+
+    #[test]
+    fn cancelled_batch_keeps_partial_progress() {
+        let tasks = vec![task("a"), task("b"), task("c")];
+        let mut run = BatchRun::new(&tasks, 100);
+        run.finish(&tasks[0], Ok(()), Some(10));
+        run.start(&tasks[1]);
+        run.finish(&tasks[1], Err(CANCELLED.to_string()), Some(5));
+
+        assert_eq!(run.journal.remaining(), ["c".to_string()]);
+        let AppState::Cancelled(stats) = run.cancelled_outcome(Vec::new()) else {
+            panic!("取消后应为 Cancelled");
+        };
+        assert_eq!(stats.successful_tasks, 1);
+        assert_eq!(stats.skipped_tasks, 1);
+        // 取消的任务已删除的部分也计入
+        assert_eq!(stats.total_space_freed, Some(15));
+    }
+
+    #[test]
+    fn single_task_outcomes() {
+        let single = |result: Result<(), String>| {
+            let target = task("a");
+            let mut run = BatchRun::new(std::slice::from_ref(&target), 100);
+            run.finish(&target, result, None);
+            run.single_outcome()
+        };
+
+        assert_eq!(single(Ok(())), (AppState::Success, RunOutcome::Succeeded));
+        assert_eq!(
+            single(Err("拒绝访问".to_string())),
+            (AppState::Error("拒绝访问".to_string()), RunOutcome::Failed)
+        );
+        let (state, outcome) = single(Err(format!("目录为空\n{}", NOTHING_TO_CLEAN)));
+        assert_eq!(outcome, RunOutcome::Skipped);
+        assert!(matches!(state, AppState::SuccessWithStats(stats) if stats.skipped_tasks == 1));
+    }
+
+    #[test]
+    fn splits_command_lines() {
+        assert_eq!(
+            split_command_line(r#""C:\Program Files\nodejs\node.exe"  "D:\my app\server.js" --port 80"#),
+            [r"C:\Program Files\nodejs\node.exe", r"D:\my app\server.js", "--port", "80"]
+        );
+        assert_eq!(split_command_line(r#"node "" x"#), ["node", "", "x"]);
+        assert_eq!(split_command_line("  \t "), Vec::<String>::new());
+    }
+
+    #[test]
+    fn finds_node_script() {
+        assert_eq!(node_script("node -r ts-node/register script.js").as_deref(), Some("script.js"));
+        assert_eq!(node_script("node --inspect=9229 --require=x app.js").as_deref(), Some("app.js"));
+        assert_eq!(node_script("node -- -weird.js").as_deref(), Some("-weird.js"));
+        assert_eq!(node_script("node -e \"console.log(1)\""), None);
+        assert_eq!(node_script("node"), None);
+    }
+
+    #[test]
+    fn node_workspace_missing_requires_absolute_script_on_existing_volume() {
+        let deleted = std::env::temp_dir().join("wincleaner-deleted workspace").join("index.js");
+        assert!(node_workspace_missing(&format!("node.exe \"{}\"", deleted.display())));
+        assert!(node_workspace_missing(&format!("node.exe -r x \"{}\"", deleted.display())));
+        // 相对路径的脚本无法判断
+        assert!(!node_workspace_missing("node.exe missing-script.js"));
+        // 仍存在的脚本
+        let existing = std::env::current_exe().unwrap();
+        assert!(!node_workspace_missing(&format!("node.exe \"{}\"", existing.display())));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn node_script_on_missing_volume_is_not_deleted() {
+        let Some(letter) = ('D'..='Z').find(|letter| !Path::new(&format!("{}:\\", letter)).exists()) else {
+            return;
+        };
+        assert!(!node_workspace_missing(&format!("node.exe {}:\\work\\index.js", letter)));
+    }
+
+    #[test]
+    fn classifies_build_daemons() {
+        let gradle = r#""C:\Program Files\Java\jdk-17\bin\java.exe" -Xmx2g -cp "C:\Users\a b\.gradle\wrapper\gradle-launcher.jar" org.gradle.launcher.daemon.bootstrap.GradleDaemon 8.5"#;
+        assert_eq!(BuildDaemon::classify("java.exe", gradle), Some(BuildDaemon::Gradle));
+        assert_eq!(BuildDaemon::classify("JavaW.EXE", gradle), Some(BuildDaemon::Gradle));
+        let kotlin = r#"java.exe -cp "C:\kotlin compiler\kotlin-compiler-embeddable.jar" org.jetbrains.kotlin.daemon.KotlinCompileDaemon --daemon-runFilesPath x"#;
+        assert_eq!(BuildDaemon::classify("java.exe", kotlin), Some(BuildDaemon::Kotlin));
+        assert_eq!(BuildDaemon::classify("adb.exe", "adb -L tcp:5037 fork-server server --reply-fd 512"), Some(BuildDaemon::Adb));
+        let deleted = std::env::temp_dir().join("wincleaner-deleted workspace").join("index.js");
+        assert_eq!(
+            BuildDaemon::classify("node.exe", &format!("node.exe \"{}\"", deleted.display())),
+            Some(BuildDaemon::Node)
+        );
+
+        // 普通的 java / node / adb 进程不算
+        assert_eq!(BuildDaemon::classify("java.exe", "java.exe -jar app.jar"), None);
+        assert_eq!(BuildDaemon::classify("node.exe", "node.exe server.js"), None);
+        assert_eq!(BuildDaemon::classify("node.exe", "node.exe"), None);
+        assert_eq!(BuildDaemon::classify("adb.exe", "adb devices"), None);
+        assert_eq!(BuildDaemon::classify("gradle.exe", gradle), None);
+    }
+
+    #[test]
+    fn scheduled_runs_skip_tasks_needing_confirmation() {
+        let scheduled = |risk| CleanTask {
+            risk,
+            schedule: Some(TaskSchedule::Weekly),
+            command: "echo".to_string(),
+            ..task("a")
+        };
+        assert_eq!(scheduled_skip_reason(&scheduled(RiskLevel::High), true), Some("需要确认"));
+        assert_eq!(scheduled_skip_reason(&scheduled(RiskLevel::Medium), true), Some("需要确认"));
+        assert_eq!(scheduled_skip_reason(&scheduled(RiskLevel::Low), true), None);
+
+        let admin = CleanTask {
+            requires_admin: true,
+            ..scheduled(RiskLevel::Low)
+        };
+        assert_eq!(scheduled_skip_reason(&admin, false), Some("需要管理员权限"));
+        assert_eq!(scheduled_skip_reason(&admin, true), None);
+        let item_only = CleanTask {
+            item_selection: true,
+            command: String::new(),
+            ..scheduled(RiskLevel::Low)
+        };
+        assert_eq!(scheduled_skip_reason(&item_only, true), Some("只能逐项选择清理"));
+    }
+}
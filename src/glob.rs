@@ -0,0 +1,188 @@
+// 通配符引擎 - 展开 patterns 规则中的路径模式：* 和 ? 匹配单级名称中的任意字符，
+// ** 匹配任意层目录（含零层）；不区分大小写，只匹配文件，不进入符号链接和目录联接
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// 一个模式的匹配结果
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PatternMatch {
+    pub pattern: String,
+    pub files: Vec<(PathBuf, u64)>,
+}
+
+impl PatternMatch {
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|(_, size)| size).sum()
+    }
+}
+
+pub fn has_wildcard(text: &str) -> bool {
+    text.contains(['*', '?'])
+}
+
+// 模式中第一个通配符之前的目录，即遍历的起点
+pub fn pattern_base(pattern: &str) -> PathBuf {
+    let parts = split_pattern(pattern);
+    let literal = parts.iter().take_while(|part| !has_wildcard(part)).count();
+    // 不含通配符的模式是单个文件，起点为其所在目录
+    let literal = literal.min(parts.len().saturating_sub(1));
+    join_parts(&parts[..literal])
+}
+
+// 展开已替换环境变量的模式，结果按路径排序且不重复。cancel 置位后返回已找到的部分
+pub fn expand_pattern(pattern: &str, cancel: &AtomicBool) -> PatternMatch {
+    let mut parts = split_pattern(pattern);
+    // 以 ** 结尾时匹配其下的全部文件
+    if parts.last().is_some_and(|part| *part == "**") {
+        parts.push("*");
+    }
+    let literal = parts.iter().take_while(|part| !has_wildcard(part)).count();
+
+    let mut files = Vec::new();
+    if literal == parts.len() {
+        let path = join_parts(&parts);
+        if let Ok(metadata) = fs::symlink_metadata(&path) {
+            if metadata.is_file() {
+                files.push((path, metadata.len()));
+            }
+        }
+    } else {
+        walk(&join_parts(&parts[..literal]), &parts[literal..], cancel, &mut files);
+    }
+
+    files.sort();
+    files.dedup_by(|a, b| a.0 == b.0);
+    PatternMatch {
+        pattern: pattern.to_string(),
+        files,
+    }
+}
+
+fn split_pattern(pattern: &str) -> Vec<&str> {
+    pattern
+        .split(['\\', '/'])
+        .enumerate()
+        // 保留开头的空段，以 \ 或 / 开头的路径仍是从根目录开始
+        .filter(|(index, part)| *index == 0 || !part.is_empty())
+        .map(|(_, part)| part)
+        .collect()
+}
+
+fn join_parts(parts: &[&str]) -> PathBuf {
+    let mut text = parts.join(std::path::MAIN_SEPARATOR_STR);
+    // 驱动器号之后需要分隔符，"C:" 表示当前目录而不是根目录
+    if text.ends_with(':') {
+        text.push('\\');
+    }
+    PathBuf::from(text)
+}
+
+fn walk(dir: &Path, parts: &[&str], cancel: &AtomicBool, files: &mut Vec<(PathBuf, u64)>) {
+    let Some((&part, rest)) = parts.split_first() else {
+        return;
+    };
+    if cancel.load(Ordering::Relaxed) {
+        return;
+    }
+    // ** 先匹配零层目录，再逐层进入子目录
+    if part == "**" {
+        walk(dir, rest, cancel, files);
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            continue;
+        }
+        let path = entry.path();
+        if part == "**" {
+            if file_type.is_dir() {
+                walk(&path, parts, cancel, files);
+            }
+            continue;
+        }
+        if !wildcard_match(part, &entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        if rest.is_empty() {
+            if file_type.is_file() {
+                let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+                files.push((path, size));
+            }
+        } else if file_type.is_dir() {
+            walk(&path, rest, cancel, files);
+        }
+    }
+}
+
+// 单级名称的通配符匹配，不区分大小写
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+    let name = name.to_lowercase().chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    // 最近一个 * 的位置，以及它当前吞下的字符结束处，匹配失败时从这里回溯
+    let mut star = None;
+    let mut star_end = 0;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some(p);
+            star_end = n;
+            p += 1;
+        } else if let Some(star) = star {
+            star_end += 1;
+            p = star + 1;
+            n = star_end;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&ch| ch == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_match_handles_star_and_question_mark() {
+        assert!(wildcard_match("*.tmp", "cache.TMP"));
+        assert!(wildcard_match("log?.txt", "log1.txt"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a*b*c", "aXXbYYc"));
+        assert!(!wildcard_match("*.tmp", "cache.tmp.bak"));
+        assert!(!wildcard_match("log?.txt", "log.txt"));
+    }
+
+    #[test]
+    fn pattern_base_stops_before_first_wildcard() {
+        assert_eq!(pattern_base("/data/temp/**/*.tmp"), Path::new("/data/temp"));
+        assert_eq!(pattern_base("/data\\downloads\\*.iso"), Path::new("/data/downloads"));
+        assert_eq!(pattern_base("/data/file.txt"), Path::new("/data"));
+    }
+
+    #[test]
+    fn expand_pattern_matches_files_recursively() {
+        let root = std::env::temp_dir().join(format!("wincleaner-glob-{}", std::process::id()));
+        fs::create_dir_all(root.join("a").join("b")).unwrap();
+        fs::write(root.join("top.tmp"), b"12").unwrap();
+        fs::write(root.join("a").join("keep.txt"), b"x").unwrap();
+        fs::write(root.join("a").join("b").join("deep.tmp"), b"1234").unwrap();
+
+        let pattern = format!("{}/**/*.tmp", root.display());
+        let matched = expand_pattern(&pattern, &AtomicBool::new(false));
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(matched.files.len(), 2);
+        assert_eq!(matched.total_bytes(), 6);
+    }
+}
@@ -0,0 +1,63 @@
+// WinCleaner - Windows 系统清理工具
+// config 读取配置和全局设置，tasks 定义清理任务和内置规则，engine 执行清理，
+// sizing 扫描和格式化大小，logging 记录日志和清理历史，ui 为界面
+pub mod config;
+pub mod engine;
+pub mod logging;
+pub mod sizing;
+pub mod tasks;
+pub mod ui;
+
+mod cleaner;
+mod glob;
+mod quarantine;
+mod trash;
+
+pub use engine::{run_clean_task_impl, CancelToken};
+pub use logging::TaskLog;
+pub use sizing::{format_size, parse_size, SizeUnits};
+pub use tasks::{builtin_tasks, expand_environment_variables, CleanCategory, CleanTask, RiskLevel};
+
+use engine::{
+    run_benchmark_cli, run_boot_clean, run_scheduled_tasks, run_task_cli, TaskSchedule, BENCH_ARG,
+    BOOT_CLEAN_ARG, RUN_TASK_ARG, SCHEDULE_ARG,
+};
+use logging::{log, run_export_logs_cli, EXPORT_LOGS_ARG};
+
+// 程序入口：按命令行参数执行开机清理、定期清理、提权任务、日志导出或基准测试，否则打开主窗口
+pub fn run() {
+    let args = std::env::args().collect::<Vec<_>>();
+    // 开机计划任务以 --boot-clean 启动，不显示窗口
+    if args.iter().any(|arg| arg == BOOT_CLEAN_ARG) {
+        run_boot_clean();
+        return;
+    }
+    if let Some(index) = args.iter().position(|arg| arg == SCHEDULE_ARG) {
+        match args.get(index + 1).and_then(|key| TaskSchedule::parse(key)) {
+            Some(schedule) => run_scheduled_tasks(schedule),
+            None => log("定期清理参数无效"),
+        }
+        return;
+    }
+    if let Some(index) = args.iter().position(|arg| arg == RUN_TASK_ARG) {
+        let code = match args.get(index + 1) {
+            Some(task_id) => run_task_cli(task_id),
+            None => {
+                log("执行任务参数无效");
+                1
+            }
+        };
+        std::process::exit(code);
+    }
+    if args.iter().any(|arg| arg == EXPORT_LOGS_ARG) {
+        run_export_logs_cli(&args);
+        return;
+    }
+    // 隐藏的基准测试模式: wincleaner --bench <路径>，缺省在临时目录中测试
+    if let Some(index) = args.iter().position(|arg| arg == BENCH_ARG) {
+        run_benchmark_cli(args.get(index + 1).map_or("%TEMP%", String::as_str));
+        return;
+    }
+
+    ui::launch();
+}
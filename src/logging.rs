@@ -0,0 +1,834 @@
+// 日志 - 程序日志、清理历史、单次任务日志、使用统计和日志导出
+use crate::config::{load_user_config, AUDIT_MODE, SETTINGS};
+use crate::engine::{attach_parent_console, IS_ELEVATED};
+use crate::sizing::{format_size, local_volumes, run_query_command, volume_free_space, DirSnapshot};
+use crate::tasks::INSTALLED_TOOLS;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// 环形日志缓冲区 - 恒定大小，保留最近100条日志
+static LOG_RING: Lazy<Mutex<VecDeque<String>>> = Lazy::new(|| {
+    Mutex::new(VecDeque::with_capacity(100))
+});
+
+const LOG_FILE: &str = "wincleaner.log";
+
+pub(crate) fn log(message: &str) {
+    const MAX_LOGS: usize = 100;
+    
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+    let entry = format!("[{}] {}\n", timestamp, message);
+    
+    let mut ring = LOG_RING.lock().unwrap();
+    
+    // 环形缓冲区：满了就移除最旧的
+    if ring.len() >= MAX_LOGS {
+        ring.pop_front();
+    }
+    ring.push_back(entry);
+    
+    // 原子化文件写入，失败时报告错误
+    let content = ring.iter().cloned().collect::<String>();
+    if let Err(e) = std::fs::write(LOG_FILE, content) {
+        eprintln!("日志写入失败: {}", e);
+    }
+}
+
+// 运行环境快照 - 记录在清理报告和历史中，便于比较不同机器、不同时间的清理结果和排查问题
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct EnvironmentSnapshot {
+    pub(crate) app_version: String,
+    pub(crate) windows_build: String,
+    pub(crate) elevated: bool,
+    // 各本地磁盘的可用空间 (根目录, 字节)
+    #[serde(default)]
+    pub(crate) free_space: Vec<(String, u64)>,
+    // 检测到的工具名称
+    #[serde(default)]
+    pub(crate) tools: Vec<String>,
+}
+
+// 系统版本号，如 "10.0.22631.3880"，取自 ver 命令的输出
+static WINDOWS_BUILD: Lazy<String> = Lazy::new(|| {
+    let output = run_query_command("ver").unwrap_or_default();
+    let output = output.trim();
+    output
+        .rsplit_once(' ')
+        .and_then(|(_, version)| version.strip_suffix(']'))
+        .unwrap_or(output)
+        .to_string()
+});
+
+impl EnvironmentSnapshot {
+    pub(crate) fn capture() -> Self {
+        EnvironmentSnapshot {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            windows_build: WINDOWS_BUILD.clone(),
+            elevated: *IS_ELEVATED,
+            free_space: local_volumes()
+                .into_iter()
+                .filter_map(|root| volume_free_space(&root).map(|bytes| (root, bytes)))
+                .collect(),
+            tools: INSTALLED_TOOLS.iter().map(|tool| tool.to_string()).collect(),
+        }
+    }
+
+    pub(crate) fn summary(&self) -> Vec<(String, String)> {
+        let mut summary = vec![
+            ("程序版本".to_string(), self.app_version.clone()),
+            ("系统版本".to_string(), self.windows_build.clone()),
+            ("管理员权限".to_string(), if self.elevated { "是" } else { "否" }.to_string()),
+            (
+                "检测到的工具".to_string(),
+                if self.tools.is_empty() { "无".to_string() } else { self.tools.join(", ") },
+            ),
+        ];
+        summary.extend(self.free_space.iter().map(|(root, bytes)| {
+            (format!("可用空间 {}", root.trim_end_matches('\\')), format_size(*bytes))
+        }));
+        summary
+    }
+
+    // 历史列表中显示的一行摘要
+    pub(crate) fn short_text(&self) -> String {
+        let mut parts = vec![format!("v{}", self.app_version)];
+        if !self.windows_build.is_empty() {
+            parts.push(format!("Windows {}", self.windows_build));
+        }
+        if self.elevated {
+            parts.push("管理员".to_string());
+        }
+        parts.extend(
+            self.free_space
+                .iter()
+                .map(|(root, bytes)| format!("{} 可用 {}", root.trim_end_matches('\\'), format_size(*bytes))),
+        );
+        parts.join(" · ")
+    }
+}
+
+// 清理历史 - 每次执行追加一行 JSON，包含有 path_check 任务的清理前后快照
+const HISTORY_FILE: &str = "wincleaner-history.jsonl";
+pub(crate) const HISTORY_VIEW_LIMIT: usize = 50;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct HistoryEntry {
+    pub(crate) task_id: String,
+    pub(crate) task_name: String,
+    // 执行的命令（展开环境变量前）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) started_at: Option<i64>, // Unix时间戳（秒）
+    pub(crate) finished_at: i64,
+    pub(crate) success: bool,
+    #[serde(default)]
+    pub(crate) error: Option<String>,
+    #[serde(default)]
+    pub(crate) before: Option<DirSnapshot>,
+    #[serde(default)]
+    pub(crate) after: Option<DirSnapshot>,
+    // 执行耗时，用于限时清理估计各任务的清理速度
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) duration_ms: Option<u64>,
+    // 无需清理而跳过时的原因；跳过不算失败，success 仍为 true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) skipped: Option<String>,
+    // 本次执行的任务日志文件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) log_file: Option<String>,
+    // 删除引擎报告的释放字节数，用于没有清理前后快照的任务
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) freed: Option<u64>,
+    // 执行时的运行环境，旧版历史没有此项
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) environment: Option<EnvironmentSnapshot>,
+}
+
+impl HistoryEntry {
+    // 清理前后的快照都存在时才能给出对比
+    pub(crate) fn comparison(&self) -> Option<(&DirSnapshot, &DirSnapshot)> {
+        Some((self.before.as_ref()?, self.after.as_ref()?))
+    }
+
+    pub(crate) fn bytes_freed(&self) -> Option<u64> {
+        self.comparison()
+            .map(|(before, after)| before.bytes.saturating_sub(after.bytes))
+            .or(self.freed)
+    }
+
+    pub(crate) fn duration_text(&self) -> Option<String> {
+        let seconds = self.duration_ms? as f64 / 1000.0;
+        Some(if seconds < 60.0 {
+            format!("{:.1} 秒", seconds)
+        } else {
+            format!("{} 分 {} 秒", seconds as u64 / 60, seconds as u64 % 60)
+        })
+    }
+
+    // 历史筛选：since 之后结束，且任务ID或名称包含 task（为空时不筛选）
+    pub(crate) fn matches(&self, since: Option<i64>, task: &str) -> bool {
+        let task = task.trim();
+        since.is_none_or(|since| self.finished_at >= since)
+            && (task.is_empty() || self.task_id.contains(task) || self.task_name.contains(task))
+    }
+
+    pub(crate) fn time_text(&self) -> String {
+        chrono::DateTime::from_timestamp(self.finished_at, 0)
+            .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
+}
+
+pub(crate) static HISTORY: Lazy<Mutex<Vec<HistoryEntry>>> = Lazy::new(|| {
+    let entries = fs::read_to_string(HISTORY_FILE)
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str::<HistoryEntry>(line).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    Mutex::new(entries)
+});
+
+pub(crate) fn append_history(entry: HistoryEntry) {
+    use std::io::Write;
+
+    match serde_json::to_string(&entry) {
+        Ok(line) => {
+            let result = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(HISTORY_FILE)
+                .and_then(|mut file| writeln!(file, "{}", line));
+            if let Err(e) = result {
+                log(&format!("清理历史写入失败: {}", e));
+            }
+        }
+        Err(e) => log(&format!("清理历史序列化失败: {}", e)),
+    }
+    HISTORY.lock().unwrap().push(entry);
+}
+
+// 任务日志 - 每次执行单独写入 logs\<开始时间>-<任务ID>.log，包含命令的完整输出和删除引擎的逐项记录；
+// 共享日志只保留最近100条且各任务交错，较长的输出（如 DISM）看不全
+const TASK_LOG_DIR: &str = "logs";
+
+#[derive(Clone, Default)]
+pub struct TaskLog {
+    pub(crate) content: Arc<Mutex<String>>,
+    // 删除引擎报告的释放字节数，没有清理前后快照的任务据此记录释放的空间
+    pub(crate) freed: Arc<Mutex<Option<u64>>>,
+}
+
+impl TaskLog {
+    pub(crate) fn line(&self, message: &str) {
+        let timestamp = chrono::Local::now().format("%H:%M:%S");
+        self.content.lock().unwrap().push_str(&format!("[{}] {}\n", timestamp, message));
+    }
+
+    // 同时写入共享日志
+    pub(crate) fn log(&self, message: &str) {
+        log(message);
+        self.line(message);
+    }
+
+    // 原样追加命令的 stdout 或 stderr
+    pub(crate) fn output(&self, stream: &str, bytes: &[u8]) {
+        let text = String::from_utf8_lossy(bytes);
+        if text.trim().is_empty() {
+            return;
+        }
+        let mut content = self.content.lock().unwrap();
+        content.push_str(&format!("----- {} -----\n", stream));
+        content.push_str(text.trim_end());
+        content.push('\n');
+    }
+
+    pub(crate) fn record_freed(&self, bytes: u64) {
+        *self.freed.lock().unwrap().get_or_insert(0) += bytes;
+    }
+
+    pub(crate) fn freed(&self) -> Option<u64> {
+        *self.freed.lock().unwrap()
+    }
+
+    // 写入日志文件，返回其路径
+    pub(crate) fn save(&self, task_id: &str, started_at: chrono::DateTime<chrono::Local>) -> Option<String> {
+        let id = task_id
+            .chars()
+            .map(|ch| if ch.is_alphanumeric() || ch == '-' || ch == '_' { ch } else { '_' })
+            .collect::<String>();
+        let path = Path::new(TASK_LOG_DIR).join(format!("{}-{}.log", started_at.format("%Y%m%d-%H%M%S"), id));
+        let result = fs::create_dir_all(TASK_LOG_DIR).and_then(|()| fs::write(&path, &*self.content.lock().unwrap()));
+        match result {
+            Ok(()) => Some(path.to_string_lossy().into_owned()),
+            Err(e) => {
+                log(&format!("任务日志写入失败: {} ({})", path.display(), e));
+                None
+            }
+        }
+    }
+}
+
+// 删除超过保留天数的任务日志
+pub(crate) fn prune_task_logs() {
+    let Ok(entries) = fs::read_dir(TASK_LOG_DIR) else {
+        return;
+    };
+    let max_age = Duration::from_secs(SETTINGS.task_log_days * 24 * 60 * 60);
+    let expired = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > max_age)
+        })
+        .filter(|entry| fs::remove_file(entry.path()).is_ok())
+        .count();
+    if expired > 0 {
+        log(&format!("已删除 {} 个过期的任务日志", expired));
+    }
+}
+
+// 用默认程序（通常是记事本）打开任务日志
+pub(crate) fn open_task_log(path: &str) {
+    let path = std::env::current_dir().map(|dir| dir.join(path)).unwrap_or_else(|_| path.into());
+    if let Err(e) = Command::new("explorer").arg(&path).spawn() {
+        log(&format!("无法打开任务日志: {} ({})", path.display(), e));
+    }
+}
+
+// 仍然存在的任务日志文件
+pub(crate) fn existing_log_file(entry: &HistoryEntry) -> Option<String> {
+    entry.log_file.clone().filter(|path| Path::new(path).exists())
+}
+
+// 读取其他进程（提升权限的任务进程）追加的清理历史，返回 since 之后新增的记录
+pub(crate) fn reload_history_since(since: i64) -> Vec<HistoryEntry> {
+    let content = fs::read_to_string(HISTORY_FILE).unwrap_or_default();
+    let mut history = HISTORY.lock().unwrap();
+    let added = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<HistoryEntry>(line).ok())
+        .filter(|entry| entry.finished_at >= since && !history.contains(entry))
+        .collect::<Vec<_>>();
+    history.extend(added.iter().cloned());
+    added
+}
+
+pub(crate) fn last_history_entry(task_id: &str) -> Option<HistoryEntry> {
+    HISTORY.lock().unwrap().iter().rev().find(|entry| entry.task_id == task_id).cloned()
+}
+
+// 失败记录在任务卡片上的徽章：(徽章文字, 是否可重试, 错误摘要)
+// 权限不足和文件占用通常换个时机或以管理员身份重试即可，用 ⚠ 区分
+pub(crate) fn failure_badge(entry: &HistoryEntry) -> (String, bool, String) {
+    const EXCERPT_CHARS: usize = 80;
+
+    let error = entry.error.as_deref().unwrap_or("未知错误");
+    let kind = error_kind(error);
+    let retryable = matches!(kind, "权限不足" | "文件占用");
+    let first_line = error.lines().next().unwrap_or_default().trim();
+    let excerpt = if first_line.chars().count() > EXCERPT_CHARS {
+        format!("{}…", first_line.chars().take(EXCERPT_CHARS).collect::<String>())
+    } else {
+        first_line.to_string()
+    };
+    (
+        format!(
+            "{} {}",
+            if retryable { "⚠" } else { "✗" },
+            if kind == "其他" { "失败" } else { kind }
+        ),
+        retryable,
+        format!("{} 失败: {}", entry.time_text(), excerpt),
+    )
+}
+
+// 失效规则提示 - 最近连续几次成功执行都几乎没有释放空间的任务，规则可能已失效（如程序移动了缓存目录）
+pub(crate) const STALE_TASK_RUNS: usize = 3;
+const STALE_TASK_MAX_BYTES: u64 = 1024 * 1024;
+
+// 只统计有前后快照的执行，无路径检查的任务不会被判为失效
+pub(crate) fn is_stale_task(task_id: &str) -> bool {
+    let history = HISTORY.lock().unwrap();
+    let recent = history
+        .iter()
+        .rev()
+        .filter(|entry| entry.task_id == task_id && entry.success)
+        .take(STALE_TASK_RUNS)
+        .map(HistoryEntry::bytes_freed)
+        .collect::<Vec<_>>();
+    recent.len() == STALE_TASK_RUNS
+        && recent.iter().all(|bytes| bytes.is_some_and(|bytes| bytes <= STALE_TASK_MAX_BYTES))
+}
+
+// 最近的历史记录，新的在前
+pub(crate) fn recent_history(limit: usize) -> Vec<HistoryEntry> {
+    HISTORY.lock().unwrap().iter().rev().take(limit).cloned().collect()
+}
+
+// 历史条目的开始时间、耗时和释放的空间
+pub(crate) fn history_details(entry: &HistoryEntry) -> Option<String> {
+    let mut details = Vec::new();
+    if let Some(started) = entry
+        .started_at
+        .and_then(|started| chrono::DateTime::from_timestamp(started, 0))
+    {
+        details.push(format!("开始于 {}", started.with_timezone(&chrono::Local).format("%H:%M:%S")));
+    }
+    if let Some(duration) = entry.duration_text() {
+        details.push(format!("耗时 {}", duration));
+    }
+    if let Some(bytes) = entry.bytes_freed().filter(|_| entry.success && entry.skipped.is_none()) {
+        details.push(format!("释放 {}", format_size(bytes)));
+    }
+    (!details.is_empty()).then(|| details.join(" · "))
+}
+
+// 符合筛选条件的记录总数和其中最近的 limit 条
+pub(crate) fn filtered_history(since: Option<i64>, task: &str, limit: usize) -> (usize, Vec<HistoryEntry>) {
+    let history = HISTORY.lock().unwrap();
+    let matching = history.iter().rev().filter(|entry| entry.matches(since, task));
+    let total = matching.clone().count();
+    (total, matching.take(limit).cloned().collect())
+}
+
+// 任务最近一次实际执行了清理（成功且未跳过）的记录
+pub(crate) fn last_clean(task_id: &str) -> Option<HistoryEntry> {
+    HISTORY
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|entry| entry.task_id == task_id && entry.success && entry.skipped.is_none())
+        .cloned()
+}
+
+// 使用统计 - 仅保存在本机，需用户在"诊断"中主动开启；导出诊断包时附带，便于反馈问题
+pub(crate) const USAGE_FILE: &str = "wincleaner-usage.json";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct UsageStats {
+    pub(crate) enabled: bool,
+    pub(crate) since: i64, // 开启统计的时间，Unix时间戳（秒）
+    pub(crate) features: BTreeMap<String, u64>, // 功能 -> 使用次数
+    pub(crate) errors: BTreeMap<String, u64>,   // 错误类型 -> 次数
+    pub(crate) scans: u64,
+    pub(crate) scan_entries: u64,
+    pub(crate) scan_millis: u64,
+}
+
+impl UsageStats {
+    pub(crate) fn average_scan_text(&self) -> String {
+        if self.scans == 0 {
+            return "暂无".to_string();
+        }
+        format!(
+            "平均 {} 毫秒 / {} 个条目（共 {} 次）",
+            self.scan_millis / self.scans,
+            self.scan_entries / self.scans,
+            self.scans
+        )
+    }
+
+    // 查看器和诊断包中的文字形式
+    pub(crate) fn report(&self) -> String {
+        let mut lines = vec![format!(
+            "统计开始: {}",
+            chrono::DateTime::from_timestamp(self.since, 0)
+                .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default()
+        )];
+        lines.push("功能使用次数:".to_string());
+        lines.extend(self.features.iter().map(|(feature, count)| format!("  {}: {}", feature, count)));
+        lines.push("错误类型:".to_string());
+        lines.extend(self.errors.iter().map(|(kind, count)| format!("  {}: {}", kind, count)));
+        lines.push(format!("目录扫描: {}", self.average_scan_text()));
+        lines.join("\r\n")
+    }
+}
+
+static USAGE: Lazy<Mutex<UsageStats>> = Lazy::new(|| {
+    Mutex::new(
+        fs::read_to_string(USAGE_FILE)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default(),
+    )
+});
+
+fn save_usage(stats: &UsageStats) {
+    match serde_json::to_string_pretty(stats) {
+        Ok(content) => {
+            if let Err(e) = fs::write(USAGE_FILE, content) {
+                log(&format!("使用统计写入失败: {}", e));
+            }
+        }
+        Err(e) => log(&format!("使用统计序列化失败: {}", e)),
+    }
+}
+
+pub(crate) fn usage_stats() -> UsageStats {
+    USAGE.lock().unwrap().clone()
+}
+
+// 开启时从零开始统计，关闭时清空已有数据
+pub(crate) fn set_usage_enabled(enabled: bool) {
+    let mut stats = USAGE.lock().unwrap();
+    *stats = UsageStats {
+        enabled,
+        since: chrono::Local::now().timestamp(),
+        ..Default::default()
+    };
+    save_usage(&stats);
+}
+
+// 未开启统计时不记录任何内容
+fn record_usage(update: impl FnOnce(&mut UsageStats)) {
+    let mut stats = USAGE.lock().unwrap();
+    if stats.enabled {
+        update(&mut stats);
+        save_usage(&stats);
+    }
+}
+
+pub(crate) fn record_feature(feature: &str) {
+    record_usage(|stats| *stats.features.entry(feature.to_string()).or_default() += 1);
+}
+
+// 只记录错误类型，不记录路径等错误详情
+pub(crate) fn record_error(error: &str) {
+    record_usage(|stats| *stats.errors.entry(error_kind(error).to_string()).or_default() += 1);
+}
+
+pub(crate) fn record_scan(entries: u64, elapsed: Duration) {
+    record_usage(|stats| {
+        stats.scans += 1;
+        stats.scan_entries += entries;
+        stats.scan_millis += elapsed.as_millis() as u64;
+    });
+}
+
+fn error_kind(error: &str) -> &'static str {
+    const KINDS: [(&[&str], &str); 6] = [
+        (&["审计模式"], "审计模式拦截"),
+        (&["权限不足", "拒绝访问", "管理员"], "权限不足"),
+        (&["正在被使用", "正在使用", "占用"], "文件占用"),
+        (&["不存在", "找不到"], "路径不存在"),
+        (&["禁区", "层级", "安全检查", "拒绝"], "安全检查拦截"),
+        (&["超时"], "超时"),
+    ];
+    KINDS
+        .iter()
+        .find(|(keywords, _)| keywords.iter().any(|keyword| error.contains(keyword)))
+        .map_or("其他", |(_, kind)| kind)
+}
+
+// 诊断包：版本、系统、设置、使用统计、最近的日志和历史，写入程序目录并返回文件路径
+pub(crate) fn export_diagnostics_bundle() -> Result<String, String> {
+    let mut sections = vec![
+        format!("WinCleaner {} 诊断信息", env!("CARGO_PKG_VERSION")),
+        format!("生成时间: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")),
+        format!("审计模式: {}", *AUDIT_MODE),
+        String::new(),
+        "[运行环境]".to_string(),
+    ];
+    sections.extend(
+        EnvironmentSnapshot::capture()
+            .summary()
+            .into_iter()
+            .map(|(key, value)| format!("{}: {}", key, value)),
+    );
+    sections.extend([
+        String::new(),
+        "[设置]".to_string(),
+        format!("{:#?}", *SETTINGS),
+        format!("自定义规则: {} 条", load_user_config().task.len()),
+        String::new(),
+        "[使用统计]".to_string(),
+    ]);
+    let stats = usage_stats();
+    sections.push(if stats.enabled { stats.report() } else { "未开启".to_string() });
+
+    sections.push(String::new());
+    sections.push("[最近历史]".to_string());
+    for entry in recent_history(20) {
+        sections.push(format!(
+            "{} {} {}{}",
+            entry.time_text(),
+            entry.task_name,
+            match (entry.success, &entry.skipped) {
+                (true, Some(_)) => "跳过",
+                (true, None) => "成功",
+                (false, _) => "失败",
+            },
+            entry
+                .error
+                .as_ref()
+                .or(entry.skipped.as_ref())
+                .map(|e| format!(": {}", e))
+                .unwrap_or_default()
+        ));
+    }
+
+    sections.push(String::new());
+    sections.push("[最近日志]".to_string());
+    sections.push(LOG_RING.lock().unwrap().iter().cloned().collect::<String>());
+
+    let file = format!("wincleaner-diagnostics-{}.txt", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    fs::write(&file, sections.join("\r\n")).map_err(|e| format!("诊断包写入失败: {}", e))?;
+    let path = fs::canonicalize(&file).map_or(file, |path| path.to_string_lossy().to_string());
+    log(&format!("导出诊断包: {}", path));
+    Ok(path)
+}
+
+// 日志导出 - 按时间范围、任务和级别筛选日志与清理历史，导出为 JSON 或 CSV
+// 命令行: wincleaner --export-logs [文件] [--since 7d|24h|2024-05-01] [--until 日期] [--task ID] [--severity warning] [--format csv]
+pub(crate) const EXPORT_LOGS_ARG: &str = "--export-logs";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum LogSeverity {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogSeverity {
+    pub(crate) const ALL: [LogSeverity; 3] = [LogSeverity::Info, LogSeverity::Warning, LogSeverity::Error];
+
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            LogSeverity::Info => "info",
+            LogSeverity::Warning => "warning",
+            LogSeverity::Error => "error",
+        }
+    }
+
+    pub(crate) fn parse(key: &str) -> Option<LogSeverity> {
+        LogSeverity::ALL.into_iter().find(|severity| severity.key().eq_ignore_ascii_case(key))
+    }
+
+    // 筛选项的文字：不低于该级别
+    pub(crate) fn filter_label(&self) -> &'static str {
+        match self {
+            LogSeverity::Info => "全部",
+            LogSeverity::Warning => "警告及以上",
+            LogSeverity::Error => "仅错误",
+        }
+    }
+
+    // 日志行没有级别字段，按内容中的关键字判断
+    pub(crate) fn of_log(message: &str) -> LogSeverity {
+        if ["失败", "错误", "拦截", "无法"].iter().any(|word| message.contains(word)) {
+            LogSeverity::Error
+        } else if ["警告", "跳过", "超时", "提醒"].iter().any(|word| message.contains(word)) {
+            LogSeverity::Warning
+        } else {
+            LogSeverity::Info
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LogExportFormat {
+    Json,
+    Csv,
+}
+
+impl LogExportFormat {
+    pub(crate) fn extension(&self) -> &'static str {
+        match self {
+            LogExportFormat::Json => "json",
+            LogExportFormat::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct LogFilter {
+    pub(crate) since: Option<i64>, // Unix时间戳（秒）
+    pub(crate) until: Option<i64>,
+    pub(crate) task: String, // 任务ID或名称中的文字，为空时不筛选
+    pub(crate) min_severity: LogSeverity,
+}
+
+impl LogFilter {
+    pub(crate) fn matches(&self, record: &LogRecord) -> bool {
+        let task = self.task.trim();
+        self.since.is_none_or(|since| record.timestamp >= since)
+            && self.until.is_none_or(|until| record.timestamp <= until)
+            && record.severity >= self.min_severity
+            && (task.is_empty() || record.task.contains(task) || record.message.contains(task))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct LogRecord {
+    pub(crate) time: String,
+    #[serde(skip)]
+    pub(crate) timestamp: i64,
+    pub(crate) source: &'static str, // "log" 或 "history"
+    pub(crate) severity: LogSeverity,
+    pub(crate) task: String,
+    pub(crate) message: String,
+}
+
+// 读取日志文件和全部清理历史，按时间排序后筛选
+fn log_records(filter: &LogFilter) -> Vec<LogRecord> {
+    const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+    let mut records: Vec<LogRecord> = Vec::new();
+    for line in fs::read_to_string(LOG_FILE).unwrap_or_default().lines() {
+        let parsed = line
+            .strip_prefix('[')
+            .and_then(|line| line.split_once("] "))
+            .and_then(|(time, message)| {
+                let time = chrono::NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()?;
+                Some((time, message))
+            });
+        match parsed {
+            Some((time, message)) => records.push(LogRecord {
+                time: time.format(TIME_FORMAT).to_string(),
+                timestamp: time.and_local_timezone(chrono::Local).earliest().map_or(0, |time| time.timestamp()),
+                source: "log",
+                severity: LogSeverity::of_log(message),
+                task: String::new(),
+                message: message.to_string(),
+            }),
+            // 多行日志的后续行
+            None => {
+                if let Some(last) = records.last_mut() {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                }
+            }
+        }
+    }
+    for entry in HISTORY.lock().unwrap().iter() {
+        let (severity, message) = match (&entry.error, &entry.skipped) {
+            (Some(error), _) => (LogSeverity::Error, format!("失败: {}", error)),
+            (None, Some(reason)) => (LogSeverity::Warning, format!("跳过: {}", reason)),
+            (None, None) if !entry.success => (LogSeverity::Error, "失败".to_string()),
+            (None, None) => (
+                LogSeverity::Info,
+                match entry.bytes_freed() {
+                    Some(bytes) => format!("成功，释放 {}", format_size(bytes)),
+                    None => "成功".to_string(),
+                },
+            ),
+        };
+        records.push(LogRecord {
+            time: chrono::DateTime::from_timestamp(entry.finished_at, 0)
+                .map(|time| time.with_timezone(&chrono::Local).format(TIME_FORMAT).to_string())
+                .unwrap_or_default(),
+            timestamp: entry.finished_at,
+            source: "history",
+            severity,
+            task: entry.task_id.clone(),
+            message: format!("{} {}", entry.task_name, message),
+        });
+    }
+    records.retain(|record| filter.matches(record));
+    records.sort_by_key(|record| record.timestamp);
+    records
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// 写入导出文件，未指定路径时在程序目录生成带时间的文件名，返回完整路径
+pub(crate) fn export_logs(filter: &LogFilter, format: LogExportFormat, path: Option<&str>) -> Result<String, String> {
+    let records = log_records(filter);
+    let content = match format {
+        LogExportFormat::Json => {
+            serde_json::to_string_pretty(&records).map_err(|e| format!("日志导出失败: {}", e))?
+        }
+        LogExportFormat::Csv => {
+            let mut lines = vec!["time,source,severity,task,message".to_string()];
+            lines.extend(records.iter().map(|record| {
+                [record.time.as_str(), record.source, record.severity.key(), &record.task, &record.message]
+                    .map(csv_field)
+                    .join(",")
+            }));
+            // 带 BOM 以便 Excel 正确识别中文
+            format!("\u{feff}{}\r\n", lines.join("\r\n"))
+        }
+    };
+
+    let file = path.map(str::to_string).unwrap_or_else(|| {
+        format!(
+            "wincleaner-logs-{}.{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            format.extension()
+        )
+    });
+    fs::write(&file, content).map_err(|e| format!("日志导出写入失败: {}", e))?;
+    let path = fs::canonicalize(&file).map_or(file, |path| path.to_string_lossy().to_string());
+    log(&format!("导出日志: {}（{} 条）", path, records.len()));
+    Ok(path)
+}
+
+// 命令行时间参数："24h"、"7d" 表示距今的时长，或 "2024-05-01" 这样的日期（当天 0 点）
+fn parse_time_arg(text: &str) -> Option<i64> {
+    let now = chrono::Local::now().timestamp();
+    if let Some(hours) = text.strip_suffix('h').and_then(|hours| hours.parse::<i64>().ok()) {
+        return Some(now - hours * 3600);
+    }
+    if let Some(days) = text.strip_suffix('d').and_then(|days| days.parse::<i64>().ok()) {
+        return Some(now - days * 86400);
+    }
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()?
+        .and_hms_opt(0, 0, 0)?
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .map(|time| time.timestamp())
+}
+
+pub(crate) fn run_export_logs_cli(args: &[String]) {
+    attach_parent_console();
+    let value_of = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|index| args.get(index + 1))
+            .map(String::as_str)
+    };
+    let path = value_of(EXPORT_LOGS_ARG).filter(|value| !value.starts_with("--"));
+    let format = match value_of("--format") {
+        Some(format) if format.eq_ignore_ascii_case("csv") => LogExportFormat::Csv,
+        Some(_) => LogExportFormat::Json,
+        None if path.is_some_and(|path| path.to_ascii_lowercase().ends_with(".csv")) => LogExportFormat::Csv,
+        None => LogExportFormat::Json,
+    };
+    let filter = LogFilter {
+        since: value_of("--since").and_then(parse_time_arg),
+        until: value_of("--until").and_then(parse_time_arg),
+        task: value_of("--task").unwrap_or_default().to_string(),
+        min_severity: value_of("--severity").and_then(LogSeverity::parse).unwrap_or_default(),
+    };
+
+    match export_logs(&filter, format, path) {
+        Ok(path) => println!("已导出: {}", path),
+        Err(e) => eprintln!("{}", e),
+    }
+}
@@ -0,0 +1,305 @@
+// 撤销区 - 删除前将文件和目录移入按清理批次划分的目录（<根目录>\<批次ID>），
+// 每个批次附带清单 manifest.jsonl（每行一个条目），可整体还原到原位置；
+// 超过保留天数或总大小上限的批次会被删除
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const MANIFEST_FILE: &str = "manifest.jsonl";
+
+// 同一批次内存放位置的序号
+static NEXT_SLOT: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    pub original: PathBuf,
+    // 批次目录中的存放名称
+    pub stored: String,
+    pub bytes: u64,
+    pub staged_at: i64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuarantineRun {
+    pub id: String,
+    pub entries: Vec<QuarantineEntry>,
+}
+
+impl QuarantineRun {
+    pub fn total_bytes(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.bytes).sum()
+    }
+
+    pub fn created_at(&self) -> i64 {
+        self.entries
+            .iter()
+            .map(|entry| entry.staged_at)
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+// 还原结果：未能还原的条目（原位置已有同名文件或移动失败）留在撤销区
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RestoreReport {
+    pub restored: usize,
+    pub restored_bytes: u64,
+    pub failures: Vec<(PathBuf, String)>,
+}
+
+pub struct Quarantine {
+    root: PathBuf,
+}
+
+impl Quarantine {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Quarantine { root: root.into() }
+    }
+
+    fn run_dir(&self, run_id: &str) -> PathBuf {
+        self.root.join(run_id)
+    }
+
+    fn load_run(&self, run_id: &str) -> Option<QuarantineRun> {
+        let content = fs::read_to_string(self.run_dir(run_id).join(MANIFEST_FILE)).ok()?;
+        let entries = content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect::<Vec<_>>();
+        (!entries.is_empty()).then(|| QuarantineRun {
+            id: run_id.to_string(),
+            entries,
+        })
+    }
+
+    // 将路径移入批次目录。只做重命名，不复制数据：
+    // 目标与撤销区不在同一磁盘或文件被占用时返回错误，由调用方改为直接删除
+    pub fn stage(
+        &self,
+        run_id: &str,
+        path: &Path,
+        bytes: u64,
+        staged_at: i64,
+    ) -> Result<(), String> {
+        let dir = self.run_dir(run_id);
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("无法创建撤销区目录: {} ({})", dir.display(), e))?;
+
+        let stored = loop {
+            let name = NEXT_SLOT.fetch_add(1, Ordering::Relaxed).to_string();
+            if !dir.join(&name).exists() {
+                break name;
+            }
+        };
+        fs::rename(path, dir.join(&stored)).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let entry = QuarantineEntry {
+            original: path.to_path_buf(),
+            stored,
+            bytes,
+            staged_at,
+        };
+        let line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(MANIFEST_FILE))
+            .and_then(|mut file| writeln!(file, "{}", line))
+            .map_err(|e| {
+                // 清单写入失败时条目无法还原，移回原位置
+                let _ = fs::rename(dir.join(&entry.stored), path);
+                format!("无法写入撤销区清单: {}", e)
+            })
+    }
+
+    // 全部批次，最新的在前
+    pub fn runs(&self) -> Vec<QuarantineRun> {
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return Vec::new();
+        };
+        let mut runs = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| self.load_run(&entry.file_name().to_string_lossy()))
+            .collect::<Vec<_>>();
+        runs.sort_by(|a, b| {
+            b.created_at()
+                .cmp(&a.created_at())
+                .then_with(|| b.id.cmp(&a.id))
+        });
+        runs
+    }
+
+    pub fn latest(&self) -> Option<QuarantineRun> {
+        self.runs().into_iter().next()
+    }
+
+    // 将批次中的条目移回原位置，全部还原后删除批次目录
+    pub fn restore(&self, run_id: &str) -> Result<RestoreReport, String> {
+        let run = self
+            .load_run(run_id)
+            .ok_or_else(|| format!("找不到撤销记录: {}", run_id))?;
+        let dir = self.run_dir(run_id);
+        let mut report = RestoreReport::default();
+        let mut remaining = Vec::new();
+
+        for entry in run.entries {
+            let result = if entry.original.exists() {
+                Err("原位置已存在同名文件或目录".to_string())
+            } else {
+                entry
+                    .original
+                    .parent()
+                    .map_or(Ok(()), fs::create_dir_all)
+                    .and_then(|()| fs::rename(dir.join(&entry.stored), &entry.original))
+                    .map_err(|e| e.to_string())
+            };
+            match result {
+                Ok(()) => {
+                    report.restored += 1;
+                    report.restored_bytes += entry.bytes;
+                }
+                Err(e) => {
+                    report.failures.push((entry.original.clone(), e));
+                    remaining.push(entry);
+                }
+            }
+        }
+
+        if remaining.is_empty() {
+            let _ = fs::remove_dir_all(&dir);
+        } else {
+            let content = remaining
+                .iter()
+                .filter_map(|entry| serde_json::to_string(entry).ok())
+                .map(|line| line + "\n")
+                .collect::<String>();
+            fs::write(dir.join(MANIFEST_FILE), content)
+                .map_err(|e| format!("无法写入撤销区清单: {}", e))?;
+        }
+        Ok(report)
+    }
+
+    // 从最旧的批次开始，删除早于 expire_before 的批次以及使总大小超过 max_bytes 的批次；
+    // keep 指定的批次（正在进行的清理）不删除。返回被删除的批次ID
+    pub fn prune(&self, max_bytes: u64, expire_before: i64, keep: Option<&str>) -> Vec<String> {
+        let mut runs = self.runs();
+        let mut total = runs.iter().map(QuarantineRun::total_bytes).sum::<u64>();
+        let mut removed = Vec::new();
+
+        while let Some(run) = runs.pop() {
+            if keep == Some(run.id.as_str())
+                || (run.created_at() >= expire_before && total <= max_bytes)
+            {
+                continue;
+            }
+            if fs::remove_dir_all(self.run_dir(&run.id)).is_ok() {
+                total = total.saturating_sub(run.total_bytes());
+                removed.push(run.id);
+            }
+        }
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 返回 (撤销区, 待清理目录)
+    fn temp_quarantine(name: &str) -> (Quarantine, PathBuf) {
+        let base = std::env::temp_dir().join(format!(
+            "wincleaner-quarantine-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&base);
+        let work = base.join("work");
+        fs::create_dir_all(work.join("dir")).unwrap();
+        fs::write(work.join("a.txt"), b"hello").unwrap();
+        fs::write(work.join("dir").join("b.txt"), b"abc").unwrap();
+        (Quarantine::new(base.join("store")), work)
+    }
+
+    #[test]
+    fn stage_and_restore_round_trip() {
+        let (quarantine, work) = temp_quarantine("round-trip");
+        quarantine
+            .stage("run", &work.join("a.txt"), 5, 100)
+            .unwrap();
+        quarantine.stage("run", &work.join("dir"), 3, 101).unwrap();
+        assert!(!work.join("a.txt").exists());
+        assert!(!work.join("dir").exists());
+
+        let runs = quarantine.runs();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].id, "run");
+        assert_eq!(runs[0].entries.len(), 2);
+        assert_eq!(runs[0].total_bytes(), 8);
+        assert_eq!(runs[0].created_at(), 100);
+
+        let report = quarantine.restore("run").unwrap();
+        assert_eq!(report.restored, 2);
+        assert_eq!(report.restored_bytes, 8);
+        assert!(report.failures.is_empty());
+        assert_eq!(fs::read(work.join("a.txt")).unwrap(), b"hello");
+        assert_eq!(fs::read(work.join("dir").join("b.txt")).unwrap(), b"abc");
+        assert!(quarantine.runs().is_empty());
+        assert!(quarantine.restore("run").is_err());
+
+        fs::remove_dir_all(work.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn restore_keeps_entries_whose_original_exists() {
+        let (quarantine, work) = temp_quarantine("conflict");
+        quarantine
+            .stage("run", &work.join("a.txt"), 5, 100)
+            .unwrap();
+        quarantine.stage("run", &work.join("dir"), 3, 100).unwrap();
+        fs::write(work.join("a.txt"), b"new").unwrap();
+
+        let report = quarantine.restore("run").unwrap();
+        assert_eq!(report.restored, 1);
+        assert_eq!(report.restored_bytes, 3);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].0, work.join("a.txt"));
+        assert_eq!(fs::read(work.join("a.txt")).unwrap(), b"new");
+
+        // 未还原的条目留在清单中，原位置腾出后可以再次还原
+        let runs = quarantine.runs();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].entries.len(), 1);
+        assert_eq!(runs[0].entries[0].original, work.join("a.txt"));
+        fs::remove_file(work.join("a.txt")).unwrap();
+        assert_eq!(quarantine.restore("run").unwrap().restored, 1);
+        assert_eq!(fs::read(work.join("a.txt")).unwrap(), b"hello");
+
+        fs::remove_dir_all(work.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn prune_honours_keep_age_and_size_limit() {
+        let (quarantine, work) = temp_quarantine("prune");
+        for (run_id, staged_at) in [("old", 100), ("mid", 200), ("new", 300)] {
+            let path = work.join(run_id);
+            fs::write(&path, b"0123456789").unwrap();
+            quarantine.stage(run_id, &path, 10, staged_at).unwrap();
+        }
+
+        // 过期的批次被删除，keep 指定的批次保留
+        assert_eq!(quarantine.prune(u64::MAX, 250, Some("old")), ["mid"]);
+        // 总大小超过上限时从最旧的批次开始删除，直到不超过上限
+        assert_eq!(quarantine.prune(10, 0, None), ["old"]);
+        assert_eq!(quarantine.prune(10, 0, None), Vec::<String>::new());
+        let ids = quarantine
+            .runs()
+            .into_iter()
+            .map(|run| run.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["new"]);
+
+        fs::remove_dir_all(work.parent().unwrap()).unwrap();
+    }
+}
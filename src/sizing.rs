@@ -0,0 +1,1091 @@
+// 大小 - 目录大小的扫描和缓存、磁盘空间、目录增长监测，以及大小的格式化和解析
+use crate::config::{LOCALE, SETTINGS};
+#[cfg(windows)]
+use crate::engine::to_wide;
+use crate::engine::{report_slow_operation, TargetItem, SLOW_SCAN_MIN_ENTRIES, SLOW_SCAN_RATE};
+use crate::logging::{log, record_scan};
+use crate::tasks::{expand_environment_variables, never_touch_rule, resolve_tool_path, CleanTask};
+use crate::trash;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// 获取目录大小（递归计算）
+pub(crate) fn get_directory_size(path: &str) -> Option<u64> {
+    scan_directory_size(path, &AtomicU64::new(0))
+}
+
+// 扫描调优参数：设置中未指定时按磁盘类型选择
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ScanTuning {
+    pub(crate) max_workers: usize, // 同一卷上同时进行的目录扫描数
+    pub(crate) batch_size: usize,  // 每处理多少个条目检查一次节流
+    pub(crate) pause: Duration,    // 每批条目之后的暂停时间
+}
+
+pub(crate) fn scan_tuning(path: &str) -> ScanTuning {
+    let kind = volume_root(path).map_or(DriveKind::Unknown, |root| drive_kind(&root));
+    let (workers, pause_ms) = match kind {
+        DriveKind::Ssd => (4, 0),
+        DriveKind::Hdd => (1, 10),
+        DriveKind::Unknown => (2, 0),
+    };
+    ScanTuning {
+        max_workers: if SETTINGS.scan_max_workers > 0 { SETTINGS.scan_max_workers } else { workers },
+        batch_size: SETTINGS.scan_batch_size.max(1),
+        pause: Duration::from_millis(SETTINGS.scan_throttle_ms.unwrap_or(pause_ms)),
+    }
+}
+
+// 扫描节流：每处理 batch_size 个条目暂停一次，让出磁盘给前台程序
+pub(crate) struct ScanThrottle {
+    pub(crate) tuning: ScanTuning,
+    pub(crate) count: usize,
+}
+
+impl ScanThrottle {
+    pub(crate) fn new(tuning: ScanTuning) -> Self {
+        ScanThrottle { tuning, count: 0 }
+    }
+
+    pub(crate) fn tick(&mut self) {
+        self.count += 1;
+        if self.count.is_multiple_of(self.tuning.batch_size) && !self.tuning.pause.is_zero() {
+            std::thread::sleep(self.tuning.pause);
+        }
+    }
+}
+
+// 每个卷上正在进行的扫描数，超过上限的扫描等待空位
+static SCAN_WORKERS: Lazy<(Mutex<HashMap<String, usize>>, std::sync::Condvar)> =
+    Lazy::new(|| (Mutex::new(HashMap::new()), std::sync::Condvar::new()));
+
+pub(crate) struct ScanPermit {
+    pub(crate) volume: String,
+}
+
+impl ScanPermit {
+    pub(crate) fn acquire(path: &str, limit: usize) -> ScanPermit {
+        let volume = volume_root(path).unwrap_or_default();
+        let (lock, available) = &*SCAN_WORKERS;
+        let mut running = lock.lock().unwrap();
+        while running.get(&volume).copied().unwrap_or(0) >= limit.max(1) {
+            running = available.wait(running).unwrap();
+        }
+        *running.entry(volume.clone()).or_default() += 1;
+        ScanPermit { volume }
+    }
+}
+
+impl Drop for ScanPermit {
+    fn drop(&mut self) {
+        let (lock, available) = &*SCAN_WORKERS;
+        if let Some(count) = lock.lock().unwrap().get_mut(&self.volume) {
+            *count = count.saturating_sub(1);
+        }
+        available.notify_all();
+    }
+}
+
+// 统计目录大小，扫描过程中把已统计的字节数累加到progress，供界面显示部分结果
+pub(crate) fn scan_directory_size(path: &str, progress: &AtomicU64) -> Option<u64> {
+    let expanded_path = expand_environment_variables(path);
+    let path = Path::new(&expanded_path);
+
+    if !path.exists() {
+        return None;
+    }
+
+    // 路径本身是文件时直接返回文件大小
+    if path.is_file() {
+        let size = fs::metadata(path).ok()?.len();
+        progress.fetch_add(size, Ordering::Relaxed);
+        return Some(size);
+    }
+
+    fn dir_size(dir: &Path, progress: &AtomicU64, throttle: &mut ScanThrottle) -> std::io::Result<u64> {
+        let mut size = 0;
+        if dir.is_dir() {
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                throttle.tick();
+                let path = entry.path();
+                if path.is_dir() {
+                    // 不进入内置禁区（例如指向系统目录的联接）
+                    if never_touch_rule(&path).is_some() {
+                        continue;
+                    }
+                    size += dir_size(&path, progress, throttle)?;
+                } else {
+                    let len = entry.metadata()?.len();
+                    progress.fetch_add(len, Ordering::Relaxed);
+                    size += len;
+                }
+            }
+        }
+        Ok(size)
+    }
+
+    let tuning = scan_tuning(&expanded_path);
+    let _permit = ScanPermit::acquire(&expanded_path, tuning.max_workers);
+    let mut throttle = ScanThrottle::new(tuning);
+    let start = std::time::Instant::now();
+    let result = dir_size(path, progress, &mut throttle);
+
+    // 节流暂停之外仍然很慢时，检测是否有杀毒软件干扰
+    let elapsed = start.elapsed().saturating_sub(tuning.pause * (throttle.count / tuning.batch_size) as u32);
+    record_scan(throttle.count as u64, elapsed);
+    let rate = throttle.count as f64 / elapsed.as_secs_f64().max(0.001);
+    if throttle.count >= SLOW_SCAN_MIN_ENTRIES && rate < SLOW_SCAN_RATE {
+        report_slow_operation(format!("扫描 {} 时每秒只处理 {:.0} 个条目", expanded_path, rate));
+    }
+
+    result.ok()
+}
+
+// 大小扫描缓存 - 持久化到文件，重启后的结果标记为"上次启动时"
+const SIZE_CACHE_FILE: &str = "wincleaner-sizes.json";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SizeEntry {
+    pub(crate) bytes: Option<u64>,
+    pub(crate) scanned_at: i64, // Unix时间戳（秒）
+    #[serde(skip)]
+    pub(crate) previous_session: bool,
+    #[serde(skip)]
+    pub(crate) scanning: bool, // 后台扫描未完成，bytes为已统计的下限
+}
+
+impl SizeEntry {
+    // 扫描时间的可读描述
+    pub(crate) fn age_text(&self) -> String {
+        if self.scanning {
+            return "扫描中…".to_string();
+        }
+        if self.previous_session {
+            return "上次启动时扫描".to_string();
+        }
+
+        let seconds = (chrono::Local::now().timestamp() - self.scanned_at).max(0);
+        match seconds {
+            0..=59 => "刚刚扫描".to_string(),
+            60..=3599 => format!("{} 分钟前扫描", seconds / 60),
+            3600..=86399 => format!("{} 小时前扫描", seconds / 3600),
+            _ => format!("{} 天前扫描", seconds / 86400),
+        }
+    }
+}
+
+static SIZE_CACHE: Lazy<Mutex<HashMap<String, SizeEntry>>> = Lazy::new(|| {
+    let mut entries = fs::read_to_string(SIZE_CACHE_FILE)
+        .ok()
+        .and_then(|content| serde_json::from_str::<HashMap<String, SizeEntry>>(&content).ok())
+        .unwrap_or_default();
+    for entry in entries.values_mut() {
+        entry.previous_session = true;
+    }
+    Mutex::new(entries)
+});
+
+fn save_size_cache(cache: &HashMap<String, SizeEntry>) {
+    match serde_json::to_string(cache) {
+        Ok(content) => {
+            if let Err(e) = fs::write(SIZE_CACHE_FILE, content) {
+                log(&format!("大小缓存写入失败: {}", e));
+            }
+        }
+        Err(e) => log(&format!("大小缓存序列化失败: {}", e)),
+    }
+}
+
+pub(crate) fn cached_size(key: &str, scan: impl FnOnce() -> Option<u64>) -> SizeEntry {
+    if let Some(entry) = SIZE_CACHE.lock().unwrap().get(key) {
+        return entry.clone();
+    }
+
+    let entry = SizeEntry {
+        bytes: scan(),
+        scanned_at: chrono::Local::now().timestamp(),
+        previous_session: false,
+        scanning: false,
+    };
+
+    let mut cache = SIZE_CACHE.lock().unwrap();
+    cache.insert(key.to_string(), entry.clone());
+    save_size_cache(&cache);
+    entry
+}
+
+// 后台扫描 - 目录或通配符扫描在等待超时后先返回已统计的部分大小，扫描继续在后台进行
+const SCAN_WAIT: Duration = Duration::from_millis(150);
+pub(crate) const SCAN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+pub(crate) const STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+pub(crate) static SIZE_SCANS: Lazy<Mutex<HashMap<String, Arc<AtomicU64>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub(crate) fn is_scan_running(key: &str) -> bool {
+    SIZE_SCANS.lock().unwrap().contains_key(key)
+}
+
+// 只读取已缓存或扫描中的大小，不触发新的扫描
+pub(crate) fn peek_size_bytes(key: &str) -> Option<u64> {
+    let scans = SIZE_SCANS.lock().unwrap();
+    if let Some(entry) = SIZE_CACHE.lock().unwrap().get(key) {
+        return entry.bytes;
+    }
+    scans.get(key).map(|progress| progress.load(Ordering::Relaxed))
+}
+
+fn partial_size_entry(progress: &AtomicU64) -> SizeEntry {
+    SizeEntry {
+        bytes: Some(progress.load(Ordering::Relaxed)),
+        scanned_at: chrono::Local::now().timestamp(),
+        previous_session: false,
+        scanning: true,
+    }
+}
+
+pub(crate) fn cached_directory_size(key: &str) -> SizeEntry {
+    cached_background_size(key, scan_directory_size)
+}
+
+// scan 在后台线程中执行，扫描过程中把已统计的字节数写入 progress
+pub(crate) fn cached_background_size(
+    key: &str,
+    scan: impl FnOnce(&str, &AtomicU64) -> Option<u64> + Send + 'static,
+) -> SizeEntry {
+    // 先锁扫描表再锁缓存，与扫描线程完成时的加锁顺序一致
+    let (progress, done_rx) = {
+        let mut scans = SIZE_SCANS.lock().unwrap();
+        if let Some(entry) = SIZE_CACHE.lock().unwrap().get(key) {
+            return entry.clone();
+        }
+        if let Some(progress) = scans.get(key) {
+            return partial_size_entry(progress);
+        }
+
+        let progress = Arc::new(AtomicU64::new(0));
+        scans.insert(key.to_string(), progress.clone());
+
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let scan_key = key.to_string();
+        let scan_progress = progress.clone();
+        std::thread::spawn(move || {
+            let entry = SizeEntry {
+                bytes: scan(&scan_key, &scan_progress),
+                scanned_at: chrono::Local::now().timestamp(),
+                previous_session: false,
+                scanning: false,
+            };
+
+            let mut scans = SIZE_SCANS.lock().unwrap();
+            let mut cache = SIZE_CACHE.lock().unwrap();
+            cache.insert(scan_key.clone(), entry.clone());
+            save_size_cache(&cache);
+            scans.remove(&scan_key);
+            let _ = done_tx.send(entry);
+        });
+        (progress, done_rx)
+    };
+
+    match done_rx.recv_timeout(SCAN_WAIT) {
+        Ok(entry) => entry,
+        Err(_) => partial_size_entry(&progress),
+    }
+}
+
+pub(crate) fn invalidate_cached_size(key: &str) {
+    let mut cache = SIZE_CACHE.lock().unwrap();
+    if cache.remove(key).is_some() {
+        save_size_cache(&cache);
+    }
+}
+
+// 目录快照 - 清理前后各扫描一次，对比任务的实际效果
+const SNAPSHOT_TOP_FOLDERS: usize = 5;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DirSnapshot {
+    pub(crate) bytes: u64,
+    pub(crate) files: u64,
+    pub(crate) top_folders: Vec<(String, u64)>, // (子目录名, 大小)，按大小降序
+}
+
+pub(crate) fn snapshot_directory(path: &str) -> Option<DirSnapshot> {
+    let expanded_path = expand_environment_variables(path);
+    let path = Path::new(&expanded_path);
+    if !path.exists() {
+        return None;
+    }
+
+    // 统计目录下的字节数和文件数，不进入符号链接和内置禁区
+    fn totals(dir: &Path, throttle: &mut ScanThrottle) -> (u64, u64) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return (0, 0);
+        };
+
+        let mut bytes = 0;
+        let mut files = 0;
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            throttle.tick();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_symlink() {
+                continue;
+            }
+            if file_type.is_dir() {
+                if never_touch_rule(&entry.path()).is_none() {
+                    let (dir_bytes, dir_files) = totals(&entry.path(), throttle);
+                    bytes += dir_bytes;
+                    files += dir_files;
+                }
+            } else {
+                bytes += entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+                files += 1;
+            }
+        }
+        (bytes, files)
+    }
+
+    if path.is_file() {
+        let bytes = fs::metadata(path).ok()?.len();
+        return Some(DirSnapshot { bytes, files: 1, top_folders: Vec::new() });
+    }
+
+    let tuning = scan_tuning(&expanded_path);
+    let _permit = ScanPermit::acquire(&expanded_path, tuning.max_workers);
+    let mut throttle = ScanThrottle::new(tuning);
+    let mut snapshot = DirSnapshot::default();
+    for entry in fs::read_dir(path).ok()?.filter_map(|entry| entry.ok()) {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            let (bytes, files) = totals(&entry.path(), &mut throttle);
+            snapshot.bytes += bytes;
+            snapshot.files += files;
+            snapshot.top_folders.push((entry.file_name().to_string_lossy().to_string(), bytes));
+        } else {
+            snapshot.bytes += entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            snapshot.files += 1;
+        }
+    }
+
+    snapshot.top_folders.sort_by_key(|(_, bytes)| std::cmp::Reverse(*bytes));
+    snapshot.top_folders.truncate(SNAPSHOT_TOP_FOLDERS);
+    Some(snapshot)
+}
+
+// 上次清理以来新增的内容：创建或修改时间不早于上次清理的文件
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct NewContent {
+    pub(crate) bytes: u64,
+    pub(crate) files: u64,
+}
+
+pub(crate) fn new_content_since(path: &Path, since: i64) -> NewContent {
+    // 复制来的文件保留原修改时间，但创建时间是复制的时间，取两者中较晚的
+    fn file_time(metadata: &fs::Metadata) -> i64 {
+        [metadata.created(), metadata.modified()]
+            .into_iter()
+            .filter_map(|time| time.ok()?.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .max()
+            .unwrap_or(0)
+    }
+
+    // 与快照相同，不进入符号链接和内置禁区
+    fn walk(path: &Path, since: i64, throttle: &mut ScanThrottle, content: &mut NewContent) {
+        let Ok(metadata) = fs::symlink_metadata(path) else {
+            return;
+        };
+        if metadata.file_type().is_symlink() {
+            return;
+        }
+        if metadata.is_dir() {
+            if never_touch_rule(path).is_some() {
+                return;
+            }
+            let Ok(entries) = fs::read_dir(path) else {
+                return;
+            };
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                throttle.tick();
+                walk(&entry.path(), since, throttle, content);
+            }
+        } else if file_time(&metadata) >= since {
+            content.bytes += metadata.len();
+            content.files += 1;
+        }
+    }
+
+    let text = path.to_string_lossy();
+    let tuning = scan_tuning(&text);
+    let _permit = ScanPermit::acquire(&text, tuning.max_workers);
+    let mut throttle = ScanThrottle::new(tuning);
+    let mut content = NewContent::default();
+    walk(path, since, &mut throttle, &mut content);
+    content
+}
+
+// 子项中上次清理以来新增的内容；只清理文件集合的子项只统计这些文件
+pub(crate) fn item_new_content(item: &TargetItem, since: i64) -> NewContent {
+    if item.files.is_empty() {
+        return new_content_since(&item.path, since);
+    }
+    item.files.iter().fold(NewContent::default(), |mut total, file| {
+        let content = new_content_since(file, since);
+        total.bytes += content.bytes;
+        total.files += content.files;
+        total
+    })
+}
+
+// 目录增长监测 - 每天记录一次常见数据目录下各子目录的大小，短时间内异常增长时提醒
+const GROWTH_FILE: &str = "wincleaner-growth.json";
+const GROWTH_ROOTS: &[&str] = &["%LOCALAPPDATA%", "%APPDATA%", "%PROGRAMDATA%"];
+const GROWTH_SCAN_INTERVAL: i64 = 86400; // 两次记录的最短间隔（秒）
+const GROWTH_WINDOW: i64 = 7 * 86400; // 增长量的比较窗口（秒）
+const GROWTH_MAX_SAMPLES: usize = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+struct GrowthSample {
+    pub(crate) at: i64, // Unix时间戳（秒）
+    pub(crate) bytes: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct GrowthAlert {
+    pub(crate) path: String, // 环境变量写法，如 %LOCALAPPDATA%\FooApp
+    pub(crate) grown: u64,
+    pub(crate) days: i64,
+    pub(crate) current: u64,
+}
+
+impl GrowthAlert {
+    // 在已忽略列表中使用的标识
+    pub(crate) fn key(&self) -> String {
+        format!("growth:{}", self.path)
+    }
+
+    pub(crate) fn summary(&self) -> String {
+        format!(
+            "{} 天内增长 {}（现 {}）",
+            self.days,
+            format_size(self.grown),
+            format_size(self.current)
+        )
+    }
+}
+
+// 键为环境变量写法的目录路径，值为按时间排列的大小记录
+static GROWTH: Lazy<Mutex<BTreeMap<String, Vec<GrowthSample>>>> = Lazy::new(|| {
+    let samples = fs::read_to_string(GROWTH_FILE)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    Mutex::new(samples)
+});
+
+// 扫描监测根目录下的每个子目录并记录大小；距上次记录不足一天时跳过
+pub(crate) fn record_growth_samples() {
+    let now = chrono::Local::now().timestamp();
+    let last_recorded = GROWTH
+        .lock()
+        .unwrap()
+        .values()
+        .filter_map(|samples| samples.last().map(|sample| sample.at))
+        .max();
+    if last_recorded.is_some_and(|at| now - at < GROWTH_SCAN_INTERVAL) {
+        return;
+    }
+
+    let mut current = Vec::new();
+    for root in GROWTH_ROOTS {
+        let Ok(entries) = fs::read_dir(expand_environment_variables(root)) else {
+            continue;
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let is_dir = entry.file_type().map(|file_type| file_type.is_dir()).unwrap_or(false);
+            if !is_dir || never_touch_rule(&entry.path()).is_some() {
+                continue;
+            }
+            if let Some(bytes) = get_directory_size(&entry.path().to_string_lossy()) {
+                let key = format!("{}\\{}", root, entry.file_name().to_string_lossy());
+                current.push((key, bytes));
+            }
+        }
+    }
+
+    let mut growth = GROWTH.lock().unwrap();
+    // 已删除的目录不再保留记录
+    growth.retain(|key, _| current.iter().any(|(path, _)| path == key));
+    for (key, bytes) in current {
+        let samples = growth.entry(key).or_default();
+        samples.push(GrowthSample { at: now, bytes });
+        if samples.len() > GROWTH_MAX_SAMPLES {
+            samples.remove(0);
+        }
+    }
+
+    match serde_json::to_string(&*growth) {
+        Ok(content) => {
+            if let Err(e) = fs::write(GROWTH_FILE, content) {
+                log(&format!("增长记录写入失败: {}", e));
+            }
+        }
+        Err(e) => log(&format!("增长记录序列化失败: {}", e)),
+    }
+    log(&format!("记录了 {} 个目录的大小", growth.len()));
+}
+
+// 比较窗口内最早和最新的记录，增长超过阈值的目录按增长量降序排列
+// 已忽略的目录只有在忽略后又增长超过阈值时才重新提醒
+pub(crate) fn growth_alerts() -> Vec<GrowthAlert> {
+    let Some(threshold) = parse_size(&SETTINGS.growth_alert_threshold) else {
+        return Vec::new();
+    };
+    let acknowledgements = load_acknowledgements();
+
+    let mut alerts = GROWTH
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|(path, samples)| {
+            let latest = samples.last()?;
+            let baseline = samples.iter().find(|sample| latest.at - sample.at <= GROWTH_WINDOW)?;
+            let grown = latest.bytes.saturating_sub(baseline.bytes);
+            (baseline.at < latest.at && grown >= threshold).then(|| GrowthAlert {
+                path: path.clone(),
+                grown,
+                days: ((latest.at - baseline.at) / 86400).max(1),
+                current: latest.bytes,
+            })
+        })
+        .filter(|alert| {
+            let key = alert.key();
+            !acknowledgements
+                .iter()
+                .any(|ack| ack.key == key && alert.current < ack.bytes.saturating_add(threshold))
+        })
+        .collect::<Vec<_>>();
+    alerts.sort_by_key(|alert| std::cmp::Reverse(alert.grown));
+    alerts
+}
+
+// 已忽略的提醒 - 用户确认正常的提醒不再显示，之后又增长超过阈值时重新提醒
+const ACKNOWLEDGED_FILE: &str = "wincleaner-acknowledged.json";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Acknowledgement {
+    pub(crate) key: String, // 提醒标识，如 "growth:%LOCALAPPDATA%\FooApp"
+    pub(crate) acknowledged_at: i64, // Unix时间戳（秒）
+    pub(crate) bytes: u64, // 忽略时的大小
+}
+
+impl Acknowledgement {
+    // 去掉类型前缀后的显示名称
+    pub(crate) fn target(&self) -> &str {
+        self.key.split_once(':').map_or(&self.key, |(_, target)| target)
+    }
+
+    pub(crate) fn time_text(&self) -> String {
+        chrono::DateTime::from_timestamp(self.acknowledged_at, 0)
+            .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    }
+}
+
+pub(crate) fn load_acknowledgements() -> Vec<Acknowledgement> {
+    fs::read_to_string(ACKNOWLEDGED_FILE)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_acknowledgements(acknowledgements: &[Acknowledgement]) {
+    match serde_json::to_string_pretty(acknowledgements) {
+        Ok(content) => {
+            if let Err(e) = fs::write(ACKNOWLEDGED_FILE, content) {
+                log(&format!("已忽略列表写入失败: {}", e));
+            }
+        }
+        Err(e) => log(&format!("已忽略列表序列化失败: {}", e)),
+    }
+}
+
+// 忽略提醒；同一提醒再次忽略时更新记录的大小
+pub(crate) fn acknowledge(key: &str, bytes: u64) {
+    let mut acknowledgements = load_acknowledgements();
+    acknowledgements.retain(|ack| ack.key != key);
+    acknowledgements.push(Acknowledgement {
+        key: key.to_string(),
+        acknowledged_at: chrono::Local::now().timestamp(),
+        bytes,
+    });
+    save_acknowledgements(&acknowledgements);
+    log(&format!("忽略提醒: {}", key));
+}
+
+pub(crate) fn unacknowledge(key: &str) {
+    let mut acknowledgements = load_acknowledgements();
+    acknowledgements.retain(|ack| ack.key != key);
+    save_acknowledgements(&acknowledgements);
+    log(&format!("恢复提醒: {}", key));
+}
+
+// 缓存大小提供者 - 向工具本身查询缓存位置或大小
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SizeProvider {
+    Npm,    // npm config get cache
+    Cargo,  // cargo cache（需要cargo-cache）
+    Docker, // docker system df
+    RecycleBin, // 各磁盘回收站的大小之和
+}
+
+impl SizeProvider {
+    pub(crate) fn query_size(&self) -> Option<u64> {
+        match self {
+            SizeProvider::Npm => {
+                let cache_dir = resolve_tool_path("%NPM_CACHE%")?;
+                get_directory_size(&cache_dir)
+            }
+            SizeProvider::Cargo => {
+                // 输出形如 "Total:      3.38 GB"
+                let output = run_query_command("cargo cache")?;
+                output
+                    .lines()
+                    .find_map(|line| line.trim().strip_prefix("Total:"))
+                    .and_then(|total| parse_size_with(total, SizeUnits::Binary, false))
+            }
+            SizeProvider::Docker => {
+                // 每行一个JSON对象，累加各类型的可回收空间，如 "1.2GB (45%)"
+                let output = run_query_command("docker system df --format \"{{json .}}\"")?;
+                let total = output
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                    .filter_map(|entry| {
+                        let reclaimable = entry["Reclaimable"].as_str()?;
+                        // docker 使用十进制单位
+                        parse_size_with(reclaimable.split(" (").next().unwrap_or(reclaimable), SizeUnits::Decimal, false)
+                    })
+                    .sum();
+                Some(total)
+            }
+            SizeProvider::RecycleBin => Some(trash::recycle_bins().iter().map(|bin| bin.bytes).sum()),
+        }
+    }
+}
+
+// 执行查询类命令并返回标准输出，失败时返回None
+pub(crate) fn run_query_command(command: &str) -> Option<String> {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    match cmd.output() {
+        Ok(output) if output.status.success() => {
+            Some(String::from_utf8_lossy(&output.stdout).to_string())
+        }
+        Ok(output) => {
+            log(&format!(
+                "查询命令失败: {} - {}",
+                command,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+            None
+        }
+        Err(e) => {
+            log(&format!("查询命令无法执行: {} - {}", command, e));
+            None
+        }
+    }
+}
+
+// 大小单位 - 格式化和解析可读的大小；单位制由设置 size_units 决定，小数点和千位分隔符跟随界面语言区域
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeUnits {
+    #[default]
+    Binary, // 1 KB = 1024 B，与资源管理器显示一致
+    Decimal, // 1 kB = 1000 B，与硬盘厂商标称一致
+}
+
+impl SizeUnits {
+    pub(crate) fn base(&self) -> f64 {
+        match self {
+            SizeUnits::Binary => 1024.0,
+            SizeUnits::Decimal => 1000.0,
+        }
+    }
+
+    pub(crate) fn labels(&self) -> [&'static str; 5] {
+        match self {
+            SizeUnits::Binary => ["B", "KB", "MB", "GB", "TB"],
+            SizeUnits::Decimal => ["B", "kB", "MB", "GB", "TB"],
+        }
+    }
+}
+
+// 以逗号作小数点、以句点分隔千位的语言
+const DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "cs", "da", "de", "es", "fi", "fr", "id", "it", "nb", "nl", "pl", "pt", "ru", "sv", "tr", "uk", "vi",
+];
+
+static DECIMAL_COMMA: Lazy<bool> = Lazy::new(|| {
+    let language = LOCALE.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+    DECIMAL_COMMA_LANGUAGES.contains(&language.as_str())
+});
+
+// 格式化文件大小为可读格式
+pub fn format_size(bytes: u64) -> String {
+    format_size_with(bytes, SETTINGS.size_units, *DECIMAL_COMMA)
+}
+
+pub fn format_size_with(bytes: u64, units: SizeUnits, decimal_comma: bool) -> String {
+    let labels = units.labels();
+    let base = units.base();
+    let mut size = bytes as f64;
+    let mut unit_index = 0;
+
+    while size >= base && unit_index < labels.len() - 1 {
+        size /= base;
+        unit_index += 1;
+    }
+
+    if unit_index == 0 {
+        return format!("{} {}", group_digits(bytes, decimal_comma), labels[0]);
+    }
+    let text = format!("{:.1}", size);
+    let (integer, fraction) = text.split_once('.').unwrap_or((&text, "0"));
+    format!(
+        "{}{}{} {}",
+        group_digits(integer.parse().unwrap_or(0), decimal_comma),
+        if decimal_comma { ',' } else { '.' },
+        fraction,
+        labels[unit_index]
+    )
+}
+
+// 整数部分每三位加分隔符
+fn group_digits(value: u64, decimal_comma: bool) -> String {
+    let separator = if decimal_comma { '.' } else { ',' };
+    let digits = value.to_string();
+    let mut grouped = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+// 解析可读的大小（如 "1.5GB"、"500 MB"、"~500MB"、"~1-3GB"），范围取下限，无法解析时返回None
+// KB/MB/GB 按设置的单位制换算，KiB/MiB/GiB 始终按 1024 换算，不带单位时为字节数
+pub fn parse_size(text: &str) -> Option<u64> {
+    parse_size_with(text, SETTINGS.size_units, *DECIMAL_COMMA)
+}
+
+pub fn parse_size_with(text: &str, units: SizeUnits, decimal_comma: bool) -> Option<u64> {
+    let text = text.trim().trim_start_matches('~').trim();
+    let number_end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(text.len());
+    // 同时出现逗号和句点时后出现的是小数点；只有逗号时按语言区域判断，配置中的 "1.5GB" 始终按小数解析
+    let number = &text[..number_end];
+    let comma_is_decimal = match (number.rfind(','), number.rfind('.')) {
+        (Some(comma), Some(dot)) => comma > dot,
+        (Some(_), None) => decimal_comma,
+        _ => false,
+    };
+    let number = if comma_is_decimal {
+        number.replace('.', "").replace(',', ".")
+    } else {
+        number.replace(',', "")
+    };
+    let value: f64 = number.parse().ok()?;
+
+    let unit = text[number_end..]
+        .trim_start_matches(|c: char| c == '-' || c == '.' || c == ',' || c.is_ascii_digit())
+        .trim()
+        .to_ascii_uppercase();
+    let (base, exponent) = match unit.as_str() {
+        "" | "B" | "BYTES" => (1.0, 0),
+        "K" | "KB" => (units.base(), 1),
+        "M" | "MB" => (units.base(), 2),
+        "G" | "GB" => (units.base(), 3),
+        "T" | "TB" => (units.base(), 4),
+        "KIB" => (1024.0, 1),
+        "MIB" => (1024.0, 2),
+        "GIB" => (1024.0, 3),
+        "TIB" => (1024.0, 4),
+        _ => return None,
+    };
+
+    Some((value * base.powi(exponent)) as u64)
+}
+
+// 查询卷的可用空间（字节），root 形如 "C:\\"
+pub(crate) fn volume_free_space(root: &str) -> Option<u64> {
+    volume_space(root).map(|(free, _)| free)
+}
+
+// 查询卷的 (可用空间, 总容量)，单位字节
+#[cfg(windows)]
+pub(crate) fn volume_space(root: &str) -> Option<(u64, u64)> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide_root = std::ffi::OsStr::new(root)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect::<Vec<u16>>();
+    let mut free_bytes = 0u64;
+    let mut total_bytes = 0u64;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide_root.as_ptr(),
+            &mut free_bytes,
+            &mut total_bytes,
+            std::ptr::null_mut(),
+        )
+    };
+
+    (ok != 0).then_some((free_bytes, total_bytes))
+}
+
+#[cfg(not(windows))]
+pub(crate) fn volume_space(_root: &str) -> Option<(u64, u64)> {
+    None
+}
+
+// 系统盘根目录，如 "C:\\"
+pub(crate) fn system_drive_root() -> String {
+    let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+    format!("{}\\", drive)
+}
+
+// 路径所在卷的根目录，如 "D:\\"，非盘符路径返回None
+pub(crate) fn volume_root(path: &str) -> Option<String> {
+    let mut chars = path.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), Some(':')) if letter.is_ascii_alphabetic() => {
+            Some(format!("{}:\\", letter.to_ascii_uppercase()))
+        }
+        _ => None,
+    }
+}
+
+// 本地固定磁盘的根目录，如 ["C:\\", "D:\\"]
+#[cfg(windows)]
+pub(crate) fn local_volumes() -> Vec<String> {
+    use windows_sys::Win32::Storage::FileSystem::{GetDriveTypeW, GetLogicalDriveStringsW};
+    const DRIVE_FIXED: u32 = 3;
+
+    let mut buffer = [0u16; 512];
+    let length =
+        unsafe { GetLogicalDriveStringsW(buffer.len() as u32, buffer.as_mut_ptr()) } as usize;
+    if length == 0 || length > buffer.len() {
+        return Vec::new();
+    }
+
+    buffer[..length]
+        .split(|&ch| ch == 0)
+        .filter(|root| !root.is_empty())
+        .filter(|root| {
+            let wide_root = root
+                .iter()
+                .copied()
+                .chain(std::iter::once(0))
+                .collect::<Vec<u16>>();
+            unsafe { GetDriveTypeW(wide_root.as_ptr()) == DRIVE_FIXED }
+        })
+        .map(String::from_utf16_lossy)
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub(crate) fn local_volumes() -> Vec<String> {
+    Vec::new()
+}
+
+// 磁盘使用情况，单位字节
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DriveUsage {
+    pub(crate) root: String,
+    pub(crate) total: u64,
+    pub(crate) free: u64,
+}
+
+impl DriveUsage {
+    pub(crate) fn used(&self) -> u64 {
+        self.total.saturating_sub(self.free)
+    }
+
+    pub(crate) fn used_percent(&self) -> f32 {
+        self.used() as f32 / self.total.max(1) as f32 * 100.0
+    }
+}
+
+// 各本地固定磁盘的总容量和可用空间
+pub(crate) fn drive_usage() -> Vec<DriveUsage> {
+    local_volumes()
+        .into_iter()
+        .filter_map(|root| volume_space(&root).map(|(free, total)| DriveUsage { root, total, free }))
+        .filter(|drive| drive.total > 0)
+        .collect()
+}
+
+// 磁盘类型 - 机械硬盘随机读取代价高，并行扫描会拖慢整个系统
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum DriveKind {
+    Ssd,
+    Hdd,
+    Unknown, // 网络驱动器、无法查询的设备等
+}
+
+static DRIVE_KINDS: Lazy<Mutex<HashMap<String, DriveKind>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 卷根目录（如 "C:\\"）所在磁盘的类型，每个卷只查询一次
+pub(crate) fn drive_kind(root: &str) -> DriveKind {
+    if let Some(kind) = DRIVE_KINDS.lock().unwrap().get(root) {
+        return *kind;
+    }
+    let kind = detect_drive_kind(root);
+    log(&format!("磁盘类型: {} {:?}", root, kind));
+    DRIVE_KINDS.lock().unwrap().insert(root.to_string(), kind);
+    kind
+}
+
+// 通过 IOCTL_STORAGE_QUERY_PROPERTY 查询是否有寻道开销（机械硬盘）
+#[cfg(windows)]
+fn detect_drive_kind(root: &str) -> DriveKind {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    };
+    use windows_sys::Win32::System::Ioctl::{
+        PropertyStandardQuery, StorageDeviceSeekPenaltyProperty, DEVICE_SEEK_PENALTY_DESCRIPTOR,
+        IOCTL_STORAGE_QUERY_PROPERTY, STORAGE_PROPERTY_QUERY,
+    };
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+
+    // 打开卷设备（如 \\.\C:），查询属性不需要读写权限
+    let device = to_wide(&format!("\\\\.\\{}", root.trim_end_matches('\\')));
+    let handle = unsafe {
+        CreateFileW(
+            device.as_ptr(),
+            0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            std::ptr::null(),
+            OPEN_EXISTING,
+            0,
+            std::ptr::null_mut(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return DriveKind::Unknown;
+    }
+
+    let query = STORAGE_PROPERTY_QUERY {
+        PropertyId: StorageDeviceSeekPenaltyProperty,
+        QueryType: PropertyStandardQuery,
+        AdditionalParameters: [0],
+    };
+    let mut descriptor: DEVICE_SEEK_PENALTY_DESCRIPTOR = unsafe { std::mem::zeroed() };
+    let mut returned = 0u32;
+    let ok = unsafe {
+        let ok = DeviceIoControl(
+            handle,
+            IOCTL_STORAGE_QUERY_PROPERTY,
+            &query as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<STORAGE_PROPERTY_QUERY>() as u32,
+            &mut descriptor as *mut _ as *mut std::ffi::c_void,
+            std::mem::size_of::<DEVICE_SEEK_PENALTY_DESCRIPTOR>() as u32,
+            &mut returned,
+            std::ptr::null_mut(),
+        );
+        CloseHandle(handle);
+        ok
+    };
+
+    match (ok != 0, descriptor.IncursSeekPenalty != 0) {
+        (false, _) => DriveKind::Unknown,
+        (true, true) => DriveKind::Hdd,
+        (true, false) => DriveKind::Ssd,
+    }
+}
+
+#[cfg(not(windows))]
+fn detect_drive_kind(_root: &str) -> DriveKind {
+    DriveKind::Unknown
+}
+
+// 记录任务涉及的各卷（含系统盘）当前的可用空间
+pub(crate) fn free_space_snapshot(tasks: &[CleanTask]) -> Vec<(String, u64)> {
+    let mut roots = vec![system_drive_root().to_ascii_uppercase()];
+    for root in tasks
+        .iter()
+        .filter_map(|task| task.get_expanded_path())
+        .filter_map(|path| volume_root(&path))
+    {
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+
+    roots
+        .into_iter()
+        .filter_map(|root| volume_free_space(&root).map(|bytes| (root, bytes)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_size_with(0, SizeUnits::Binary, false), "0 B");
+        assert_eq!(format_size_with(1536, SizeUnits::Binary, false), "1.5 KB");
+        assert_eq!(format_size_with(1500, SizeUnits::Decimal, false), "1.5 kB");
+        assert_eq!(format_size_with(1536, SizeUnits::Binary, true), "1,5 KB");
+        assert_eq!(
+            format_size_with(2048 * 1024u64.pow(4), SizeUnits::Binary, false),
+            "2,048.0 TB"
+        );
+        assert_eq!(
+            format_size_with(2048 * 1024u64.pow(4), SizeUnits::Binary, true),
+            "2.048,0 TB"
+        );
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size_with("1.5GB", SizeUnits::Binary, false), Some(1610612736));
+        assert_eq!(parse_size_with("500 MB", SizeUnits::Decimal, false), Some(500_000_000));
+        assert_eq!(parse_size_with("~1-3GB", SizeUnits::Binary, false), Some(1073741824));
+        assert_eq!(parse_size_with("2 GiB", SizeUnits::Decimal, false), Some(2147483648));
+        assert_eq!(parse_size_with("1,5 GB", SizeUnits::Binary, true), Some(1610612736));
+        assert_eq!(parse_size_with("1,024 MB", SizeUnits::Binary, false), Some(1073741824));
+        assert_eq!(parse_size_with("12345", SizeUnits::Binary, false), Some(12345));
+        assert_eq!(parse_size_with("lots", SizeUnits::Binary, false), None);
+    }
+}
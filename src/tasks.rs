@@ -0,0 +1,2075 @@
+// 清理任务 - 任务定义和内置规则、环境变量展开、删除目标的安全检查，以及新建和导入规则
+use crate::cleaner;
+use crate::config::{slugify, TaskDefinition, LOCALE, SETTINGS};
+use crate::glob;
+use crate::engine::{
+    boot_clean_command, running_process_names, ItemAnalyzer, TaskSchedule, NOTHING_TO_CLEAN,
+};
+use crate::logging::log;
+use crate::sizing::{
+    cached_background_size, cached_directory_size, cached_size, format_size, format_size_with,
+    get_directory_size, invalidate_cached_size, parse_size, parse_size_with, peek_size_bytes,
+    run_query_command, SizeEntry, SizeProvider, SizeUnits,
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum CleanCategory {
+    #[default]
+    DevTools,
+    AppCache,
+    Browser,
+    System,
+    Custom, // 用户自定义分类
+}
+
+impl CleanCategory {
+    pub(crate) const ALL: [CleanCategory; 5] = [
+        CleanCategory::DevTools,
+        CleanCategory::AppCache,
+        CleanCategory::Browser,
+        CleanCategory::System,
+        CleanCategory::Custom,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            CleanCategory::DevTools => "开发工具",
+            CleanCategory::AppCache => "应用缓存",
+            CleanCategory::Browser => "浏览器",
+            CleanCategory::System => "系统清理",
+            CleanCategory::Custom => "自定义规则",
+        }
+    }
+}
+
+// 风险等级：Medium 需要确认，High 需要额外勾选确认且批量时默认不执行
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+pub enum RiskLevel {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+// 删除方式：永久删除，或移入回收站以便还原
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeleteMode {
+    Permanent,
+    RecycleBin,
+}
+
+impl RiskLevel {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            RiskLevel::Low => "低风险",
+            RiskLevel::Medium => "中风险",
+            RiskLevel::High => "高风险",
+        }
+    }
+}
+
+// 预估可清理大小。配置中写作字符串以兼容旧版："auto"（扫描 path_check 或向工具查询）、
+// "~500MB"（固定预估，范围如 "~1-3GB" 取下限）、"~可变"；也可直接写字节数
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "EstimatedSizeText", into = "EstimatedSizeText")]
+pub enum EstimatedSize {
+    Auto,
+    Fixed(u64),
+    Variable,
+    #[default]
+    Unknown,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum EstimatedSizeText {
+    Bytes(u64),
+    Text(String),
+}
+
+impl From<EstimatedSizeText> for EstimatedSize {
+    fn from(value: EstimatedSizeText) -> Self {
+        match value {
+            EstimatedSizeText::Bytes(bytes) => EstimatedSize::Fixed(bytes),
+            EstimatedSizeText::Text(text) => {
+                let text = text.trim();
+                if text.eq_ignore_ascii_case("auto") {
+                    EstimatedSize::Auto
+                } else if text.is_empty() {
+                    EstimatedSize::Unknown
+                } else if let Some(bytes) = parse_size_with(text, SizeUnits::Binary, false) {
+                    // 规则中的预估值一直按 1024 换算，不受 size_units 影响
+                    EstimatedSize::Fixed(bytes)
+                } else if matches!(text.trim_start_matches('~').trim(), "可变" | "variable") {
+                    EstimatedSize::Variable
+                } else {
+                    EstimatedSize::Unknown
+                }
+            }
+        }
+    }
+}
+
+impl From<EstimatedSize> for EstimatedSizeText {
+    fn from(value: EstimatedSize) -> Self {
+        EstimatedSizeText::Text(match value {
+            EstimatedSize::Auto => "auto".to_string(),
+            EstimatedSize::Fixed(bytes) => {
+                format!("~{}", format_size_with(bytes, SizeUnits::Binary, false).replace(".0 ", ""))
+            }
+            EstimatedSize::Variable => "~可变".to_string(),
+            EstimatedSize::Unknown => String::new(),
+        })
+    }
+}
+
+impl EstimatedSize {
+    pub(crate) fn is_unknown(&self) -> bool {
+        *self == EstimatedSize::Unknown
+    }
+
+    // 没有实测结果时显示的文字
+    pub(crate) fn text(&self) -> Option<String> {
+        match self {
+            EstimatedSize::Fixed(bytes) => Some(format!("~{}", format_size(*bytes))),
+            EstimatedSize::Variable => Some("可变".to_string()),
+            EstimatedSize::Auto | EstimatedSize::Unknown => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct CleanTask {
+    // 稳定的任务ID，选择集合与统计均以此为键；自定义规则未填写时由名称生成
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub category: CleanCategory,
+    pub command: String,
+    pub path_check: Option<String>,
+    pub requires_confirmation: bool,
+    #[serde(default)]
+    pub risk: RiskLevel,
+    // 旧版配置字段，加载时折算为 risk = "High"
+    #[serde(default, skip_serializing)]
+    pub dangerous: bool,
+    #[serde(default)]
+    pub requires_admin: bool, // 需要管理员权限才能执行
+    #[serde(default, skip_serializing_if = "EstimatedSize::is_unknown")]
+    pub estimated_size: EstimatedSize,
+    pub icon: Option<String>,
+    // 允许逐项选择目标目录下的子项进行清理
+    #[serde(default)]
+    pub item_selection: bool,
+    // 逐项选择时的子项分析器，用于标注并预选可安全清理的项
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_analyzer: Option<ItemAnalyzer>,
+    // 无路径检查的任务可通过工具自身查询缓存大小
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_provider: Option<SizeProvider>,
+    // 视为成功的退出码，如 "0-7" 或 "0, 3010"；缺省时只有 0 表示成功
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success_codes: Option<String>,
+    // 已知退出码的说明，键为退出码
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub exit_messages: BTreeMap<String, String>,
+    // 命令的工作目录（支持环境变量），缺省时沿用程序启动时的当前目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    // 执行命令时额外设置的环境变量（值支持环境变量）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    // 使用最小环境执行：只保留系统必需的变量，PATH 仅包含系统目录
+    #[serde(default)]
+    pub minimal_env: bool,
+    // 路径不存在或目录为空时计为"跳过（无需清理）"而不是失败，适合未必安装的程序
+    #[serde(default)]
+    pub skip_if_missing: bool,
+    // 执行前必须已退出的程序进程名，如 "OUTLOOK.EXE"；其中任一正在运行时拒绝执行
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_closed: Vec<String>,
+    // 所属程序的进程名（多个用 ; 分隔），如 "Code.exe"；清理前检测到正在运行时提示并可先关闭程序，
+    // 与 requires_closed 不同，仍可选择继续清理
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_check: Option<String>,
+    // 删除方式："Permanent" 或 "RecycleBin"，未设置时高风险任务移入回收站
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delete_mode: Option<DeleteMode>,
+    // 备注，显示在任务卡片中并参与搜索
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    // 标签，如 "work"、"weekly"，可用于搜索和按标签批量选择
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    // 关联的程序（支持环境变量，多个候选用 ; 分隔），存在时卡片显示程序图标
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_exe: Option<String>,
+    // 所属工具的检测方式：PATH 中的命令名或程序目录（支持环境变量），多个候选用 ; 分隔；
+    // 都不存在时视为未安装，任务默认不显示在列表中
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detect: Option<String>,
+    // 浏览器缓存任务：逐个配置文件清空缓存目录，代替 command 执行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser: Option<Browser>,
+    // 停止残留的构建守护进程（Gradle、Kotlin、adb、工作区已删除的 Node.js），代替 command 执行
+    #[serde(default)]
+    pub stop_daemons: bool,
+    // 通配符路径模式（支持环境变量），如 "%TEMP%\\**\\*.tmp"；设置后删除匹配的文件，代替 command 执行
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+    // 只删除最后修改时间早于这些天数的文件，适用于内置删除引擎执行的删除命令和 patterns
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub older_than_days: Option<u64>,
+    // 只删除不小于该大小的文件，如 "100 MB"，适用范围同 older_than_days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_file_size: Option<String>,
+    // 定期执行频率："daily"、"weekly" 或 "monthly"，由计划任务在后台执行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<TaskSchedule>,
+    // 批量清理时排在这些任务（名称或ID）之后执行，只影响同一批次中的顺序
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub run_after: Vec<String>,
+    // 配置覆盖来源说明（运行时生成，不写入配置）
+    #[serde(skip)]
+    pub override_source: Option<String>,
+}
+
+impl CleanTask {
+    // 旧版配置中 dangerous = true 的规则视为高风险
+    pub(crate) fn apply_legacy_dangerous(&mut self) {
+        if self.dangerous {
+            log(&format!("配置警告: {} 使用了已弃用的 dangerous 字段，请改用 risk", self.name));
+            self.risk = self.risk.max(RiskLevel::High);
+            self.dangerous = false;
+        }
+    }
+
+    // 搜索匹配：名称、描述、备注和标签，不区分大小写；以 # 开头时只匹配标签
+    pub(crate) fn matches_search(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if let Some(tag) = query.strip_prefix('#') {
+            return self.has_tag(tag);
+        }
+        let contains = |text: &str| text.to_lowercase().contains(&query);
+        contains(&self.name)
+            || contains(&self.description)
+            || self.notes.as_deref().is_some_and(contains)
+            || self.tags.iter().any(|tag| contains(tag))
+    }
+
+    pub(crate) fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own| own.eq_ignore_ascii_case(tag.trim()))
+    }
+
+    // detect 中任一候选存在即视为已安装，未设置 detect 的任务总是显示
+    pub(crate) fn tool_installed(&self) -> bool {
+        self.detect.as_deref().is_none_or(|detect| {
+            detect
+                .split(';')
+                .map(str::trim)
+                .filter(|candidate| !candidate.is_empty())
+                .any(tool_present)
+        })
+    }
+
+    // requires_closed 中正在运行的程序
+    pub(crate) fn running_blockers(&self) -> Vec<String> {
+        if self.requires_closed.is_empty() {
+            return Vec::new();
+        }
+        let running = running_process_names();
+        self.requires_closed
+            .iter()
+            .filter(|name| running.contains(&name.trim().to_lowercase()))
+            .cloned()
+            .collect()
+    }
+
+    // process_check 中正在运行的程序
+    pub(crate) fn running_owner_processes(&self) -> Vec<String> {
+        let names = self
+            .process_check
+            .as_deref()
+            .unwrap_or_default()
+            .split(';')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            return Vec::new();
+        }
+        let running = running_process_names();
+        names
+            .into_iter()
+            .filter(|name| running.contains(&name.to_lowercase()))
+            .map(str::to_string)
+            .collect()
+    }
+
+    // older_than_days 和 min_file_size 组成的文件筛选，min_file_size 无法解析时返回错误
+    pub(crate) fn file_filter(&self) -> Result<cleaner::FileFilter, String> {
+        let min_size = match &self.min_file_size {
+            Some(text) => Some(parse_size(text).ok_or_else(|| format!("无法解析 min_file_size: {}", text))?),
+            None => None,
+        };
+        Ok(cleaner::FileFilter {
+            min_age: self
+                .older_than_days
+                .map(|days| std::time::Duration::from_secs(days * 24 * 60 * 60)),
+            min_size,
+        })
+    }
+
+    // 展开各通配符模式，内置禁区中的文件和不符合文件筛选的文件不计入；结果中的 pattern 为未展开环境变量的原始模式
+    pub(crate) fn pattern_matches(&self, cancel: &AtomicBool) -> Vec<glob::PatternMatch> {
+        let filter = self.file_filter().unwrap_or_default();
+        self.patterns
+            .iter()
+            .map(|pattern| match_pattern(pattern, &filter, cancel))
+            .collect()
+    }
+
+    // 通配符任务的匹配大小，每个模式匹配完成后把累计大小写入 progress，供后台扫描显示部分结果
+    fn pattern_size(&self, progress: &AtomicU64) -> Option<u64> {
+        let filter = self.file_filter().unwrap_or_default();
+        let cancel = AtomicBool::new(false);
+        for pattern in &self.patterns {
+            let matched = match_pattern(pattern, &filter, &cancel);
+            progress.fetch_add(matched.total_bytes(), Ordering::Relaxed);
+        }
+        Some(progress.load(Ordering::Relaxed))
+    }
+
+    // run_after 中是否引用了 other（按 ID 或名称）
+    pub(crate) fn runs_after(&self, other: &CleanTask) -> bool {
+        self.run_after
+            .iter()
+            .any(|dependency| *dependency == other.id || *dependency == other.name)
+    }
+
+    // 没有整体清理命令、只能逐项选择清理的任务
+    pub(crate) fn is_item_only(&self) -> bool {
+        self.item_selection && self.command.trim().is_empty()
+    }
+
+    // 退出码是否表示成功，success_codes 中无法解析的部分会被忽略
+    pub(crate) fn is_success_code(&self, code: i32) -> bool {
+        let Some(spec) = &self.success_codes else {
+            return code == 0;
+        };
+
+        spec.split(',').map(str::trim).any(|part| {
+            // 跳过开头的负号，以便支持 "-1" 这样的单个负值
+            match part.get(1..).and_then(|rest| rest.split_once('-')) {
+                Some((start, end)) => {
+                    let start = format!("{}{}", &part[..1], start);
+                    matches!(
+                        (start.trim().parse::<i32>(), end.trim().parse::<i32>()),
+                        (Ok(start), Ok(end)) if (start..=end).contains(&code)
+                    )
+                }
+                None => part.parse::<i32>() == Ok(code),
+            }
+        })
+    }
+
+    // 按任务配置设置命令的工作目录和环境变量
+    pub(crate) fn configure_command(&self, cmd: &mut Command) {
+        if self.minimal_env {
+            // 最小环境只保留系统必需的变量，避免用户 PATH 中的同名工具被优先调用
+            const KEPT_VARS: &[&str] = &[
+                "SystemRoot", "windir", "SystemDrive", "ComSpec", "PATHEXT", "TEMP", "TMP",
+                "USERPROFILE", "USERNAME", "APPDATA", "LOCALAPPDATA", "PROGRAMDATA",
+                "ProgramFiles", "ProgramFiles(x86)", "NUMBER_OF_PROCESSORS", "PROCESSOR_ARCHITECTURE",
+            ];
+            cmd.env_clear();
+            for name in KEPT_VARS {
+                if let Ok(value) = std::env::var(name) {
+                    cmd.env(name, value);
+                }
+            }
+            cmd.env(
+                "PATH",
+                expand_environment_variables(
+                    "%SystemRoot%\\System32;%SystemRoot%;%SystemRoot%\\System32\\WindowsPowerShell\\v1.0",
+                ),
+            );
+        }
+
+        for (name, value) in &self.env {
+            cmd.env(name, expand_environment_variables(value));
+        }
+
+        if let Some(working_dir) = &self.working_dir {
+            cmd.current_dir(expand_environment_variables(working_dir));
+        }
+    }
+
+    // 获取展开后的路径检查
+    pub(crate) fn get_expanded_path(&self) -> Option<String> {
+        self.path_check.as_ref().map(|path| expand_environment_variables(path))
+    }
+    
+    // 自动检测大小的缓存键：工具查询或展开后的路径
+    pub(crate) fn size_cache_key(&self) -> Option<String> {
+        if let Some(browser) = self.browser {
+            return Some(format!("browser:{:?}", browser));
+        }
+        if let Some(provider) = self.size_provider {
+            return Some(format!("provider:{:?}", provider));
+        }
+        if !self.patterns.is_empty() {
+            // 筛选条件不同时匹配的文件不同，分别缓存
+            return Some(format!(
+                "patterns:{}:{:?}:{:?}",
+                self.patterns.join(";"),
+                self.older_than_days,
+                self.min_file_size
+            ));
+        }
+        if self.estimated_size == EstimatedSize::Auto {
+            return self.get_expanded_path();
+        }
+        None
+    }
+
+    // 获取缓存的扫描结果，缓存缺失时立即扫描（目录和通配符扫描超时后返回部分结果）
+    pub(crate) fn get_size_entry(&self) -> Option<SizeEntry> {
+        let key = self.size_cache_key()?;
+        if !self.patterns.is_empty() {
+            let task = self.clone();
+            return Some(cached_background_size(&key, move |_, progress| task.pattern_size(progress)));
+        }
+        Some(match (self.browser, self.size_provider) {
+            (Some(browser), _) => cached_size(&key, || Some(browser.cache_size())),
+            (None, Some(provider)) => cached_size(&key, || provider.query_size()),
+            (None, None) => cached_directory_size(&key),
+        })
+    }
+
+    // 丢弃缓存的扫描结果，下次显示时重新扫描
+    pub(crate) fn invalidate_size(&self) {
+        if let Some(key) = self.size_cache_key() {
+            invalidate_cached_size(&key);
+        }
+    }
+
+    // 删除方式，未设置时高风险任务移入回收站
+    pub(crate) fn delete_mode(&self) -> DeleteMode {
+        match self.delete_mode {
+            Some(mode) => mode,
+            None if self.risk >= RiskLevel::High => DeleteMode::RecycleBin,
+            None => DeleteMode::Permanent,
+        }
+    }
+
+    // 整体清理是否移入回收站：只有内置删除引擎能识别的目录删除命令、浏览器缓存和通配符任务可以移入回收站
+    pub(crate) fn recycles(&self) -> bool {
+        self.delete_mode() == DeleteMode::RecycleBin
+            && (self.browser.is_some()
+                || !self.patterns.is_empty()
+                || cleaner::parse_delete_command(&self.command).is_some())
+    }
+
+    // 实测大小超过永久删除阈值时返回该大小，移入回收站的任务可以还原，不受限制。
+    // 只读取已缓存的大小，不触发扫描或工具查询；需要时先用 prescan_uncached 扫描
+    pub(crate) fn large_delete_size(&self) -> Option<u64> {
+        if self.recycles() {
+            return None;
+        }
+        let threshold = parse_size(&SETTINGS.large_delete_threshold)?;
+        let bytes = peek_size_bytes(&self.size_cache_key()?)?;
+        (bytes > threshold).then_some(bytes)
+    }
+
+    // 设置了 skip_if_missing 且因路径不存在或目录为空而未执行，计为跳过而不是失败
+    pub(crate) fn skipped_by(&self, error: &str) -> bool {
+        self.skip_if_missing && error.ends_with(NOTHING_TO_CLEAN)
+    }
+
+    // 执行前是否必须经过用户确认
+    pub(crate) fn needs_confirmation(&self) -> bool {
+        self.always_needs_confirmation() || self.large_delete_size().is_some()
+    }
+
+    // 由规则本身决定、与扫描到的大小无关的确认条件
+    pub(crate) fn always_needs_confirmation(&self) -> bool {
+        // 通配符任务总是确认，以便在确认弹窗中查看各模式的匹配结果
+        self.requires_confirmation || self.risk >= RiskLevel::Medium || !self.patterns.is_empty()
+    }
+
+    // 获取实际大小，支持自动检测
+    pub(crate) fn get_actual_size(&self) -> Option<String> {
+        // 优先使用扫描或工具查询到的真实大小，工具查询失败时回退到预估值
+        if let Some(entry) = self.get_size_entry() {
+            match entry.bytes {
+                Some(bytes) if entry.scanning => {
+                    return Some(format!("≥ {}, 扫描中…", format_size(bytes)));
+                }
+                Some(bytes) => return Some(format_size(bytes)),
+                None if self.size_provider.is_none() => return None,
+                None => {}
+            }
+        }
+        self.estimated_size.text()
+    }
+
+    // 获取可用于求和的预估字节数
+    pub(crate) fn get_estimated_bytes(&self) -> Option<u64> {
+        if let Some(bytes) = self.get_size_entry().and_then(|entry| entry.bytes) {
+            return Some(bytes);
+        }
+
+        match self.estimated_size {
+            EstimatedSize::Fixed(bytes) => Some(bytes),
+            EstimatedSize::Auto | EstimatedSize::Variable | EstimatedSize::Unknown => None,
+        }
+    }
+}
+
+// 展开一个通配符模式，去掉禁区内和不满足文件筛选条件的文件
+fn match_pattern(pattern: &str, filter: &cleaner::FileFilter, cancel: &AtomicBool) -> glob::PatternMatch {
+    let mut matched = glob::expand_pattern(&expand_environment_variables(pattern), cancel);
+    matched.files.retain(|(file, _)| never_touch_rule(file).is_none());
+    if filter.is_active() {
+        matched
+            .files
+            .retain(|(file, _)| fs::metadata(file).is_ok_and(|metadata| filter.matches(&metadata)));
+    }
+    matched.pattern = pattern.to_string();
+    matched
+}
+
+// 扩展环境变量
+pub fn expand_environment_variables(path: &str) -> String {
+    if !path.contains('%') {
+        return path.to_string();
+    }
+    
+    // 获取所有常用Windows环境变量
+    let env_vars = [
+        ("%USERPROFILE%", std::env::var("USERPROFILE").unwrap_or_default()),
+        ("%APPDATA%", std::env::var("APPDATA").unwrap_or_default()),
+        ("%LOCALAPPDATA%", std::env::var("LOCALAPPDATA").unwrap_or_default()),
+        ("%TEMP%", std::env::var("TEMP").unwrap_or_default()),
+        ("%TMP%", std::env::var("TMP").unwrap_or_default()),
+        ("%PROGRAMFILES%", std::env::var("PROGRAMFILES").unwrap_or_default()),
+        ("%PROGRAMFILES(X86)%", std::env::var("PROGRAMFILES(X86)").unwrap_or_default()),
+        ("%SYSTEMDRIVE%", std::env::var("SYSTEMDRIVE").unwrap_or_default()),
+        ("%WINDIR%", std::env::var("WINDIR").unwrap_or_default()),
+        ("%PUBLIC%", std::env::var("PUBLIC").unwrap_or_default()),
+        ("%PROGRAMDATA%", std::env::var("PROGRAMDATA").unwrap_or_default()),
+        ("%SYSTEMROOT%", std::env::var("SYSTEMROOT").unwrap_or_default()),
+    ];
+    
+    let mut result = path.to_string();
+    for (var_name, var_value) in &env_vars {
+        result = result.replace(var_name, var_value);
+    }
+
+    // 工具缓存目录变量，仅在用到时才查询工具配置
+    for var_name in TOOL_PATH_VARS {
+        if result.contains(var_name) {
+            let var_value = resolve_tool_path(var_name).unwrap_or_default();
+            result = result.replace(var_name, &var_value);
+        }
+    }
+    
+    result
+}
+
+// 找出展开后为空或无法解析的 %VAR% 变量
+pub fn find_unresolved_variables(text: &str) -> Vec<String> {
+    let mut unresolved = Vec::new();
+    for segment in expansion_segments(text) {
+        if let ExpansionSegment::Variable { token, value: None } = segment {
+            if !unresolved.contains(&token) {
+                unresolved.push(token);
+            }
+        }
+    }
+    unresolved
+}
+
+// 文本按 %VAR% 拆分后的片段，value 为 None 表示变量未定义或为空
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ExpansionSegment {
+    Text(String),
+    Variable { token: String, value: Option<String> },
+}
+
+// 拆分文本中的环境变量并逐个展开，用于确认弹窗中对照显示展开前后的命令
+pub(crate) fn expansion_segments(text: &str) -> Vec<ExpansionSegment> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('%') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('%') else {
+            break;
+        };
+
+        let name = &after[..end];
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            // "%%" 或命令中单独的百分号，不是变量
+            literal.push_str(&rest[..start + 1]);
+            rest = &after[end..];
+            continue;
+        }
+
+        literal.push_str(&rest[..start]);
+        if !literal.is_empty() {
+            segments.push(ExpansionSegment::Text(std::mem::take(&mut literal)));
+        }
+
+        let token = format!("%{}%", name);
+        let expanded = expand_environment_variables(&token);
+        let value = if expanded != token {
+            expanded
+        } else {
+            // 不在内置列表中的变量由cmd展开，使用进程环境
+            std::env::var(name).unwrap_or_default()
+        };
+        segments.push(ExpansionSegment::Variable {
+            token,
+            value: (!value.is_empty()).then_some(value),
+        });
+
+        rest = &after[end + 1..];
+    }
+
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        segments.push(ExpansionSegment::Text(literal));
+    }
+    segments
+}
+
+// 内置禁区：不受任何配置影响，扫描器不会进入、删除操作一律拒绝
+// (路径, 是否包含其下所有内容)；false 表示只保护目录本身，允许清理其中的子目录
+const NEVER_TOUCH: &[(&str, bool)] = &[
+    ("%SYSTEMROOT%\\WinSxS", true),
+    ("%SYSTEMROOT%\\System32\\DriverStore", true),
+    ("%SYSTEMROOT%\\System32", true),
+    ("%SYSTEMROOT%\\SysWOW64", true),
+    ("%SYSTEMROOT%\\Boot", true),
+    ("%SYSTEMROOT%\\Fonts", true),
+    ("%SYSTEMROOT%", false),
+    ("%PROGRAMFILES%", false),
+    ("%PROGRAMFILES(X86)%", false),
+    ("%PROGRAMDATA%", false),
+    ("%SYSTEMDRIVE%\\Users", false),
+    ("%PUBLIC%", false),
+    ("%USERPROFILE%", false),
+    ("%APPDATA%", false),
+    ("%LOCALAPPDATA%", false),
+];
+
+// 统一大小写、分隔符和长路径前缀，便于按字符串比较路径
+pub(crate) fn normalize_path_for_compare(path: &str) -> String {
+    let path = path.replace('/', "\\").to_lowercase();
+    let path = path.strip_prefix("\\\\?\\").unwrap_or(&path);
+    path.trim_end_matches('\\').to_string()
+}
+
+// 检查路径是否落入内置禁区，返回命中的规则
+pub(crate) fn never_touch_rule(path: &Path) -> Option<String> {
+    let target = normalize_path_for_compare(&path.to_string_lossy());
+    if target.is_empty() {
+        return None;
+    }
+
+    for (rule, subtree) in NEVER_TOUCH {
+        let root = normalize_path_for_compare(&expand_environment_variables(rule));
+        // 变量未定义时规则无效，不能让空前缀匹配所有路径
+        if root.is_empty() || root.contains('%') {
+            continue;
+        }
+        if target == root || (*subtree && target.starts_with(&format!("{}\\", root))) {
+            return Some(expand_environment_variables(rule));
+        }
+    }
+
+    // 其他用户的配置文件根目录
+    let users_root = normalize_path_for_compare(&expand_environment_variables("%SYSTEMDRIVE%\\Users"));
+    if !users_root.is_empty() && !users_root.contains('%') {
+        if let Some(parent) = Path::new(&target).parent() {
+            if normalize_path_for_compare(&parent.to_string_lossy()) == users_root {
+                return Some(format!("用户配置文件目录 {}", path.display()));
+            }
+        }
+    }
+
+    None
+}
+
+// 从命令中提取绝对路径参数（引号内整段或空白分隔），跳过被调用的程序本身
+pub(crate) fn command_path_arguments(command: &str) -> Vec<String> {
+    const PROGRAM_EXTENSIONS: [&str; 5] = [".exe", ".com", ".bat", ".cmd", ".ps1"];
+
+    let mut tokens = Vec::new();
+    for (index, segment) in command.split(['"', '\'']).enumerate() {
+        if index % 2 == 1 {
+            tokens.push(segment.to_string());
+        } else {
+            tokens.extend(segment.split_whitespace().map(str::to_string));
+        }
+    }
+
+    tokens
+        .into_iter()
+        .filter(|token| {
+            let bytes = token.as_bytes();
+            let is_absolute = (bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && &bytes[1..3] == b":\\")
+                || token.starts_with("\\\\");
+            let lower = token.to_lowercase();
+            is_absolute && !PROGRAM_EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+        })
+        .collect()
+}
+
+// 路径安全检查：拒绝内置禁区、驱动器根目录、用户目录本身以及层级过浅的路径
+pub(crate) fn check_target_sanity(path: &Path) -> Result<(), String> {
+    let canonical = fs::canonicalize(path)
+        .map_err(|e| format!("无法解析目标路径: {} ({})", path.display(), e))?;
+
+    // 同时检查原路径和解析后的路径，防止通过目录联接绕过
+    if let Some(rule) = never_touch_rule(path).or_else(|| never_touch_rule(&canonical)) {
+        let msg = format!(
+            "目标位于系统关键目录: {}（内置禁区 {}）\n出于安全考虑，此操作被拒绝",
+            path.display(),
+            rule
+        );
+        log(&format!("内置禁区拦截: {}", msg));
+        return Err(msg);
+    }
+    let depth = canonical
+        .components()
+        .filter(|component| matches!(component, std::path::Component::Normal(_)))
+        .count();
+
+    if depth == 0 {
+        return Err(format!(
+            "目标是驱动器根目录: {}\n出于安全考虑，此操作被拒绝",
+            path.display()
+        ));
+    }
+
+    let user_profile = std::env::var("USERPROFILE")
+        .ok()
+        .and_then(|profile| fs::canonicalize(profile).ok());
+    if user_profile.as_ref() == Some(&canonical) {
+        return Err(format!(
+            "目标是用户目录本身: {}\n出于安全考虑，此操作被拒绝",
+            path.display()
+        ));
+    }
+
+    if depth < SETTINGS.min_target_depth {
+        return Err(format!(
+            "目标路径层级过浅: {}（{} 级，要求至少 {} 级）\n可通过 min_target_depth 设置调整",
+            path.display(),
+            depth,
+            SETTINGS.min_target_depth
+        ));
+    }
+
+    Ok(())
+}
+
+// 从工具配置解析的缓存目录变量，支持用户将缓存迁移到其他位置
+// CI runner 目录优先取已安装的服务所在目录，未安装服务时使用官方文档的默认目录
+const TOOL_PATH_VARS: [&str; 5] = [
+    "%GOMODCACHE%",
+    "%GRADLE_USER_HOME%",
+    "%NPM_CACHE%",
+    "%ACTIONS_RUNNER%",
+    "%AZP_AGENT%",
+];
+
+static TOOL_PATHS: Lazy<Mutex<HashMap<&'static str, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub(crate) fn resolve_tool_path(var_name: &'static str) -> Option<String> {
+    if let Some(path) = TOOL_PATHS.lock().unwrap().get(var_name) {
+        return path.clone();
+    }
+
+    let env_or_query = |env_name: &str, query: &str| {
+        std::env::var(env_name)
+            .ok()
+            .or_else(|| run_query_command(query))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty() && value != "undefined")
+    };
+
+    let path = match var_name {
+        "%GOMODCACHE%" => env_or_query("GOMODCACHE", "go env GOMODCACHE")
+            .or_else(|| Some(expand_environment_variables("%USERPROFILE%\\go\\pkg\\mod"))),
+        "%GRADLE_USER_HOME%" => std::env::var("GRADLE_USER_HOME")
+            .ok()
+            .filter(|value| !value.is_empty())
+            .or_else(|| Some(expand_environment_variables("%USERPROFILE%\\.gradle"))),
+        "%NPM_CACHE%" => env_or_query("npm_config_cache", "npm config get cache")
+            .or_else(|| Some(expand_environment_variables("%LOCALAPPDATA%\\npm-cache"))),
+        "%ACTIONS_RUNNER%" => runner_service_dir("actions.runner.")
+            .or_else(|| Some(expand_environment_variables("%SYSTEMDRIVE%\\actions-runner"))),
+        "%AZP_AGENT%" => runner_service_dir("vstsagent.")
+            .or_else(|| Some(expand_environment_variables("%SYSTEMDRIVE%\\agent"))),
+        _ => None,
+    };
+
+    log(&format!("解析工具目录: {} -> {}", var_name, path.as_deref().unwrap_or("未找到")));
+    TOOL_PATHS.lock().unwrap().insert(var_name, path.clone());
+    path
+}
+
+// 按服务名前缀查找已安装的 CI runner 服务（GitHub Actions 为 actions.runner.*，Azure Pipelines 为 vstsagent.*），
+// 返回其安装目录，即服务程序 <目录>\bin\RunnerService.exe 中 bin 的上一级
+fn runner_service_dir(service_prefix: &str) -> Option<String> {
+    let services = run_query_command("reg query \"HKLM\\SYSTEM\\CurrentControlSet\\Services\"")?;
+    let key = services.lines().map(str::trim).find(|line| {
+        line.rsplit('\\')
+            .next()
+            .is_some_and(|name| name.to_lowercase().starts_with(service_prefix))
+    })?;
+    let output = run_query_command(&format!("reg query \"{}\" /v ImagePath", key))?;
+    let image_path = output
+        .lines()
+        .find_map(|line| line.split_once("REG_EXPAND_SZ").or_else(|| line.split_once("REG_SZ")))
+        .map(|(_, value)| value.trim())?;
+    // 带引号的路径后面可能还有参数
+    let executable = match image_path.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().unwrap_or(quoted),
+        None => image_path,
+    };
+    let dir = Path::new(&expand_environment_variables(executable)).parent()?.parent()?.to_path_buf();
+    log(&format!(
+        "检测到 CI runner 服务: {} -> {}",
+        key.rsplit('\\').next().unwrap_or(key),
+        dir.display()
+    ));
+    Some(dir.to_string_lossy().to_string())
+}
+
+// 工具检测 - 按 detect 判断内置任务所属的工具或程序是否已安装，未安装的任务默认隐藏；
+// 报告和历史的环境信息中记录下列开发工具的检测结果：(名称, 命令)
+const KNOWN_TOOLS: [(&str, &str); 5] = [
+    ("Go", "go"),
+    ("Cargo", "cargo"),
+    ("npm", "npm"),
+    ("Gradle", "gradle"),
+    ("Docker", "docker"),
+];
+
+// 在 PATH 中查找命令，按 PATHEXT 依次尝试扩展名
+fn find_on_path(command: &str) -> Option<std::path::PathBuf> {
+    let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    std::env::split_paths(&std::env::var_os("PATH")?).find_map(|dir| {
+        extensions
+            .split(';')
+            .filter(|extension| !extension.is_empty())
+            .map(|extension| dir.join(format!("{}{}", command, extension.to_lowercase())))
+            .find(|path| path.is_file())
+    })
+}
+
+static TOOL_DETECTION: Lazy<Mutex<HashMap<String, bool>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 检测候选是否存在：含路径分隔符或环境变量的按目录或文件检查，否则在 PATH 中查找命令；每个候选只检测一次
+fn tool_present(candidate: &str) -> bool {
+    if let Some(present) = TOOL_DETECTION.lock().unwrap().get(candidate) {
+        return *present;
+    }
+    let present = if candidate.contains(['\\', '/', '%']) {
+        let path = expand_environment_variables(candidate);
+        !path.is_empty() && !path.contains('%') && Path::new(&path).exists()
+    } else {
+        find_on_path(candidate).is_some()
+    };
+    TOOL_DETECTION.lock().unwrap().insert(candidate.to_string(), present);
+    present
+}
+
+// 已安装的工具名称，每次启动只检测一次
+pub(crate) static INSTALLED_TOOLS: Lazy<Vec<&'static str>> = Lazy::new(|| {
+    let tools = KNOWN_TOOLS
+        .iter()
+        .filter(|(_, command)| tool_present(command))
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>();
+    log(&format!("检测到的工具: {}", if tools.is_empty() { "无".to_string() } else { tools.join(", ") }));
+    tools
+});
+
+// 浏览器缓存 - 枚举浏览器的全部配置文件（多用户配置各自有一套缓存），只清空缓存目录，
+// 不涉及书签、密码、Cookie 和历史记录
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Browser {
+    Chrome,
+    Edge,
+    Firefox,
+    Brave,
+}
+
+// Chromium 内核浏览器每个配置文件中的缓存目录
+const CHROMIUM_CACHE_DIRS: [&str; 5] = [
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "Service Worker\\CacheStorage",
+    "Service Worker\\ScriptCache",
+];
+
+// Firefox 的缓存位于 %LOCALAPPDATA% 下与配置文件同名的目录中
+const FIREFOX_CACHE_DIRS: [&str; 2] = ["cache2", "startupCache"];
+
+impl Browser {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Browser::Chrome => "Chrome",
+            Browser::Edge => "Edge",
+            Browser::Firefox => "Firefox",
+            Browser::Brave => "Brave",
+        }
+    }
+
+    // 存放各配置文件的目录
+    pub(crate) fn profiles_root(&self) -> String {
+        expand_environment_variables(match self {
+            Browser::Chrome => "%LOCALAPPDATA%\\Google\\Chrome\\User Data",
+            Browser::Edge => "%LOCALAPPDATA%\\Microsoft\\Edge\\User Data",
+            Browser::Firefox => "%LOCALAPPDATA%\\Mozilla\\Firefox\\Profiles",
+            Browser::Brave => "%LOCALAPPDATA%\\BraveSoftware\\Brave-Browser\\User Data",
+        })
+    }
+
+    // 配置文件目录：Chromium 内核为带 Preferences 文件的子目录（Default、Profile 1、Guest Profile 等），
+    // Firefox 为 Profiles 下的全部子目录
+    pub(crate) fn profiles(&self) -> Vec<std::path::PathBuf> {
+        let Ok(entries) = fs::read_dir(self.profiles_root()) else {
+            return Vec::new();
+        };
+        let mut profiles = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .filter(|path| *self == Browser::Firefox || path.join("Preferences").is_file())
+            .collect::<Vec<_>>();
+        profiles.sort();
+        profiles
+    }
+
+    // 全部配置文件中存在的缓存目录
+    pub(crate) fn cache_dirs(&self) -> Vec<std::path::PathBuf> {
+        let names: &[&str] = match self {
+            Browser::Firefox => &FIREFOX_CACHE_DIRS,
+            _ => &CHROMIUM_CACHE_DIRS,
+        };
+        self.profiles()
+            .iter()
+            .flat_map(|profile| names.iter().map(move |name| profile.join(name)))
+            .filter(|dir| dir.is_dir())
+            .collect()
+    }
+
+    pub(crate) fn cache_size(&self) -> u64 {
+        self.cache_dirs()
+            .iter()
+            .filter_map(|dir| get_directory_size(&dir.to_string_lossy()))
+            .sum()
+    }
+}
+
+// 内置清理任务
+pub fn builtin_tasks() -> Vec<CleanTask> {
+    vec![
+        CleanTask {
+            id: "go-modcache".to_string(),
+            name: "Go Module Cache".to_string(),
+            description: "清理Go模块缓存".to_string(),
+            category: CleanCategory::DevTools,
+            command: "go clean -modcache".to_string(),
+            path_check: Some("%GOMODCACHE%".to_string()),
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🐹".to_string()),
+            detect: Some("go;%GOMODCACHE%".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "stop-build-daemons".to_string(),
+            name: "Stop Build Daemons".to_string(),
+            description: "停止残留的 Gradle / Kotlin 守护进程、adb 服务和工作区已删除的 Node.js 进程，释放内存并解除缓存锁定".to_string(),
+            category: CleanCategory::DevTools,
+            command: String::new(), // 由 stop_daemons 查找并停止进程
+            path_check: None,
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Unknown,
+            icon: Some("🛑".to_string()),
+            skip_if_missing: true,
+            notes: Some("正在进行的构建会失败，请在构建结束后执行".to_string()),
+            stop_daemons: true,
+            ..Default::default()
+        },
+        CleanTask {
+            id: "gradle-caches".to_string(),
+            name: "Gradle Cache".to_string(),
+            description: "清理Gradle缓存".to_string(),
+            category: CleanCategory::DevTools,
+            command: "rmdir /s /q \"%GRADLE_USER_HOME%\\caches\"".to_string(),
+            path_check: Some("%GRADLE_USER_HOME%\\caches".to_string()),
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🐘".to_string()),
+            detect: Some("gradle;%GRADLE_USER_HOME%".to_string()),
+            run_after: vec!["stop-build-daemons".to_string()],
+            ..Default::default()
+        },
+        CleanTask {
+            id: "cargo-cache".to_string(),
+            name: "Cargo Cache".to_string(),
+            description: "清理Cargo缓存（需要cargo-cache）".to_string(),
+            category: CleanCategory::DevTools,
+            command: "cargo cache --remove-dir all".to_string(),
+            path_check: None,
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Fixed(2 * 1024 * 1024 * 1024),
+            icon: Some("🦀".to_string()),
+            size_provider: Some(SizeProvider::Cargo),
+            detect: Some("cargo;%USERPROFILE%\\.cargo\\bin".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "npm-cache".to_string(),
+            name: "npm Cache".to_string(),
+            description: "清理npm缓存".to_string(),
+            category: CleanCategory::DevTools,
+            command: "npm cache clean --force".to_string(),
+            path_check: None,
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Fixed(200 * 1024 * 1024),
+            icon: Some("📦".to_string()),
+            size_provider: Some(SizeProvider::Npm),
+            detect: Some("npm;%PROGRAMFILES%\\nodejs".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "docker-prune".to_string(),
+            name: "Docker Prune".to_string(),
+            description: "清理Docker停止的容器、悬空镜像和构建缓存".to_string(),
+            category: CleanCategory::DevTools,
+            command: "docker system prune -f".to_string(),
+            path_check: None,
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Variable,
+            icon: Some("🐳".to_string()),
+            size_provider: Some(SizeProvider::Docker),
+            detect: Some("docker;%PROGRAMFILES%\\Docker\\Docker".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "act-cache".to_string(),
+            name: "act Action Cache".to_string(),
+            description: "清理 act 本地运行 GitHub Actions 时下载的 action 仓库和缓存服务器数据".to_string(),
+            category: CleanCategory::DevTools,
+            command: "rmdir /s /q \"%USERPROFILE%\\.cache\\act\"".to_string(),
+            path_check: Some("%USERPROFILE%\\.cache\\act".to_string()),
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🎬".to_string()),
+            skip_if_missing: true,
+            detect: Some("act;%USERPROFILE%\\.cache\\act".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "act-volumes".to_string(),
+            name: "act Docker Volumes".to_string(),
+            description: "删除 act 创建的 Docker 卷（工具缓存和各任务的环境卷），正在运行的 act 任务使用的卷会被跳过".to_string(),
+            category: CleanCategory::DevTools,
+            command: "powershell -NoProfile -Command \"docker volume ls -q --filter name=act- | ForEach-Object { docker volume rm $_ }\"".to_string(),
+            path_check: None,
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Variable,
+            icon: Some("🐳".to_string()),
+            detect: Some("docker;%PROGRAMFILES%\\Docker\\Docker".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "github-runner-tool-cache".to_string(),
+            name: "GitHub Actions Runner Tool Cache".to_string(),
+            description: "清理自托管 GitHub Actions runner 的工具缓存（_work\\_tool），setup-* 操作会重新下载".to_string(),
+            category: CleanCategory::DevTools,
+            command: "rmdir /s /q \"%ACTIONS_RUNNER%\\_work\\_tool\"".to_string(),
+            path_check: Some("%ACTIONS_RUNNER%\\_work\\_tool".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🏃".to_string()),
+            skip_if_missing: true,
+            notes: Some("请在 runner 空闲时清理；已安装 runner 服务时自动使用其安装目录".to_string()),
+            detect: Some("%ACTIONS_RUNNER%".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "github-runner-workspaces".to_string(),
+            name: "GitHub Actions Runner Workspaces".to_string(),
+            description: "清理自托管 GitHub Actions runner 的工作目录（_work），包括各仓库的检出和构建产物".to_string(),
+            category: CleanCategory::DevTools,
+            command: "rmdir /s /q \"%ACTIONS_RUNNER%\\_work\"".to_string(),
+            path_check: Some("%ACTIONS_RUNNER%\\_work".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::High,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🏃".to_string()),
+            item_selection: true,
+            skip_if_missing: true,
+            notes: Some("正在执行的任务会失败，请在 runner 空闲时清理".to_string()),
+            detect: Some("%ACTIONS_RUNNER%".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "azure-agent-tool-cache".to_string(),
+            name: "Azure Pipelines Agent Tool Cache".to_string(),
+            description: "清理自托管 Azure Pipelines 代理的工具缓存（_work\\_tool）".to_string(),
+            category: CleanCategory::DevTools,
+            command: "rmdir /s /q \"%AZP_AGENT%\\_work\\_tool\"".to_string(),
+            path_check: Some("%AZP_AGENT%\\_work\\_tool".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🔷".to_string()),
+            skip_if_missing: true,
+            notes: Some("请在代理空闲时清理；已安装代理服务时自动使用其安装目录".to_string()),
+            detect: Some("%AZP_AGENT%".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "azure-agent-workspaces".to_string(),
+            name: "Azure Pipelines Agent Workspaces".to_string(),
+            description: "清理自托管 Azure Pipelines 代理的工作目录（_work），包括各流水线的源码、产物和临时文件".to_string(),
+            category: CleanCategory::DevTools,
+            command: "rmdir /s /q \"%AZP_AGENT%\\_work\"".to_string(),
+            path_check: Some("%AZP_AGENT%\\_work".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::High,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🔷".to_string()),
+            item_selection: true,
+            skip_if_missing: true,
+            notes: Some("正在执行的流水线会失败，请在代理空闲时清理".to_string()),
+            detect: Some("%AZP_AGENT%".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "trae-ai-chat-logs".to_string(),
+            name: "Trae AI Chat Logs".to_string(),
+            description: "清理Trae AI聊天记录（可能很大）".to_string(),
+            category: CleanCategory::AppCache,
+            command: "rmdir /s /q %USERPROFILE%\\.marscode\\ai-chat\\logs".to_string(),
+            path_check: Some("%USERPROFILE%\\.marscode\\ai-chat\\logs".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🤖".to_string()),
+            app_exe: Some("%LOCALAPPDATA%\\Programs\\Trae\\Trae.exe".to_string()),
+            process_check: Some("Trae.exe".to_string()),
+            detect: Some("%USERPROFILE%\\.marscode;%LOCALAPPDATA%\\Programs\\Trae".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "kugou-images-cache".to_string(),
+            name: "KuGou Image Cache".to_string(),
+            description: "清理酷狗音乐图片缓存".to_string(),
+            category: CleanCategory::AppCache,
+            command: "rmdir /s /q %USERPROFILE%\\AppData\\Roaming\\KuGou8\\ImagesCache"
+                .to_string(),
+            path_check: Some(
+                "%USERPROFILE%\\AppData\\Roaming\\KuGou8\\ImagesCache".to_string(),
+            ),
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🎵".to_string()),
+            app_exe: Some("%PROGRAMFILES(X86)%\\KuGou\\KGMusic\\KuGou.exe;%PROGRAMFILES%\\KuGou\\KGMusic\\KuGou.exe".to_string()),
+            process_check: Some("KuGou.exe".to_string()),
+            detect: Some("%APPDATA%\\KuGou8".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "vscode-cpptools".to_string(),
+            name: "VSCode Cpptools Cache".to_string(),
+            description: "清理VSCode Cpptools缓存".to_string(),
+            category: CleanCategory::AppCache,
+            command: "rmdir /s /q %LOCALAPPDATA%\\Microsoft\\vscode-cpptools".to_string(),
+            path_check: Some("%LOCALAPPDATA%\\Microsoft\\vscode-cpptools".to_string()),
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("💻".to_string()),
+            app_exe: Some("%LOCALAPPDATA%\\Programs\\Microsoft VS Code\\Code.exe;%PROGRAMFILES%\\Microsoft VS Code\\Code.exe".to_string()),
+            process_check: Some("Code.exe".to_string()),
+            detect: Some("code;%LOCALAPPDATA%\\Microsoft\\vscode-cpptools".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "vscode-workspace-storage".to_string(),
+            name: "VSCode Workspace Storage".to_string(),
+            description: "清理VSCode工作区存储（默认只勾选项目已不存在的条目）".to_string(),
+            category: CleanCategory::AppCache,
+            command: "rmdir /s /q \"%APPDATA%\\Code\\User\\workspaceStorage\"".to_string(),
+            path_check: Some("%APPDATA%\\Code\\User\\workspaceStorage".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("💻".to_string()),
+            app_exe: Some("%LOCALAPPDATA%\\Programs\\Microsoft VS Code\\Code.exe;%PROGRAMFILES%\\Microsoft VS Code\\Code.exe".to_string()),
+            item_selection: true,
+            item_analyzer: Some(ItemAnalyzer::VscodeWorkspaceStorage),
+            process_check: Some("Code.exe".to_string()),
+            detect: Some("code;%APPDATA%\\Code".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "office-updates".to_string(),
+            name: "Office Updates".to_string(),
+            description: "清理Office更新缓存".to_string(),
+            category: CleanCategory::AppCache,
+            command: "rmdir /s /q \"C:\\Program Files (x86)\\Microsoft Office\\Updates\""
+                .to_string(),
+            path_check: Some("C:\\Program Files (x86)\\Microsoft Office\\Updates".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::High,
+            requires_admin: true,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("📊".to_string()),
+            detect: Some("%PROGRAMFILES(X86)%\\Microsoft Office;%PROGRAMFILES%\\Microsoft Office".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "gradle-wrapper-dists".to_string(),
+            name: "Gradle Wrapper Dists".to_string(),
+            description: "清理Gradle Wrapper分发缓存".to_string(),
+            category: CleanCategory::DevTools,
+            command: "rmdir /s /q \"%GRADLE_USER_HOME%\\wrapper\\dists\"".to_string(),
+            path_check: Some("%GRADLE_USER_HOME%\\wrapper\\dists".to_string()),
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🐘".to_string()),
+            detect: Some("gradle;%GRADLE_USER_HOME%".to_string()),
+            run_after: vec!["stop-build-daemons".to_string()],
+            ..Default::default()
+        },
+        CleanTask {
+            id: "android-avd".to_string(),
+            name: "Android AVD Images".to_string(),
+            description: "清理Android模拟器镜像（可逐个选择保留）".to_string(),
+            category: CleanCategory::DevTools,
+            command: "rmdir /s /q \"%USERPROFILE%\\.android\\avd\"".to_string(),
+            path_check: Some("%USERPROFILE%\\.android\\avd".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::High,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("📱".to_string()),
+            item_selection: true,
+            detect: Some("%USERPROFILE%\\.android".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "qq-miniapp".to_string(),
+            name: "QQ MiniApp".to_string(),
+            description: "清理QQ小程序缓存（未经测试）".to_string(),
+            category: CleanCategory::AppCache,
+            command: "rmdir /s /q %USERPROFILE%\\AppData\\Roaming\\QQ\\miniapp".to_string(),
+            path_check: Some("%USERPROFILE%\\AppData\\Roaming\\QQ\\miniapp".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("💬".to_string()),
+            app_exe: Some("%PROGRAMFILES%\\Tencent\\QQNT\\QQ.exe;%PROGRAMFILES(X86)%\\Tencent\\QQ\\Bin\\QQ.exe".to_string()),
+            process_check: Some("QQ.exe".to_string()),
+            detect: Some("%APPDATA%\\QQ".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "outlook-roamcache".to_string(),
+            name: "Outlook RoamCache".to_string(),
+            description: "清理经典版 Outlook 的漫游缓存（RoamCache），不影响 OST 邮件数据文件".to_string(),
+            category: CleanCategory::AppCache,
+            command: "rmdir /s /q \"%LOCALAPPDATA%\\Microsoft\\Outlook\\RoamCache\"".to_string(),
+            path_check: Some("%LOCALAPPDATA%\\Microsoft\\Outlook\\RoamCache".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("📧".to_string()),
+            skip_if_missing: true,
+            requires_closed: vec!["OUTLOOK.EXE".to_string()],
+            notes: Some("需先退出 Outlook；自动完成列表和共享日历等会从服务器重新同步，大邮箱首次启动较慢".to_string()),
+            app_exe: Some("%PROGRAMFILES%\\Microsoft Office\\root\\Office16\\OUTLOOK.EXE;%PROGRAMFILES(X86)%\\Microsoft Office\\root\\Office16\\OUTLOOK.EXE".to_string()),
+            detect: Some("%LOCALAPPDATA%\\Microsoft\\Outlook".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "teams-classic-cache".to_string(),
+            name: "Teams (classic) Cache".to_string(),
+            description: "清理经典版 Teams 的浏览器缓存、图片和本地数据库，保留设置".to_string(),
+            category: CleanCategory::AppCache,
+            command: [
+                "Cache",
+                "Code Cache",
+                "GPUCache",
+                "blob_storage",
+                "databases",
+                "IndexedDB",
+                "Local Storage",
+                "tmp",
+            ]
+            .iter()
+            .map(|dir| format!("if exist \"%APPDATA%\\Microsoft\\Teams\\{dir}\" rmdir /s /q \"%APPDATA%\\Microsoft\\Teams\\{dir}\""))
+            .collect::<Vec<_>>()
+            .join(" & "),
+            path_check: Some("%APPDATA%\\Microsoft\\Teams".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("👥".to_string()),
+            skip_if_missing: true,
+            requires_closed: vec!["Teams.exe".to_string()],
+            notes: Some("需先退出 Teams；下次启动需要重新登录，聊天记录和图片会重新下载".to_string()),
+            app_exe: Some("%LOCALAPPDATA%\\Microsoft\\Teams\\current\\Teams.exe".to_string()),
+            detect: Some("%APPDATA%\\Microsoft\\Teams".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "teams-new-cache".to_string(),
+            name: "Teams (new) Cache".to_string(),
+            description: "清理新版 Teams 的本地缓存，即官方重置 Teams 时删除的内容".to_string(),
+            category: CleanCategory::AppCache,
+            command: "powershell -NoProfile Remove-Item -Recurse -Force '%LOCALAPPDATA%\\Packages\\MSTeams_8wekyb3d8bbwe\\LocalCache\\Microsoft\\MSTeams\\*'".to_string(),
+            path_check: Some("%LOCALAPPDATA%\\Packages\\MSTeams_8wekyb3d8bbwe\\LocalCache\\Microsoft\\MSTeams".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("👥".to_string()),
+            skip_if_missing: true,
+            requires_closed: vec!["ms-teams.exe".to_string()],
+            notes: Some("需先退出 Teams；下次启动需要重新登录，聊天记录和图片会重新下载，本机的 Teams 设置会恢复默认".to_string()),
+            detect: Some("%LOCALAPPDATA%\\Packages\\MSTeams_8wekyb3d8bbwe".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "onedrive-logs".to_string(),
+            name: "OneDrive Logs".to_string(),
+            description: "清理 OneDrive 同步客户端的日志和安装更新日志，不影响同步的文件".to_string(),
+            category: CleanCategory::AppCache,
+            command: "powershell -NoProfile Remove-Item -Recurse -Force '%LOCALAPPDATA%\\Microsoft\\OneDrive\\logs\\*'".to_string(),
+            path_check: Some("%LOCALAPPDATA%\\Microsoft\\OneDrive\\logs".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("☁️".to_string()),
+            skip_if_missing: true,
+            requires_closed: vec!["OneDrive.exe".to_string()],
+            notes: Some("需先退出 OneDrive（任务栏图标 → 暂停同步 → 退出）；只删除日志，不会触发重新同步".to_string()),
+            app_exe: Some("%LOCALAPPDATA%\\Microsoft\\OneDrive\\OneDrive.exe;%PROGRAMFILES%\\Microsoft OneDrive\\OneDrive.exe".to_string()),
+            detect: Some("%LOCALAPPDATA%\\Microsoft\\OneDrive".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "onedrive-temp".to_string(),
+            name: "OneDrive Update Downloads".to_string(),
+            description: "清理 OneDrive 下载的更新安装包等临时文件".to_string(),
+            category: CleanCategory::AppCache,
+            command: "powershell -NoProfile Remove-Item -Recurse -Force '%LOCALAPPDATA%\\Microsoft\\OneDrive\\Update\\*'".to_string(),
+            path_check: Some("%LOCALAPPDATA%\\Microsoft\\OneDrive\\Update".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("☁️".to_string()),
+            skip_if_missing: true,
+            requires_closed: vec!["OneDrive.exe".to_string(), "OneDriveSetup.exe".to_string()],
+            notes: Some("需先退出 OneDrive；更新进行中时不要清理".to_string()),
+            app_exe: Some("%LOCALAPPDATA%\\Microsoft\\OneDrive\\OneDrive.exe;%PROGRAMFILES%\\Microsoft OneDrive\\OneDrive.exe".to_string()),
+            detect: Some("%LOCALAPPDATA%\\Microsoft\\OneDrive".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "chrome-cache".to_string(),
+            name: "Google Chrome Cache".to_string(),
+            description: "清理 Google Chrome 各配置文件的网页缓存、代码缓存、GPU 缓存和 Service Worker 缓存，不影响书签、密码、Cookie 和历史记录".to_string(),
+            category: CleanCategory::Browser,
+            command: String::new(), // 按配置文件逐个清空缓存目录
+            path_check: None,
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🌐".to_string()),
+            skip_if_missing: true,
+            requires_closed: vec!["chrome.exe".to_string()],
+            notes: Some("需先退出 Google Chrome（包括后台运行的进程）；网页首次打开会稍慢，缓存随浏览重新生成".to_string()),
+            app_exe: Some("%PROGRAMFILES%\\Google\\Chrome\\Application\\chrome.exe;%PROGRAMFILES(X86)%\\Google\\Chrome\\Application\\chrome.exe;%LOCALAPPDATA%\\Google\\Chrome\\Application\\chrome.exe".to_string()),
+            detect: Some("%LOCALAPPDATA%\\Google\\Chrome\\User Data".to_string()),
+            browser: Some(Browser::Chrome),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "edge-cache".to_string(),
+            name: "Microsoft Edge Cache".to_string(),
+            description: "清理 Microsoft Edge 各配置文件的网页缓存、代码缓存、GPU 缓存和 Service Worker 缓存，不影响书签、密码、Cookie 和历史记录".to_string(),
+            category: CleanCategory::Browser,
+            command: String::new(), // 按配置文件逐个清空缓存目录
+            path_check: None,
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🌊".to_string()),
+            skip_if_missing: true,
+            requires_closed: vec!["msedge.exe".to_string()],
+            notes: Some("需先退出 Microsoft Edge（包括后台运行的进程）；网页首次打开会稍慢，缓存随浏览重新生成".to_string()),
+            app_exe: Some("%PROGRAMFILES(X86)%\\Microsoft\\Edge\\Application\\msedge.exe;%PROGRAMFILES%\\Microsoft\\Edge\\Application\\msedge.exe".to_string()),
+            detect: Some("%LOCALAPPDATA%\\Microsoft\\Edge\\User Data".to_string()),
+            browser: Some(Browser::Edge),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "firefox-cache".to_string(),
+            name: "Firefox Cache".to_string(),
+            description: "清理 Firefox 各配置文件的网页缓存（cache2）和启动缓存，不影响书签、密码、Cookie 和历史记录".to_string(),
+            category: CleanCategory::Browser,
+            command: String::new(), // 按配置文件逐个清空缓存目录
+            path_check: None,
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🦊".to_string()),
+            skip_if_missing: true,
+            requires_closed: vec!["firefox.exe".to_string()],
+            notes: Some("需先退出 Firefox；网页首次打开会稍慢，缓存随浏览重新生成".to_string()),
+            app_exe: Some("%PROGRAMFILES%\\Mozilla Firefox\\firefox.exe;%PROGRAMFILES(X86)%\\Mozilla Firefox\\firefox.exe".to_string()),
+            detect: Some("%LOCALAPPDATA%\\Mozilla\\Firefox\\Profiles".to_string()),
+            browser: Some(Browser::Firefox),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "brave-cache".to_string(),
+            name: "Brave Cache".to_string(),
+            description: "清理 Brave 各配置文件的网页缓存、代码缓存、GPU 缓存和 Service Worker 缓存，不影响书签、密码、Cookie 和历史记录".to_string(),
+            category: CleanCategory::Browser,
+            command: String::new(), // 按配置文件逐个清空缓存目录
+            path_check: None,
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🦁".to_string()),
+            skip_if_missing: true,
+            requires_closed: vec!["brave.exe".to_string()],
+            notes: Some("需先退出 Brave（包括后台运行的进程）；网页首次打开会稍慢，缓存随浏览重新生成".to_string()),
+            app_exe: Some("%PROGRAMFILES%\\BraveSoftware\\Brave-Browser\\Application\\brave.exe;%LOCALAPPDATA%\\BraveSoftware\\Brave-Browser\\Application\\brave.exe".to_string()),
+            detect: Some("%LOCALAPPDATA%\\BraveSoftware\\Brave-Browser\\User Data".to_string()),
+            browser: Some(Browser::Brave),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "dism-component-cleanup".to_string(),
+            name: "System Component Cleanup".to_string(),
+            description: "系统组件清理（需要管理员权限）".to_string(),
+            category: CleanCategory::System,
+            command: "Dism.exe /online /Cleanup-Image /StartComponentCleanup /ResetBase"
+                .to_string(),
+            path_check: None,
+            requires_confirmation: true,
+            risk: RiskLevel::High,
+            requires_admin: true,
+            estimated_size: EstimatedSize::Fixed(1024 * 1024 * 1024), // 1-3GB，取下限,
+            icon: Some("⚙️".to_string()),
+            success_codes: Some("0, 3010".to_string()),
+            exit_messages: BTreeMap::from([
+                ("3010".to_string(), "清理完成，需要重启以完成组件清理".to_string()),
+                ("740".to_string(), "需要以管理员身份运行".to_string()),
+                ("-2146498554".to_string(), "组件存储正在被 Windows 更新使用，请稍后重试".to_string()),
+            ]),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "wer-reports".to_string(),
+            name: "Windows Error Reporting".to_string(),
+            description: "清理错误报告队列和存档：应用崩溃时生成的转储与诊断报告副本，已上传或等待上传给微软，删除不影响系统运行（需要管理员权限）".to_string(),
+            category: CleanCategory::System,
+            command: "if exist \"%PROGRAMDATA%\\Microsoft\\Windows\\WER\\ReportQueue\" rmdir /s /q \"%PROGRAMDATA%\\Microsoft\\Windows\\WER\\ReportQueue\" & if exist \"%PROGRAMDATA%\\Microsoft\\Windows\\WER\\ReportArchive\" rmdir /s /q \"%PROGRAMDATA%\\Microsoft\\Windows\\WER\\ReportArchive\"".to_string(),
+            path_check: Some("%PROGRAMDATA%\\Microsoft\\Windows\\WER".to_string()),
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: true,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🐞".to_string()),
+            item_selection: true,
+            ..Default::default()
+        },
+        CleanTask {
+            id: "search-index-rebuild".to_string(),
+            name: "Rebuild Search Index".to_string(),
+            description: "停止 Windows Search 服务，删除索引数据库（Windows.edb / Windows.db）并重启服务重建索引。仅在索引异常膨胀时使用，重建期间搜索结果不完整（需要管理员权限）".to_string(),
+            category: CleanCategory::System,
+            command: "net stop WSearch /y && reg add \"HKLM\\SOFTWARE\\Microsoft\\Windows Search\" /v SetupCompletedSuccessfully /t REG_DWORD /d 0 /f && rmdir /s /q \"%PROGRAMDATA%\\Microsoft\\Search\\Data\\Applications\\Windows\" & net start WSearch".to_string(),
+            path_check: Some("%PROGRAMDATA%\\Microsoft\\Search\\Data\\Applications\\Windows".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: true,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("🔍".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "cleanmgr".to_string(),
+            name: "Disk Cleanup".to_string(),
+            description: "Windows自带磁盘清理工具".to_string(),
+            category: CleanCategory::System,
+            command: "cleanmgr".to_string(),
+            path_check: None,
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Variable,
+            icon: Some("🧹".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "boot-clean".to_string(),
+            name: "Clean Temp at Next Boot".to_string(),
+            description: "下次开机时、在其他程序启动前清理 Windows\\Temp 和各用户的临时文件夹，运行时被占用的文件也能删除，结果在下次打开时显示".to_string(),
+            category: CleanCategory::System,
+            command: boot_clean_command(), // 注册一次性计划任务，运行后自动删除
+            path_check: None,
+            requires_confirmation: true,
+            risk: RiskLevel::Low,
+            requires_admin: true,
+            estimated_size: EstimatedSize::Variable,
+            icon: Some("⏰".to_string()),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "installer-cache".to_string(),
+            name: "Windows Installer Cache".to_string(),
+            description: "分析 %WINDIR%\\Installer，只清理孤立的安装包和补丁包（仅逐项清理）".to_string(),
+            category: CleanCategory::System,
+            command: String::new(), // 整体删除会破坏修复和卸载，只允许逐项清理
+            path_check: Some("%WINDIR%\\Installer".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::High,
+            requires_admin: true,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("💿".to_string()),
+            item_selection: true,
+            item_analyzer: Some(ItemAnalyzer::WindowsInstaller),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "package-cache".to_string(),
+            name: "Package Cache".to_string(),
+            description: "分析 ProgramData\\Package Cache，只清理所属产品已卸载的缓存（仅逐项清理）".to_string(),
+            category: CleanCategory::System,
+            command: String::new(), // 整体删除会破坏修复和卸载，只允许逐项清理
+            path_check: Some("%PROGRAMDATA%\\Package Cache".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::High,
+            requires_admin: true,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("📀".to_string()),
+            item_selection: true,
+            item_analyzer: Some(ItemAnalyzer::PackageCache),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "log-files".to_string(),
+            name: "Logs & Crash Dumps".to_string(),
+            description: "汇总崩溃转储、IIS 日志和应用 logs 目录中的旧日志，按位置选择清理".to_string(),
+            category: CleanCategory::System,
+            command: String::new(), // 按位置和文件年龄筛选，只能逐项清理
+            path_check: None,
+            requires_confirmation: false,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Unknown,
+            icon: Some("📜".to_string()),
+            item_selection: true,
+            item_analyzer: Some(ItemAnalyzer::LogFiles),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "zone-identifiers".to_string(),
+            name: "Unblock Downloaded Files".to_string(),
+            description: "移除下载和项目文件夹中文件的 Zone.Identifier 等备用数据流（解除锁定），文件内容不变".to_string(),
+            category: CleanCategory::System,
+            command: String::new(), // 需要先枚举各文件的数据流，只能逐项清理
+            path_check: None,
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Unknown,
+            icon: Some("🏷️".to_string()),
+            item_selection: true,
+            item_analyzer: Some(ItemAnalyzer::AlternateStreams),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "uninstall-leftovers".to_string(),
+            name: "Uninstall Leftovers".to_string(),
+            description: "查找已卸载应用遗留在 AppData / ProgramData 中的数据目录（仅逐项清理）".to_string(),
+            category: CleanCategory::System,
+            command: String::new(), // 无整体清理命令，只能通过"选择…"逐项清理
+            path_check: None,
+            requires_confirmation: true,
+            risk: RiskLevel::Medium,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Unknown,
+            icon: Some("📦".to_string()),
+            item_selection: true,
+            item_analyzer: Some(ItemAnalyzer::UninstallLeftovers),
+            ..Default::default()
+        },
+        CleanTask {
+            id: "downloads".to_string(),
+            name: "Downloads Folder".to_string(),
+            description: "清理下载文件夹（建议使用\"选择…\"逐项清理）".to_string(),
+            category: CleanCategory::System,
+            command: "powershell -NoProfile Remove-Item -Recurse -Force '%USERPROFILE%\\Downloads\\*'".to_string(),
+            path_check: Some("%USERPROFILE%\\Downloads".to_string()),
+            requires_confirmation: true,
+            risk: RiskLevel::High,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+            icon: Some("📥".to_string()),
+            item_selection: true,
+            ..Default::default()
+        },
+        CleanTask {
+            id: "recycle-bin".to_string(),
+            name: "Clear Recycle Bin".to_string(),
+            description: "清空回收站（可通过\"选择…\"只清空部分磁盘的回收站）".to_string(),
+            category: CleanCategory::System,
+            command: "powershell Clear-RecycleBin -Force".to_string(),
+            path_check: None,
+            requires_confirmation: true,
+            risk: RiskLevel::Low,
+            requires_admin: false,
+            estimated_size: EstimatedSize::Variable,
+            icon: Some("🗑️".to_string()),
+            item_selection: true,
+            item_analyzer: Some(ItemAnalyzer::RecycleBins),
+            size_provider: Some(SizeProvider::RecycleBin),
+            ..Default::default()
+        },
+    ]
+}
+
+// 新建规则的删除方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum DeleteStrategy {
+    RemoveFolder,  // 删除整个文件夹
+    EmptyContents, // 保留文件夹，只删除其中的内容
+    ItemsOnly,     // 不提供整体清理，只能逐项选择
+}
+
+impl DeleteStrategy {
+    pub(crate) const ALL: [DeleteStrategy; 3] = [
+        DeleteStrategy::RemoveFolder,
+        DeleteStrategy::EmptyContents,
+        DeleteStrategy::ItemsOnly,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            DeleteStrategy::RemoveFolder => "删除整个文件夹",
+            DeleteStrategy::EmptyContents => "只清空内容",
+            DeleteStrategy::ItemsOnly => "仅逐项选择",
+        }
+    }
+
+    pub(crate) fn command(&self, path_check: &str) -> String {
+        match self {
+            DeleteStrategy::RemoveFolder => format!("rmdir /s /q \"{}\"", path_check),
+            DeleteStrategy::EmptyContents => {
+                format!("powershell -NoProfile Remove-Item -Recurse -Force '{}\\*'", path_check)
+            }
+            DeleteStrategy::ItemsOnly => String::new(),
+        }
+    }
+}
+
+// 由文件夹生成自定义规则，path_check 可以是泛化后的 %VAR% 路径
+pub(crate) fn rule_task(
+    path_check: &str,
+    name: &str,
+    strategy: DeleteStrategy,
+    risk: RiskLevel,
+    icon: &str,
+    category: CleanCategory,
+) -> CleanTask {
+    CleanTask {
+        id: format!("rule-{}", slugify(name)),
+        name: name.to_string(),
+        description: format!("清理 {}", path_check),
+        category,
+        command: strategy.command(path_check),
+        path_check: Some(path_check.to_string()),
+        requires_confirmation: true,
+        risk,
+        estimated_size: EstimatedSize::Auto, // 自动检测实际大小
+        icon: Some(icon.to_string()),
+        item_selection: true,
+        ..Default::default()
+    }
+}
+
+// 拖放到窗口的文件夹生成的临时任务，用于逐项预览
+pub(crate) fn folder_task(path: &Path) -> CleanTask {
+    let path_text = path.to_string_lossy().to_string();
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path_text.clone());
+
+    rule_task(
+        &path_text,
+        &name,
+        DeleteStrategy::RemoveFolder,
+        RiskLevel::Medium,
+        "📁",
+        CleanCategory::Custom,
+    )
+}
+
+// 路径泛化：把绝对路径中的已知目录替换为环境变量，让规则在其他电脑上也能使用
+// 越具体的变量越靠前（%TEMP% 优先于 %LOCALAPPDATA%），最后是原始路径
+const GENERALIZE_VARS: &[&str] = &[
+    "%TEMP%",
+    "%LOCALAPPDATA%",
+    "%APPDATA%",
+    "%PUBLIC%",
+    "%USERPROFILE%",
+    "%PROGRAMDATA%",
+    "%PROGRAMFILES(X86)%",
+    "%PROGRAMFILES%",
+    "%WINDIR%",
+    "%SYSTEMDRIVE%",
+];
+
+// 所有能覆盖该路径的环境变量写法，按匹配长度从长到短排列
+fn generalize_candidates(path: &str) -> Vec<String> {
+    let path = path.trim().trim_end_matches('\\');
+    let mut candidates = GENERALIZE_VARS
+        .iter()
+        .filter_map(|var| {
+            let root = expand_environment_variables(var);
+            let root = root.trim_end_matches('\\');
+            if root.is_empty() || root.contains('%') {
+                return None;
+            }
+
+            let prefix = path.get(..root.len())?;
+            let rest = &path[root.len()..];
+            let matches = normalize_path_for_compare(prefix) == normalize_path_for_compare(root)
+                && (rest.is_empty() || rest.starts_with('\\'));
+            matches.then(|| (root.len(), format!("{}{}", var, rest)))
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(length, _)| std::cmp::Reverse(*length));
+    candidates.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+// 反向展开：把绝对路径换成最匹配的 %VAR% 写法，没有可用变量时原样返回
+pub(crate) fn generalize_path(path: &str) -> String {
+    generalize_candidates(path)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| path.trim().to_string())
+}
+
+// 把命令中的绝对路径参数替换为环境变量写法
+fn generalize_command(command: &str) -> String {
+    let mut result = command.to_string();
+    for argument in command_path_arguments(command) {
+        let generalized = generalize_path(&argument);
+        if generalized.contains('%') {
+            result = result.replace(argument.trim_end_matches('\\'), &generalized);
+        }
+    }
+    result
+}
+
+// 泛化任务中的所有路径（path_check、command、working_dir），返回是否有改动
+pub(crate) fn generalize_task(task: &mut CleanTask) -> bool {
+    let original = (task.path_check.clone(), task.command.clone(), task.working_dir.clone());
+    task.path_check = task.path_check.as_deref().map(generalize_path);
+    task.command = generalize_command(&task.command);
+    task.working_dir = task.working_dir.as_deref().map(generalize_path);
+    original != (task.path_check.clone(), task.command.clone(), task.working_dir.clone())
+}
+
+// 向导中的路径选项：各种环境变量写法，最后是原始路径
+pub(crate) fn path_suggestions(path: &str) -> Vec<String> {
+    let mut suggestions = generalize_candidates(path);
+    let path = path.trim().trim_end_matches('\\');
+    if !path.is_empty() {
+        suggestions.push(path.to_string());
+    }
+    suggestions.dedup();
+    suggestions
+}
+
+// 剪贴板导入的规则及其安全检查结果；有错误的规则不允许导入
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ImportedRule {
+    pub(crate) task: CleanTask,
+    pub(crate) errors: Vec<String>,
+    pub(crate) warnings: Vec<String>,
+}
+
+// 解析分享的规则片段：[[task]] 格式的 TOML、单条规则的 TOML/JSON 对象或 JSON 数组
+// 聊天软件中常带有 ``` 代码块标记，解析前去掉
+pub(crate) fn parse_shared_rules(text: &str) -> Result<Vec<CleanTask>, String> {
+    let text = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("剪贴板中没有文本".to_string());
+    }
+
+    let value = match serde_json::from_str::<toml::Value>(text) {
+        Ok(value) => value,
+        Err(_) => toml::from_str::<toml::Table>(text)
+            .map(toml::Value::Table)
+            .map_err(|e| format!("无法识别为 TOML 或 JSON 规则: {}", e))?,
+    };
+    let definitions = match value {
+        toml::Value::Array(items) => items,
+        toml::Value::Table(mut table) => match table.remove("task") {
+            Some(toml::Value::Array(items)) => items,
+            Some(item @ toml::Value::Table(_)) => vec![item],
+            Some(_) => return Err("task 字段应为规则表或规则数组".to_string()),
+            None => vec![toml::Value::Table(table)],
+        },
+        _ => return Err("无法识别的规则格式".to_string()),
+    };
+    if definitions.is_empty() {
+        return Err("没有找到规则".to_string());
+    }
+
+    definitions
+        .into_iter()
+        .enumerate()
+        .map(|(index, definition)| {
+            let mut task = definition
+                .try_into::<TaskDefinition>()
+                .map_err(|e| e.to_string())
+                .and_then(|definition| definition.resolve(&LOCALE))
+                .map_err(|e| format!("第 {} 条规则格式错误: {}", index + 1, e))?;
+            task.apply_legacy_dangerous();
+            Ok(task)
+        })
+        .collect()
+}
+
+// 导入前的安全检查：与执行时相同的禁区和路径检查，并把本机绝对路径泛化为环境变量
+pub(crate) fn review_imported_rule(mut task: CleanTask, existing_names: &[String]) -> ImportedRule {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    // 来源不明的规则一律在执行前确认
+    task.requires_confirmation = true;
+    if generalize_task(&mut task) {
+        warnings.push("已将本机绝对路径改写为环境变量写法".to_string());
+    }
+    if task.command.trim().is_empty() && task.patterns.is_empty() && !task.item_selection {
+        errors.push("缺少清理命令".to_string());
+    }
+
+    let unresolved = find_unresolved_variables(&format!(
+        "{} {}",
+        task.command,
+        task.path_check.as_deref().unwrap_or_default()
+    ));
+    if !unresolved.is_empty() {
+        warnings.push(format!("本机无法解析的变量: {}", unresolved.join(", ")));
+    }
+
+    if let Some(path_check) = &task.path_check {
+        let expanded = expand_environment_variables(path_check);
+        let path = Path::new(&expanded);
+        if path.exists() {
+            if let Err(e) = check_target_sanity(path) {
+                errors.push(e);
+            }
+        } else if let Some(rule) = never_touch_rule(path) {
+            errors.push(format!("目标位于内置禁区 {}", rule));
+        } else {
+            warnings.push(format!("本机不存在该路径: {}", expanded));
+        }
+    }
+
+    for argument in command_path_arguments(&expand_environment_variables(&task.command)) {
+        if let Some(rule) = never_touch_rule(Path::new(&argument)) {
+            errors.push(format!("命令涉及系统关键目录: {}（内置禁区 {}）", argument, rule));
+        }
+    }
+
+    if task.risk == RiskLevel::High {
+        warnings.push("高风险规则，请确认命令内容".to_string());
+    }
+    if task.requires_admin {
+        warnings.push("需要管理员权限".to_string());
+    }
+    if existing_names.contains(&task.name) {
+        warnings.push("已存在同名任务".to_string());
+    }
+
+    ImportedRule { task, errors, warnings }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_known_variables() {
+        std::env::set_var("PUBLIC", r"C:\Users\Public");
+        assert_eq!(
+            expand_environment_variables(r"%PUBLIC%\Documents"),
+            r"C:\Users\Public\Documents"
+        );
+    }
+
+    #[test]
+    fn leaves_text_without_variables_unchanged() {
+        assert_eq!(expand_environment_variables(r"C:\Temp"), r"C:\Temp");
+        assert_eq!(expand_environment_variables("100% done"), "100% done");
+    }
+
+    #[test]
+    fn reports_unresolved_variables() {
+        assert_eq!(
+            find_unresolved_variables(r"%WINCLEANER_TEST_UNSET%\cache"),
+            vec!["%WINCLEANER_TEST_UNSET%".to_string()]
+        );
+        assert!(find_unresolved_variables("100% done").is_empty());
+    }
+
+    // 非 Windows 环境下缺少的目录变量指向临时目录中创建的目录，使禁区规则可以生效
+    fn sanity_environment() -> std::path::PathBuf {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        let base = std::env::temp_dir().join("wincleaner-sanity");
+        INIT.call_once(|| {
+            for (var, dir) in [("SYSTEMROOT", "Windows"), ("USERPROFILE", "profile")] {
+                if std::env::var_os(var).is_none() {
+                    let path = base.join(dir);
+                    fs::create_dir_all(&path).unwrap();
+                    std::env::set_var(var, path);
+                }
+            }
+            let system_root = std::path::PathBuf::from(std::env::var("SYSTEMROOT").unwrap());
+            if !system_root.join("System32").exists() {
+                fs::create_dir_all(system_root.join("System32").join("drivers")).unwrap();
+                fs::create_dir_all(system_root.join("Temp")).unwrap();
+            }
+            fs::create_dir_all(base.join("cache").join("deep")).unwrap();
+        });
+        base
+    }
+
+    fn depth(path: &Path) -> usize {
+        fs::canonicalize(path)
+            .unwrap()
+            .components()
+            .filter(|component| matches!(component, std::path::Component::Normal(_)))
+            .count()
+    }
+
+    #[test]
+    fn sanity_rejects_drive_root_and_user_profile() {
+        sanity_environment();
+        let root = std::env::temp_dir().ancestors().last().unwrap().to_path_buf();
+        assert!(check_target_sanity(&root).is_err());
+        let profile = std::env::var("USERPROFILE").unwrap();
+        assert!(check_target_sanity(Path::new(&profile)).is_err());
+    }
+
+    #[test]
+    fn sanity_rejects_shallow_paths() {
+        let base = sanity_environment();
+        let shallow = base.ancestors().find(|path| depth(path) == 1).unwrap();
+        let error = check_target_sanity(shallow).unwrap_err();
+        if never_touch_rule(shallow).is_none() {
+            assert!(error.contains("层级过浅"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn sanity_rejects_never_touch_paths_by_raw_and_resolved_path() {
+        sanity_environment();
+        let system_root = std::path::PathBuf::from(std::env::var("SYSTEMROOT").unwrap());
+        let raw = system_root.join("System32").join("drivers");
+        assert!(never_touch_rule(&raw).is_some());
+        assert!(check_target_sanity(&raw).unwrap_err().contains("内置禁区"));
+
+        // 原路径不在禁区中，解析后才落入禁区
+        let indirect = system_root.join("Temp").join("..").join("System32").join("drivers");
+        assert!(never_touch_rule(&indirect).is_none());
+        assert!(check_target_sanity(&indirect).unwrap_err().contains("内置禁区"));
+    }
+
+    #[test]
+    fn sanity_accepts_deep_temp_directory() {
+        let deep = sanity_environment().join("cache").join("deep");
+        assert_eq!(check_target_sanity(&deep), Ok(()));
+    }
+}
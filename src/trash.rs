@@ -0,0 +1,155 @@
+// 回收站 - 通过 SHFileOperationW 将文件和目录移入回收站，误删后可以从回收站还原
+use std::path::Path;
+
+// 将多个路径一次性移入回收站。路径必须是绝对路径；
+// 目标所在磁盘没有回收站或文件超过回收站容量时，系统会弹出提示询问是否永久删除，而不是直接删除
+#[cfg(windows)]
+pub fn move_to_recycle_bin(paths: &[&Path]) -> Result<(), String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::UI::Shell::{
+        SHFileOperationW, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI, FOF_SILENT,
+        FOF_WANTNUKEWARNING, FO_DELETE, SHFILEOPSTRUCTW,
+    };
+
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    // pFrom 是以 \0 分隔、以两个 \0 结尾的路径列表，不支持 \\?\ 前缀
+    let mut from = Vec::new();
+    for path in paths {
+        let text = path.to_string_lossy();
+        let text = text.strip_prefix("\\\\?\\").unwrap_or(&text);
+        if !Path::new(text).is_absolute() {
+            return Err(format!("不是绝对路径: {}", text));
+        }
+        from.extend(std::ffi::OsStr::new(text).encode_wide());
+        from.push(0);
+    }
+    from.push(0);
+
+    let mut operation = SHFILEOPSTRUCTW {
+        hwnd: std::ptr::null_mut(),
+        wFunc: FO_DELETE,
+        pFrom: from.as_ptr(),
+        pTo: std::ptr::null(),
+        fFlags: (FOF_ALLOWUNDO
+            | FOF_NOCONFIRMATION
+            | FOF_NOERRORUI
+            | FOF_SILENT
+            | FOF_WANTNUKEWARNING) as u16,
+        fAnyOperationsAborted: 0,
+        hNameMappings: std::ptr::null_mut(),
+        lpszProgressTitle: std::ptr::null(),
+    };
+    let code = unsafe { SHFileOperationW(&mut operation) };
+
+    if code != 0 {
+        Err(format!(
+            "移入回收站失败（错误码 0x{:X}），文件可能正在被使用或权限不足",
+            code
+        ))
+    } else if operation.fAnyOperationsAborted != 0 {
+        Err("已取消移入回收站".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(not(windows))]
+pub fn move_to_recycle_bin(paths: &[&Path]) -> Result<(), String> {
+    if paths.is_empty() {
+        Ok(())
+    } else {
+        Err("移入回收站仅支持 Windows".to_string())
+    }
+}
+
+// 某个磁盘上的回收站，root 形如 "D:\\"
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecycleBinInfo {
+    pub root: String,
+    pub bytes: u64,
+    pub items: u64,
+}
+
+// 本地固定磁盘和可移动磁盘上的回收站，网络驱动器没有回收站
+#[cfg(windows)]
+pub fn recycle_bins() -> Vec<RecycleBinInfo> {
+    use windows_sys::Win32::Storage::FileSystem::{GetDriveTypeW, GetLogicalDriveStringsW};
+    use windows_sys::Win32::UI::Shell::{SHQueryRecycleBinW, SHQUERYRBINFO};
+    // 来自 WindowsProgramming，为这两个常量不单独启用该功能
+    const DRIVE_REMOVABLE: u32 = 2;
+    const DRIVE_FIXED: u32 = 3;
+
+    let mut buffer = [0u16; 512];
+    let length =
+        unsafe { GetLogicalDriveStringsW(buffer.len() as u32, buffer.as_mut_ptr()) } as usize;
+    if length == 0 || length > buffer.len() {
+        return Vec::new();
+    }
+
+    // 缓冲区是以 \0 分隔的根目录列表，如 "C:\\\0D:\\\0\0"
+    buffer[..length]
+        .split(|&ch| ch == 0)
+        .filter(|root| !root.is_empty())
+        .filter_map(|root| {
+            let wide_root = root
+                .iter()
+                .copied()
+                .chain(std::iter::once(0))
+                .collect::<Vec<u16>>();
+            let drive_type = unsafe { GetDriveTypeW(wide_root.as_ptr()) };
+            if drive_type != DRIVE_FIXED && drive_type != DRIVE_REMOVABLE {
+                return None;
+            }
+
+            let mut info: SHQUERYRBINFO = unsafe { std::mem::zeroed() };
+            info.cbSize = std::mem::size_of::<SHQUERYRBINFO>() as u32;
+            let hr = unsafe { SHQueryRecycleBinW(wide_root.as_ptr(), &mut info) };
+            (hr >= 0).then(|| RecycleBinInfo {
+                root: String::from_utf16_lossy(root),
+                bytes: info.i64Size.max(0) as u64,
+                items: info.i64NumItems.max(0) as u64,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub fn recycle_bins() -> Vec<RecycleBinInfo> {
+    Vec::new()
+}
+
+// 只清空指定磁盘（如 "D:\\"）上的回收站
+#[cfg(windows)]
+pub fn empty_recycle_bin(root: &str) -> Result<(), String> {
+    use windows_sys::Win32::UI::Shell::{
+        SHEmptyRecycleBinW, SHERB_NOCONFIRMATION, SHERB_NOPROGRESSUI, SHERB_NOSOUND,
+    };
+    // 回收站本来就是空的时返回 E_UNEXPECTED
+    const E_UNEXPECTED: i32 = 0x8000FFFFu32 as i32;
+
+    let wide_root = root
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect::<Vec<u16>>();
+    let hr = unsafe {
+        SHEmptyRecycleBinW(
+            std::ptr::null_mut(),
+            wide_root.as_ptr(),
+            SHERB_NOCONFIRMATION | SHERB_NOPROGRESSUI | SHERB_NOSOUND,
+        )
+    };
+
+    if hr >= 0 || hr == E_UNEXPECTED {
+        Ok(())
+    } else {
+        Err(format!("清空回收站失败（错误码 0x{:X}）", hr))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn empty_recycle_bin(_root: &str) -> Result<(), String> {
+    Err("清空回收站仅支持 Windows".to_string())
+}
@@ -0,0 +1,6859 @@
+// 界面 - 主窗口、主题和各个界面组件
+use crate::config::{
+    append_custom_task, export_audit_report, kiosk_task_list, load_user_config, merge_tasks, WindowBackdrop,
+    AUDIT_MODE, CONFIG_FILE, KIOSK_MODE, SETTINGS,
+};
+#[cfg(windows)]
+use crate::engine::to_wide;
+use crate::engine::{
+    append_schedule_overrides, archive_file, below_emergency_line, cancel_requested, cancel_running_clean,
+    clear_batch_journal, clear_finished_runs, close_processes, completion_feedback, completion_message,
+    current_av_hint, delete_target_item, emergency_target, export_batch_report, find_large_files,
+    last_quarantine_summary, list_target_items, load_batch_journal, move_queued_run, plan_emergency,
+    plan_time_box, prescan_size, prescan_uncached, prune_quarantine, queue_slot, queued_run_count,
+    recommended_schedule_tasks, remove_queued_run, run_batch_clean, run_batch_clean_until, run_item_cleanup,
+    run_queue_snapshot, run_slots_full, simulate_tasks, submit_clean_task, sync_schedule_jobs,
+    take_boot_clean_report, undo_last_cleanup, AppState, BatchStop, BatchTaskStatus, LargeFile,
+    LargeFileSort, QueueSlot, ReportFormat, RunHost, RunOutcome, SimulationEntry, SimulationOutcome,
+    StateCell, TargetItem, TaskSchedule, ELEVATED_RUNNING, IS_ELEVATED, LAST_BATCH_REPORT, SCHEDULE_HOUR,
+    SCHEDULE_OFFER_KEY,
+};
+use crate::logging::{
+    existing_log_file, export_diagnostics_bundle, export_logs, failure_badge, filtered_history,
+    history_details, is_stale_task, last_clean, last_history_entry, log, open_task_log, prune_task_logs,
+    recent_history, record_feature, set_usage_enabled, usage_stats, LogExportFormat, LogFilter, LogSeverity,
+    HISTORY_VIEW_LIMIT, STALE_TASK_RUNS, USAGE_FILE,
+};
+use crate::sizing::{
+    acknowledge, drive_usage, format_size, growth_alerts, is_scan_running, item_new_content,
+    load_acknowledgements, parse_size, peek_size_bytes, record_growth_samples, run_query_command,
+    snapshot_directory, system_drive_root, unacknowledge, volume_free_space, volume_space, DirSnapshot,
+    DriveUsage, GrowthAlert, NewContent, SCAN_POLL_INTERVAL, SIZE_SCANS, STATUS_REFRESH_INTERVAL,
+};
+use crate::tasks::{
+    check_target_sanity, expand_environment_variables, expansion_segments, find_unresolved_variables,
+    folder_task, generalize_path, normalize_path_for_compare, parse_shared_rules, path_suggestions,
+    review_imported_rule, rule_task, CleanCategory, CleanTask, DeleteMode, DeleteStrategy, ExpansionSegment,
+    RiskLevel,
+};
+use freya::prelude::*;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use winit::window::{Icon, WindowLevel};
+
+// Include the window icon
+const WINDOW_ICON: &[u8] = include_bytes!("../assets/wincleaner_icon.png");
+
+// 多尺寸窗口图标 (边长, PNG)，按系统图标尺寸选用，避免高 DPI 下缩放模糊
+const WINDOW_ICONS: &[(u32, &[u8])] = &[
+    (16, include_bytes!("../assets/wincleaner_icon_16.png")),
+    (20, include_bytes!("../assets/wincleaner_icon_20.png")),
+    (24, include_bytes!("../assets/wincleaner_icon_24.png")),
+    (32, include_bytes!("../assets/wincleaner_icon_small.png")),
+    (40, include_bytes!("../assets/wincleaner_icon_40.png")),
+    (48, include_bytes!("../assets/wincleaner_icon_48.png")),
+    (64, include_bytes!("../assets/wincleaner_icon_64.png")),
+    (96, include_bytes!("../assets/wincleaner_icon_96.png")),
+    (128, include_bytes!("../assets/wincleaner_icon_128.png")),
+    (256, WINDOW_ICON),
+];
+
+// 选择不小于目标边长的最小图标，只缩小不放大
+fn icon_for_size(size: u32) -> Icon {
+    let bytes = WINDOW_ICONS
+        .iter()
+        .find(|(edge, _)| *edge >= size)
+        .map(|(_, bytes)| *bytes)
+        .unwrap_or(WINDOW_ICON);
+    LaunchConfig::load_icon(bytes)
+}
+
+// 标题栏小图标与任务栏/Alt-Tab 大图标的像素边长，随系统 DPI 变化
+#[cfg(windows)]
+fn system_icon_sizes() -> (u32, u32) {
+    use windows_sys::Win32::UI::HiDpi::{GetDpiForSystem, GetSystemMetricsForDpi};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{SM_CXICON, SM_CXSMICON};
+
+    unsafe {
+        let dpi = GetDpiForSystem();
+        let small = GetSystemMetricsForDpi(SM_CXSMICON, dpi).max(16) as u32;
+        let large = GetSystemMetricsForDpi(SM_CXICON, dpi).max(32) as u32;
+        (small, large)
+    }
+}
+
+#[cfg(not(windows))]
+fn system_icon_sizes() -> (u32, u32) {
+    (32, 256)
+}
+
+// Apple设计系统色彩方案 - 语义化命名
+#[derive(PartialEq)]
+struct AppTheme {
+    // 背景层次 - macOS风格
+    pub(crate) background_primary: &'static str,
+    pub(crate) background_secondary: &'static str,
+    pub(crate) background_tertiary: &'static str,
+
+    // 前景内容
+    pub(crate) label_primary: &'static str,
+    pub(crate) label_secondary: &'static str,
+    pub(crate) label_tertiary: &'static str,
+
+    // 交互元素
+    pub(crate) accent: &'static str,
+    pub(crate) accent_hover: &'static str,
+    pub(crate) danger: &'static str,
+    pub(crate) danger_hover: &'static str,
+    pub(crate) danger_background: &'static str, // 危险提示框底色
+    pub(crate) warning: &'static str,
+    pub(crate) warning_hover: &'static str,
+
+    // 边框和分隔线
+    pub(crate) separator: &'static str,
+    pub(crate) grid: &'static str,
+}
+
+// 界面字体链：首选字体在前，依次为后备字体，去掉空项和重复项
+static UI_FONTS: Lazy<Vec<String>> = Lazy::new(|| {
+    let mut fonts: Vec<String> = Vec::new();
+    for font in std::iter::once(&SETTINGS.font_family).chain(&SETTINGS.font_fallback) {
+        let font = font.trim();
+        if !font.is_empty() && !fonts.iter().any(|known| known.eq_ignore_ascii_case(font)) {
+            fonts.push(font.to_string());
+        }
+    }
+    fonts
+});
+
+static UI_FONT_FAMILY: Lazy<String> = Lazy::new(|| UI_FONTS.join(", "));
+
+impl AppTheme {
+    // 字体与深浅色无关，两套主题共用设置中的字体链
+    pub(crate) fn font_family(&self) -> &'static str {
+        UI_FONT_FAMILY.as_str()
+    }
+}
+
+// 浅色主题 - 参考macOS浅色模式
+const LIGHT_THEME: AppTheme = AppTheme {
+    background_primary: "rgb(255, 255, 255)",
+    background_secondary: "rgb(247, 247, 247)",
+    background_tertiary: "rgb(242, 242, 247)",
+
+    label_primary: "rgb(0, 0, 0)",
+    label_secondary: "rgb(99, 99, 102)",
+    label_tertiary: "rgb(142, 142, 147)",
+
+    accent: "rgb(0, 122, 255)",
+    accent_hover: "rgb(0, 105, 220)",
+    danger: "rgb(255, 59, 48)",
+    danger_hover: "rgb(230, 35, 25)",
+    danger_background: "rgb(255, 240, 240)",
+    warning: "rgb(255, 149, 0)",
+    warning_hover: "rgb(230, 130, 0)",
+
+    separator: "rgb(224, 224, 224)",
+    grid: "rgb(229, 229, 234)",
+};
+
+// 深色主题 - 参考macOS深色模式
+const DARK_THEME: AppTheme = AppTheme {
+    background_primary: "rgb(28, 28, 30)",
+    background_secondary: "rgb(44, 44, 46)",
+    background_tertiary: "rgb(58, 58, 60)",
+
+    label_primary: "rgb(255, 255, 255)",
+    label_secondary: "rgb(174, 174, 178)",
+    label_tertiary: "rgb(99, 99, 102)",
+
+    accent: "rgb(10, 132, 255)",
+    accent_hover: "rgb(20, 122, 255)",
+    danger: "rgb(255, 69, 58)",
+    danger_hover: "rgb(235, 49, 38)",
+    danger_background: "rgb(60, 30, 30)",
+    warning: "rgb(255, 159, 10)",
+    warning_hover: "rgb(235, 140, 0)",
+
+    separator: "rgb(84, 84, 88)",
+    grid: "rgb(58, 58, 62)",
+};
+
+// 主题管理 - 支持动态切换
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
+enum ThemeMode {
+    Light,
+    #[default]
+    Dark, // 默认深色主题，更专业
+}
+
+impl ThemeMode {
+    pub(crate) fn current_theme(&self) -> &'static AppTheme {
+        match self {
+            ThemeMode::Light => &LIGHT_THEME,
+            ThemeMode::Dark => &DARK_THEME,
+        }
+    }
+}
+
+// 界面状态 - 跨启动保留上次使用的主题等，启动时用于窗口初始配置
+const SESSION_STATE_FILE: &str = "wincleaner-session.json";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct SessionState {
+    pub(crate) theme: ThemeMode,
+    pub(crate) maximized: bool,
+    pub(crate) always_on_top: bool,
+}
+
+fn load_session_state() -> SessionState {
+    fs::read_to_string(SESSION_STATE_FILE)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_session_state(state: &SessionState) {
+    match serde_json::to_string_pretty(state) {
+        Ok(content) => {
+            if let Err(e) = fs::write(SESSION_STATE_FILE, content) {
+                log(&format!("界面状态写入失败: {}", e));
+            }
+        }
+        Err(e) => log(&format!("界面状态序列化失败: {}", e)),
+    }
+}
+
+// 记录窗口最大化状态，仅在变化时写入
+fn remember_maximized(maximized: bool) {
+    let mut session = load_session_state();
+    if session.maximized != maximized {
+        session.maximized = maximized;
+        save_session_state(&session);
+    }
+}
+
+fn window_level(always_on_top: bool) -> WindowLevel {
+    if always_on_top {
+        WindowLevel::AlwaysOnTop
+    } else {
+        WindowLevel::Normal
+    }
+}
+
+static BACKDROP_ACTIVE: Lazy<bool> =
+    Lazy::new(|| SETTINGS.window_backdrop != WindowBackdrop::None && is_windows_11());
+
+// Windows 11 仍报告版本 10.0，按内部版本号 22000 区分
+fn is_windows_11() -> bool {
+    run_query_command("reg query \"HKLM\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\" /v CurrentBuildNumber")
+        .and_then(|output| output.split_whitespace().last().and_then(|build| build.parse::<u32>().ok()))
+        .is_some_and(|build| build >= 22000)
+}
+
+// 窄窗口布局 - 宽度低于该值时侧边栏改为顶部分类条，任务卡片的按钮移到文字下方
+const NARROW_LAYOUT_WIDTH: f32 = 700.0;
+
+// 任务列表的行：分组标题或任务卡片
+#[derive(Clone, Debug, PartialEq)]
+enum TaskListRow {
+    Header(String),
+    Task(CleanTask),
+}
+
+// 行数超过该值时才按可见范围渲染，较短的列表保持完整渲染
+const VIRTUALIZE_MIN_ROWS: usize = 60;
+// 可见范围上下额外渲染的高度，减少快速滚动时的空白
+const TASK_LIST_OVERSCAN: f32 = 600.0;
+// 列表上方头部和已选抽屉的大致高度
+const TASK_LIST_HEADER_ESTIMATE: f32 = 60.0;
+
+impl TaskListRow {
+    // 估计的行高（含卡片间距），用于计算可见范围和占位高度
+    pub(crate) fn estimated_height(&self) -> f32 {
+        match self {
+            TaskListRow::Header(_) => 30.0,
+            TaskListRow::Task(task) => {
+                let mut height = 92.0 + 12.0;
+                if task.notes.is_some() {
+                    height += 20.0;
+                }
+                if task.schedule.is_some() {
+                    height += 20.0;
+                }
+                if !task.tags.is_empty() {
+                    height += 26.0;
+                }
+                height
+            }
+        }
+    }
+}
+
+// 返回需要渲染的行范围，以及其上方和下方的占位高度
+fn visible_row_range(heights: &[f32], offset: f32, viewport: f32) -> (usize, usize, f32, f32) {
+    if heights.len() < VIRTUALIZE_MIN_ROWS {
+        return (0, heights.len(), 0.0, 0.0);
+    }
+
+    // 首次渲染时尚不知道视口高度，按较大的窗口估计
+    let viewport = if viewport > 0.0 { viewport } else { 1200.0 };
+    let start = offset - TASK_LIST_OVERSCAN;
+    let end = offset + viewport + TASK_LIST_OVERSCAN;
+
+    let mut first = None;
+    let mut last = heights.len();
+    let mut top = 0.0;
+    let mut y = 0.0;
+    for (index, height) in heights.iter().enumerate() {
+        if y > end {
+            last = index;
+            break;
+        }
+        if first.is_none() && y + height >= start {
+            first = Some(index);
+            top = y;
+        }
+        y += height;
+    }
+
+    let first = first.unwrap_or(last);
+    if first == last {
+        top = heights[..last].iter().sum();
+    }
+    let bottom = heights[last..].iter().sum();
+    (first, last, top, bottom)
+}
+
+// Shift 连续选择：返回列表顺序中锚点和目标之间（含两端）的任务；锚点已不在列表中时只选目标
+fn selection_range<'a>(order: &'a [String], anchor: &str, target: &str) -> &'a [String] {
+    let Some(end) = order.iter().position(|id| id == target) else {
+        return &[];
+    };
+    let start = order.iter().position(|id| id == anchor).unwrap_or(end);
+    &order[start.min(end)..=start.max(end)]
+}
+
+// 批量模式下单击任务后的选择集合和新的范围起点：普通单击切换该任务并以其为起点，
+// Shift+单击选中起点到该任务之间的全部任务且起点不变
+fn click_selection(
+    selected: &HashSet<String>,
+    order: &[String],
+    anchor: Option<&str>,
+    target: &str,
+    extend_range: bool,
+) -> (HashSet<String>, Option<String>) {
+    let mut selected = selected.clone();
+    match anchor.filter(|_| extend_range) {
+        Some(anchor) => {
+            selected.extend(selection_range(order, anchor, target).iter().cloned());
+            (selected, Some(anchor.to_string()))
+        }
+        None => {
+            if !selected.remove(target) {
+                selected.insert(target.to_string());
+            }
+            (selected, Some(target.to_string()))
+        }
+    }
+}
+
+// 按键本身对应的修饰键；按下 Shift 的那次事件中 modifiers 可能尚未包含它
+fn key_modifier(key: &Key) -> Modifiers {
+    match key {
+        Key::Shift => Modifiers::SHIFT,
+        Key::Control => Modifiers::CONTROL,
+        _ => Modifiers::empty(),
+    }
+}
+
+// 弹窗尺寸不超过窗口，四周各留 20
+fn fit_popup_size(size: f32, available: f32) -> std::borrow::Cow<'static, str> {
+    let fitted = if available > 0.0 { size.min(available - 40.0).max(240.0) } else { size };
+    std::borrow::Cow::Owned(format!("{}", fitted.round()))
+}
+
+// 背景材质随窗口的深浅色模式变化
+fn window_theme(mode: ThemeMode) -> winit::window::Theme {
+    match mode {
+        ThemeMode::Light => winit::window::Theme::Light,
+        ThemeMode::Dark => winit::window::Theme::Dark,
+    }
+}
+
+// 引擎通过 StateCell 写入的状态即界面的 Signal；读取时不订阅，引擎不在组件中运行
+impl<T: Clone + 'static> StateCell<T> for Signal<T> {
+    fn load(&self) -> T {
+        self.peek().clone()
+    }
+
+    fn store(&mut self, value: T) {
+        self.set(value);
+    }
+}
+
+// 执行器运行在根作用域中，不随创建它的组件卸载而结束
+impl RunHost for Signal<AppState> {
+    fn spawn_worker(&self, worker: impl std::future::Future<Output = ()> + 'static) {
+        spawn_forever(worker);
+    }
+}
+
+// 风险等级和清理结果在界面中的颜色
+impl RiskLevel {
+    pub(crate) fn color(&self, theme: &AppTheme) -> &'static str {
+        match self {
+            RiskLevel::Low => theme.accent,
+            RiskLevel::Medium => theme.warning,
+            RiskLevel::High => theme.danger,
+        }
+    }
+
+    pub(crate) fn hover_color(&self, theme: &AppTheme) -> &'static str {
+        match self {
+            RiskLevel::Low => theme.accent_hover,
+            RiskLevel::Medium => theme.warning_hover,
+            RiskLevel::High => theme.danger_hover,
+        }
+    }
+}
+
+impl RunOutcome {
+    pub(crate) fn color(&self, theme: &'static AppTheme) -> &'static str {
+        match self {
+            RunOutcome::Succeeded => theme.label_primary,
+            RunOutcome::Failed => theme.danger,
+            RunOutcome::Skipped | RunOutcome::Cancelled => theme.label_secondary,
+        }
+    }
+}
+
+impl CleanTask {
+    // 关联程序的图标（PNG），取第一个存在的候选程序
+    pub(crate) fn app_icon(&self) -> Option<Vec<u8>> {
+        self.app_exe
+            .as_deref()?
+            .split(';')
+            .map(|candidate| expand_environment_variables(candidate.trim()))
+            .find(|exe| !exe.is_empty() && Path::new(exe).is_file())
+            .and_then(|exe| app_icon_png(&exe))
+    }
+}
+
+// 应用图标 - 从任务关联的程序中提取图标显示在卡片上，找不到程序时使用表情符号图标
+static APP_ICONS: Lazy<Mutex<HashMap<String, Option<Vec<u8>>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 提取程序的大图标并编码为 PNG，结果按路径缓存
+fn app_icon_png(exe: &str) -> Option<Vec<u8>> {
+    let mut icons = APP_ICONS.lock().unwrap();
+    icons
+        .entry(exe.to_lowercase())
+        .or_insert_with(|| {
+            let png = extract_exe_icon(exe);
+            if png.is_none() {
+                log(&format!("无法提取程序图标: {}", exe));
+            }
+            png
+        })
+        .clone()
+}
+
+#[cfg(windows)]
+fn extract_exe_icon(exe: &str) -> Option<Vec<u8>> {
+    use windows_sys::Win32::Graphics::Gdi::DeleteObject;
+    use windows_sys::Win32::UI::Shell::ExtractIconExW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO};
+
+    let wide_exe = to_wide(exe);
+    let mut icon: HICON = std::ptr::null_mut();
+    let count = unsafe { ExtractIconExW(wide_exe.as_ptr(), 0, &mut icon, std::ptr::null_mut(), 1) };
+    if count == 0 || icon.is_null() {
+        return None;
+    }
+
+    let pixels = unsafe {
+        let mut info: ICONINFO = std::mem::zeroed();
+        let pixels = if GetIconInfo(icon, &mut info) != 0 {
+            let pixels = bitmap_rgba(info.hbmColor);
+            DeleteObject(info.hbmColor);
+            DeleteObject(info.hbmMask);
+            pixels
+        } else {
+            None
+        };
+        DestroyIcon(icon);
+        pixels
+    };
+    let (width, height, rgba) = pixels?;
+    Some(encode_png(width, height, &rgba))
+}
+
+#[cfg(not(windows))]
+fn extract_exe_icon(_exe: &str) -> Option<Vec<u8>> {
+    None
+}
+
+// 读取位图像素，返回 (宽, 高, RGBA)
+#[cfg(windows)]
+unsafe fn bitmap_rgba(bitmap: windows_sys::Win32::Graphics::Gdi::HBITMAP) -> Option<(u32, u32, Vec<u8>)> {
+    use windows_sys::Win32::Graphics::Gdi::{
+        GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    };
+
+    if bitmap.is_null() {
+        return None;
+    }
+    let mut header: BITMAP = std::mem::zeroed();
+    if GetObjectW(bitmap, std::mem::size_of::<BITMAP>() as i32, &mut header as *mut _ as *mut _) == 0 {
+        return None;
+    }
+    let (width, height) = (header.bmWidth, header.bmHeight);
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+
+    let mut info: BITMAPINFO = std::mem::zeroed();
+    info.bmiHeader = BITMAPINFOHEADER {
+        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: width,
+        biHeight: -height, // 自上而下的行顺序
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB,
+        ..std::mem::zeroed()
+    };
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
+    let dc = GetDC(std::ptr::null_mut());
+    let lines = GetDIBits(
+        dc,
+        bitmap,
+        0,
+        height as u32,
+        pixels.as_mut_ptr() as *mut _,
+        &mut info,
+        DIB_RGB_COLORS,
+    );
+    ReleaseDC(std::ptr::null_mut(), dc);
+    if lines == 0 {
+        return None;
+    }
+
+    // BGRA 转为 RGBA；没有 alpha 通道的旧式图标按不透明处理
+    let has_alpha = pixels.chunks(4).any(|pixel| pixel[3] != 0);
+    for pixel in pixels.chunks_mut(4) {
+        pixel.swap(0, 2);
+        if !has_alpha {
+            pixel[3] = 255;
+        }
+    }
+    Some((width as u32, height as u32, pixels))
+}
+
+// 最小的 PNG 编码：不压缩的 deflate 块，图标只有几 KB，无需引入图像库
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for byte in data {
+            crc ^= *byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            }
+        }
+        !crc
+    }
+
+    fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    // 每行前加过滤类型 0（无过滤）
+    let row = width as usize * 4;
+    let mut raw = Vec::with_capacity((row + 1) * height as usize);
+    for line in rgba.chunks(row).take(height as usize) {
+        raw.push(0);
+        raw.extend_from_slice(line);
+    }
+
+    let mut zlib = vec![0x78, 0x01];
+    let blocks = raw.chunks(0xFFFF).collect::<Vec<_>>();
+    for (index, block) in blocks.iter().enumerate() {
+        zlib.push((index + 1 == blocks.len()) as u8);
+        zlib.extend_from_slice(&(block.len() as u16).to_le_bytes());
+        zlib.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in &raw {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    zlib.extend_from_slice(&((b << 16) | a).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 6, 0, 0, 0]); // 8 位 RGBA
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &zlib);
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+// 打开主窗口，按上次的窗口状态和主题启动界面
+pub(crate) fn launch() {
+    let (small_icon_size, large_icon_size) = system_icon_sizes();
+    let window_icon = icon_for_size(small_icon_size);
+    // 按上次使用的主题设置窗口底色，避免启动时闪烁
+    let session = load_session_state();
+    let restore_window = SETTINGS.remember_window_state;
+    let maximized = restore_window && session.maximized;
+    let always_on_top = restore_window && session.always_on_top;
+    let theme_mode = session.theme;
+
+    // 设置中的字体链替换框架的默认字体，弹窗等不在根节点下的文字同样生效
+    let launch_config = UI_FONTS
+        .iter()
+        .fold(LaunchConfig::<SessionState>::new().without_default_fonts(), |config, font| {
+            config.with_default_font(font)
+        });
+
+    launch_cfg(
+        app,
+        launch_config
+            .with_size(900.0, 700.0)
+            .with_decorations(true)
+            .with_transparency(*BACKDROP_ACTIVE)
+            .with_title("WinCleaner - Windows系统清理工具")
+            .with_background(if *BACKDROP_ACTIVE {
+                "transparent"
+            } else {
+                session.theme.current_theme().background_primary
+            })
+            .with_icon(window_icon)
+            .with_window_attributes(move |attributes| {
+                let attributes = attributes
+                    .with_maximized(maximized)
+                    .with_window_level(window_level(always_on_top));
+
+                // 任务栏和 Alt-Tab 使用单独的大图标
+                #[cfg(windows)]
+                let attributes = {
+                    use winit::platform::windows::WindowAttributesExtWindows;
+                    attributes.with_taskbar_icon(Some(icon_for_size(large_icon_size)))
+                };
+                #[cfg(not(windows))]
+                let _ = large_icon_size;
+
+                // Windows 11 背景材质和圆角
+                #[cfg(windows)]
+                let attributes = if *BACKDROP_ACTIVE {
+                    use winit::platform::windows::{BackdropType, CornerPreference, WindowAttributesExtWindows};
+                    attributes
+                        .with_system_backdrop(match SETTINGS.window_backdrop {
+                            WindowBackdrop::Acrylic => BackdropType::TransientWindow,
+                            _ => BackdropType::MainWindow,
+                        })
+                        .with_corner_preference(CornerPreference::Round)
+                        .with_theme(Some(window_theme(theme_mode)))
+                } else {
+                    attributes
+                };
+                #[cfg(not(windows))]
+                let _ = theme_mode;
+
+                attributes
+            })
+            .with_state(session),
+    );
+}
+
+fn app() -> Element {
+    // Apple风格主题管理 - 初始主题来自启动配置
+    let session = use_context::<SessionState>();
+    let mut theme_mode = use_signal(|| session.theme);
+    let theme = theme_mode().current_theme();
+
+    // 窗口行为 - 启动最小化、置顶，并定期记录最大化状态
+    let platform = use_platform();
+    let mut always_on_top =
+        use_signal(|| SETTINGS.remember_window_state && session.always_on_top);
+    use_hook(move || {
+        if SETTINGS.start_minimized {
+            platform.with_window(|window| window.set_minimized(true));
+        }
+    });
+    use_future(move || async move {
+        if !SETTINGS.remember_window_state {
+            return;
+        }
+        loop {
+            tokio::time::sleep(STATUS_REFRESH_INTERVAL).await;
+            platform.with_window(|window| remember_maximized(window.is_maximized()));
+        }
+    });
+
+    // 状态管理
+    let mut selected_tasks = use_signal(|| HashSet::<String>::new());
+    // 批量模式的连续选择：上次单击的任务作为 Shift 范围的起点，鼠标事件不带修饰键，需自行记录按键状态
+    let mut selection_anchor = use_signal(|| None::<String>);
+    let mut held_modifiers = use_signal(Modifiers::empty);
+    let mut progress = use_signal(|| 0.0f32);
+    let mut show_batch_mode = use_signal(|| false);
+    let mut selected_category = use_signal(|| Some(CleanCategory::DevTools)); // None 表示"全部"
+    // 有开机清理的报告时，启动后先显示其结果
+    let mut app_state = use_signal(|| {
+        take_boot_clean_report()
+            .map(|report| AppState::SuccessWithStats(report.stats()))
+            .unwrap_or(AppState::Idle)
+    });
+
+    // 磁盘空间仪表盘 - 各固定磁盘的使用情况及与上次刷新相比可用空间的变化，启动时和每次清理结束后刷新
+    let drives = use_signal(Vec::<(DriveUsage, i64)>::new);
+    use_hook(move || refresh_drive_usage(drives));
+
+    // 清理从运行中结束时给出完成提示（提示音或通知）
+    let mut was_running = use_signal(|| false);
+    use_effect(move || {
+        let state = app_state();
+        let running = matches!(state, AppState::Running(_));
+        if *was_running.peek() && !running {
+            if let Some((success, message)) = completion_message(&state) {
+                std::thread::spawn(move || completion_feedback(false, success, &message));
+            }
+            refresh_drive_usage(drives);
+        }
+        if *was_running.peek() != running {
+            was_running.set(running);
+        }
+    });
+    
+    // 任务列表（全部任务、信息亭任务）在后台加载，完成前为 None，界面显示加载状态
+    let mut loaded_tasks = use_signal(|| None::<(Vec<CleanTask>, Vec<CleanTask>)>);
+    let tasks_loading = loaded_tasks.read().is_none();
+    let (all_tasks, kiosk_tasks) = loaded_tasks().unwrap_or_default();
+    // 未检测到所属工具的任务默认隐藏，"显示全部"时一并列出
+    let mut missing_tool_tasks = use_signal(HashSet::<String>::new);
+    let mut show_all_tasks = use_signal(|| false);
+    let hidden_tasks = if show_all_tasks() { HashSet::new() } else { missing_tool_tasks() };
+
+    // 启动时预扫描 - 当前分类的任务优先，其余任务随后在后台依次扫描
+    let mut prescan_progress = use_signal(|| None::<(usize, usize)>);
+    let mut growth_warnings = use_signal(Vec::<GrowthAlert>::new);
+    // 杀毒软件干扰提示 - 由后台扫描或清理触发检测，定期读取结果
+    let mut av_hint = use_signal(current_av_hint);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(STATUS_REFRESH_INTERVAL).await;
+            let hint = current_av_hint();
+            if *av_hint.peek() != hint {
+                av_hint.set(hint);
+            }
+        }
+    });
+    let av_hint_view = av_hint().map(|hint| (hint.message(), hint.key()));
+    let growth_rows = growth_warnings()
+        .into_iter()
+        .map(|alert| (alert.path.clone(), alert.summary(), alert.path.clone(), alert.key(), alert.current))
+        .collect::<Vec<_>>();
+    let mut show_acknowledged = use_signal(|| false);
+    let acknowledged_count = load_acknowledgements().len();
+    // 定期清理 - 各频率下的规则数和下次执行时间，以及按使用习惯给出的每周计划建议
+    let schedule_rows = TaskSchedule::ALL
+        .into_iter()
+        .filter_map(|schedule| {
+            let count = all_tasks.iter().filter(|task| task.schedule == Some(schedule)).count();
+            (count > 0).then(|| (schedule.label(), count, schedule.next_run_text()))
+        })
+        .collect::<Vec<_>>();
+    let schedule_offer = recommended_schedule_tasks(&all_tasks);
+    let schedule_offer_names = schedule_offer
+        .iter()
+        .map(|task| task.name.as_str())
+        .collect::<Vec<_>>()
+        .join("、");
+    let schedule_offer_ids = schedule_offer.iter().map(|task| task.id.clone()).collect::<Vec<_>>();
+    let mut schedule_error = use_signal(|| None::<String>);
+    use_future(move || async move {
+        // 加载自定义任务并合并到任务列表中，内置任务先应用配置覆盖；配置较大时也不阻塞窗口显示
+        let (tasks, kiosk, missing) = tokio::task::spawn_blocking(|| {
+            let user_config = load_user_config();
+            let kiosk = if *KIOSK_MODE { kiosk_task_list(&user_config.overrides) } else { Vec::new() };
+            let tasks = merge_tasks(user_config);
+            let missing = tasks
+                .iter()
+                .filter(|task| !task.tool_installed())
+                .map(|task| task.id.clone())
+                .collect::<HashSet<_>>();
+            if !missing.is_empty() {
+                log(&format!("未检测到所属工具或程序，默认隐藏 {} 个任务", missing.len()));
+            }
+            (tasks, kiosk, missing)
+        })
+        .await
+        .unwrap_or_default();
+        missing_tool_tasks.set(missing);
+        std::thread::spawn(prune_task_logs);
+        // 按规则中的 schedule 同步计划任务；审计和信息亭模式下不修改系统
+        if !*AUDIT_MODE && !*KIOSK_MODE {
+            let schedule_tasks = tasks.clone();
+            std::thread::spawn(move || sync_schedule_jobs(&schedule_tasks));
+            std::thread::spawn(prune_quarantine);
+        }
+        let mut pending = tasks
+            .iter()
+            .filter(|task| task.size_cache_key().is_some())
+            .cloned()
+            .collect::<Vec<_>>();
+        loaded_tasks.set(Some((tasks, kiosk)));
+        growth_warnings.set(tokio::task::spawn_blocking(growth_alerts).await.unwrap_or_default());
+
+        let total = pending.len();
+        while !pending.is_empty() {
+            prescan_progress.set(Some((total - pending.len(), total)));
+            let current = *selected_category.peek();
+            let index = pending
+                .iter()
+                .position(|task| current.is_none_or(|category| category == task.category))
+                .unwrap_or(0);
+            prescan_size(pending.remove(index)).await;
+        }
+        prescan_progress.set(None);
+
+        // 任务预扫描结束后再记录目录增长，避免与任务扫描争抢磁盘
+        let _ = tokio::task::spawn_blocking(record_growth_samples).await;
+        growth_warnings.set(growth_alerts());
+    });
+
+    let mut show_selection_drawer = use_signal(|| true);
+    let mut show_batch_confirmation = use_signal(|| None::<Vec<CleanTask>>);
+    let mut batch_opt_outs = use_signal(HashSet::<String>::new);
+    let selected_task_list = all_tasks
+        .iter()
+        .filter(|task| selected_tasks().contains(&task.id))
+        .cloned()
+        .collect::<Vec<_>>();
+    let batch_task_list = selected_task_list.clone();
+    let confirmed_task_list = selected_task_list.clone();
+    let flagged_ids = show_batch_confirmation()
+        .map(|flagged| flagged.iter().map(|task| task.id.clone()).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    // 上次中途被关闭的批量清理：显示已完成的任务，可继续剩余任务
+    let mut interrupted_batch = use_signal(load_batch_journal);
+    let interrupted_summary = interrupted_batch().filter(|_| !tasks_loading).map(|journal| {
+        let finished = journal
+            .finished
+            .iter()
+            .map(|entry| {
+                let detail = match &entry.status {
+                    BatchTaskStatus::Succeeded => "✅ 已完成".to_string(),
+                    BatchTaskStatus::Failed(e) => format!("❌ 失败: {}", e),
+                    BatchTaskStatus::Skipped => "⏭️ 已跳过（未确认、限时结束或无需清理）".to_string(),
+                };
+                (entry.task_name.clone(), detail)
+            })
+            .collect::<Vec<_>>();
+        let remaining = journal
+            .remaining()
+            .into_iter()
+            .map(|id| {
+                let task = all_tasks.iter().find(|task| task.id == id);
+                let detail = if task.is_none() {
+                    "规则已不存在，无法继续".to_string()
+                } else if journal.running.as_deref() == Some(id.as_str()) {
+                    "⚠️ 中断时正在执行，可能只完成了一部分".to_string()
+                } else {
+                    "未执行".to_string()
+                };
+                (task.map_or(id.clone(), |task| task.name.clone()), detail)
+            })
+            .collect::<Vec<_>>();
+        (journal.time_text(), finished, remaining)
+    });
+    let resume_task_list = interrupted_batch()
+        .filter(|_| !*AUDIT_MODE)
+        .map(|journal| {
+            journal
+                .remaining()
+                .iter()
+                .filter_map(|id| all_tasks.iter().find(|task| &task.id == id).cloned())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let resume_count = resume_task_list.len();
+    let audit_tasks = all_tasks.clone();
+    let mut show_diagnostics = use_signal(|| false);
+    let mut audit_export = use_signal(|| None::<Result<String, String>>);
+
+    let mut show_confirmation = use_signal(|| None::<CleanTask>);
+    // 确认弹窗中展开后为空的变量，执行时会被拒绝，提前提示
+    let confirmation_unresolved = show_confirmation()
+        .map(|task| {
+            let mut unresolved = find_unresolved_variables(&task.command);
+            if let Some(path_check) = &task.path_check {
+                unresolved.extend(find_unresolved_variables(path_check));
+            }
+            for pattern in &task.patterns {
+                unresolved.extend(find_unresolved_variables(pattern));
+            }
+            unresolved.dedup();
+            unresolved.join(", ")
+        })
+        .unwrap_or_default();
+    // 高风险或大体积删除：确认弹窗的初始焦点放在"取消"上
+    let confirmation_dangerous = show_confirmation()
+        .is_some_and(|task| task.risk == RiskLevel::High || task.large_delete_size().is_some());
+    let mut large_delete_ack = use_signal(|| false);
+    // 所属程序（process_check）正在运行时的提示：任务和正在运行的进程名
+    let mut process_warning = use_signal(|| None::<(CleanTask, Vec<String>)>);
+    let mut process_closing = use_signal(|| false);
+    let mut process_close_error = use_signal(|| None::<String>);
+    let process_warning_text = process_warning()
+        .map(|(task, running)| {
+            format!(
+                "{} 正在运行，清理\"{}\"时缓存文件可能被占用，部分文件会删除失败。",
+                running.join("、"),
+                task.name
+            )
+        })
+        .unwrap_or_default();
+    let process_close_label = if process_closing() { "正在关闭…" } else { "关闭程序并清理" };
+    let mut high_risk_ack = use_signal(|| false);
+    let show_item_picker = use_signal(|| None::<CleanTask>);
+    let mut show_history = use_signal(|| false);
+    let mut show_large_files = use_signal(|| false);
+    let mut show_run_queue = use_signal(|| false);
+    let mut show_rule_wizard = use_signal(|| None::<String>);
+    let mut show_rule_import = use_signal(|| false);
+    let mut show_simulation = use_signal(|| None::<Vec<CleanTask>>);
+    let simulation_tasks = batch_task_list.clone();
+    let mut search_query = use_signal(String::new);
+    let task_names = all_tasks.iter().map(|task| task.name.clone()).collect::<Vec<_>>();
+    // 拖放文件夹 - 悬停时高亮窗口，松开后打开分析
+    let mut file_hovering = use_signal(|| false);
+    let mut dropped_folder = use_signal(|| None::<std::path::PathBuf>);
+    let dropped_folder_rule = dropped_folder().and_then(|folder| {
+        let folder = normalize_path_for_compare(&folder.to_string_lossy());
+        all_tasks
+            .iter()
+            .find(|task| {
+                task.get_expanded_path()
+                    .is_some_and(|path| normalize_path_for_compare(&path) == folder)
+            })
+            .map(|task| task.name.clone())
+    });
+
+    let theme_icon = if theme_mode() == ThemeMode::Dark {
+        "🌙"
+    } else {
+        "☀️"
+    };
+
+    let categories = std::iter::once(("全部", None))
+        .chain(CleanCategory::ALL.iter().map(|category| (category.label(), Some(*category))))
+        .collect::<Vec<_>>();
+
+    let category_title = selected_category().map(|category| category.label()).unwrap_or("全部");
+
+    // 按窗口宽度切换窄布局
+    let (root_reference, root_size) = use_node_signal();
+    let window_width = root_size().area.width();
+    let window_height = root_size().area.height();
+    let narrow = window_width > 0.0 && window_width < NARROW_LAYOUT_WIDTH;
+
+    // 所有任务用到的标签（按小写去重排序），点击后批量选中带该标签的任务
+    let all_tags = all_tasks
+        .iter()
+        .flat_map(|task| task.tags.iter().map(|tag| tag.trim().to_lowercase()))
+        .filter(|tag| !tag.is_empty())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .map(|tag| {
+            let ids = all_tasks
+                .iter()
+                .filter(|task| task.has_tag(&tag) && !hidden_tasks.contains(&task.id))
+                .filter(|task| !(task.requires_admin && !*IS_ELEVATED) && !task.is_item_only())
+                .map(|task| task.id.clone())
+                .collect::<Vec<_>>();
+            (format!("#{} ({})", tag, ids.len()), tag, ids)
+        })
+        .collect::<Vec<_>>();
+
+    // 按分类分组，"全部"视图下显示分组标题，便于跨分类批量选择
+    let show_group_headers = selected_category().is_none();
+    let list_rows = CleanCategory::ALL
+        .iter()
+        .filter(|category| selected_category().is_none_or(|selected| selected == **category))
+        .flat_map(|category| {
+            let tasks = all_tasks
+                .iter()
+                .filter(|task| task.category == *category && task.matches_search(&search_query()))
+                .filter(|task| !hidden_tasks.contains(&task.id))
+                .cloned()
+                .map(TaskListRow::Task)
+                .collect::<Vec<_>>();
+            let header = (show_group_headers && !tasks.is_empty())
+                .then(|| TaskListRow::Header(format!("{} ({})", category.label(), tasks.len())));
+            header.into_iter().chain(tasks)
+        })
+        .collect::<Vec<_>>();
+
+    // 只渲染可见范围附近的行，上下用空白撑开滚动高度；切换分类时恢复各自的滚动位置
+    let mut list_scroll = use_scroll_controller(ScrollConfig::default);
+    let mut scroll_positions = use_signal(HashMap::<Option<CleanCategory>, i32>::new);
+    let (list_reference, list_size) = use_node_signal();
+    let row_heights = list_rows.iter().map(TaskListRow::estimated_height).collect::<Vec<_>>();
+    let (first_row, last_row, top_space, bottom_space) = visible_row_range(
+        &row_heights,
+        -*list_scroll.y().read() as f32 - TASK_LIST_HEADER_ESTIMATE,
+        list_size().area.height(),
+    );
+    let list_empty = list_rows.is_empty();
+    // 当前分类中因未安装而隐藏（或显示全部时本会隐藏）的任务数
+    let missing_in_category = all_tasks
+        .iter()
+        .filter(|task| selected_category().is_none_or(|category| category == task.category))
+        .filter(|task| missing_tool_tasks.read().contains(&task.id))
+        .count();
+
+    // 本分类中无需确认、可整体执行的低风险任务，一键通过批量引擎依次清理
+    let category_safe_tasks = selected_category()
+        .map(|category| {
+            all_tasks
+                .iter()
+                .filter(|task| {
+                    task.category == category
+                        && !hidden_tasks.contains(&task.id)
+                        && task.risk == RiskLevel::Low
+                        && !task.needs_confirmation()
+                        && !task.is_item_only()
+                        && !(task.requires_admin && !*IS_ELEVATED)
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let category_safe_count = category_safe_tasks.len();
+    // 限时清理的候选：所有分类中同样条件的低风险任务，不含多次执行都没有释放空间的任务
+    let time_box_tasks = all_tasks
+        .iter()
+        .filter(|task| {
+            task.risk == RiskLevel::Low
+                && !hidden_tasks.contains(&task.id)
+                && !task.needs_confirmation()
+                && !task.is_item_only()
+                && !(task.requires_admin && !*IS_ELEVATED)
+                && !is_stale_task(&task.id)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let time_box_minutes = SETTINGS.time_box_minutes.max(1);
+
+    // 磁盘空间告急 - 定期查询系统盘空间，低于告急线时显示紧急清理横幅，执行中持续显示到达目标为止
+    let mut system_space = use_signal(|| volume_space(&system_drive_root()));
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(STATUS_REFRESH_INTERVAL).await;
+            let space = volume_space(&system_drive_root());
+            if *system_space.peek() != space {
+                system_space.set(space);
+            }
+        }
+    });
+    let mut emergency_running = use_signal(|| false);
+    let mut emergency_dismissed = use_signal(|| false);
+    let mut emergency_planning = use_signal(|| false);
+    let mut emergency_plan = use_signal(|| None::<Vec<(CleanTask, u64)>>);
+    let emergency_candidates = time_box_tasks.clone();
+    let emergency_space = system_space().filter(|(free, total)| {
+        !*AUDIT_MODE && !emergency_dismissed() && (emergency_running() || below_emergency_line(*free, *total))
+    });
+    // (可用空间说明, 目标可用空间, 距目标的进度百分比)
+    let emergency_view = emergency_space.map(|(free, total)| {
+        let target = emergency_target(total);
+        (
+            format!(
+                "可用 {}（{:.1}%），目标 {}",
+                format_size(free),
+                free as f64 * 100.0 / total as f64,
+                format_size(target)
+            ),
+            target,
+            (free as f32 / target.max(1) as f32 * 100.0).min(100.0),
+        )
+    });
+    let emergency_rows = emergency_plan().map(|plan| {
+        plan.iter()
+            .map(|(task, bytes)| (task.name.clone(), format_size(*bytes)))
+            .collect::<Vec<_>>()
+    });
+    let emergency_tasks = emergency_plan()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(task, _)| task)
+        .collect::<Vec<_>>();
+    let show_time_box = !*AUDIT_MODE
+        && !show_batch_mode()
+        && !time_box_tasks.is_empty()
+        && !matches!(app_state(), AppState::Running(_));
+    let show_run_category = !*AUDIT_MODE
+        && !show_batch_mode()
+        && category_safe_count > 0
+        && !matches!(app_state(), AppState::Running(_));
+    let visible_rows = list_rows[first_row..last_row].to_vec();
+    // 可被批量选中的任务，按列表显示顺序排列
+    let selection_order = list_rows
+        .iter()
+        .filter_map(|row| match row {
+            TaskListRow::Task(task) if !task.is_item_only() && !(task.requires_admin && !*IS_ELEVATED) => {
+                Some(task.id.clone())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if *KIOSK_MODE {
+        return rsx!(KioskView {
+            tasks: kiosk_tasks,
+            loading: tasks_loading,
+            app_state: app_state,
+            progress: progress,
+            selected_tasks: selected_tasks,
+            theme: theme,
+        });
+    }
+
+    rsx!(
+
+        // Apple风格主界面
+        rect {
+            reference: root_reference,
+            width: "100%",
+            height: "100%",
+            padding: "20",
+            // 启用背景材质时露出系统的 Mica / 亚克力背景
+            background: if *BACKDROP_ACTIVE { "transparent" } else { theme.background_primary },
+            color: theme.label_primary,
+            font_family: theme.font_family(),
+            direction: "vertical",  // 垂直布局，让内容自动填充
+            border: if file_hovering() { "2 solid {theme.accent}" } else { "0 solid transparent" },
+            onglobalkeydown: move |event: KeyboardEvent| {
+                held_modifiers.set(event.modifiers.union(key_modifier(&event.key)));
+            },
+            onglobalkeyup: move |event: KeyboardEvent| {
+                held_modifiers.set(event.modifiers.difference(key_modifier(&event.key)));
+            },
+            onglobalfilehover: move |_| file_hovering.set(true),
+            onglobalfilehovercancelled: move |_| file_hovering.set(false),
+            onfiledrop: move |event: FileEvent| {
+                file_hovering.set(false);
+                match event.file_path.clone() {
+                    Some(path) if path.is_dir() => {
+                        record_feature("拖放分析");
+                        dropped_folder.set(Some(path));
+                    }
+                    Some(path) => log(&format!("忽略拖放的文件（仅支持文件夹）: {}", path.display())),
+                    None => {}
+                }
+            },
+
+            // 标题栏 - 类似macOS窗口标题，窄窗口时工具栏换到标题下方
+            rect {
+                direction: if narrow { "vertical" } else { "horizontal" },
+                width: "100%",
+                height: "auto",
+                main_align: "space_between",
+                cross_align: if narrow { "start" } else { "center" },
+                padding: "0 0 20 0",
+
+                rect {
+                    direction: "horizontal",
+                    cross_align: "center",
+
+                    label {
+                        font_size: "24",
+                        font_weight: "bold",
+                        "WinCleaner"
+                    }
+
+                    rect {
+                        width: "10"
+                    }
+
+                    label {
+                        font_size: "16",
+                        color: if file_hovering() { theme.accent } else { theme.label_secondary },
+                        if file_hovering() { "松开以分析该文件夹" } else { "系统清理工具" }
+                    }
+                }
+
+                if narrow {
+                    rect {
+                        height: "8"
+                    }
+                }
+
+                // 主题切换按钮 - 类似macOS控制中心
+                rect {
+                    direction: "horizontal",
+                    cross_align: "center",
+                    padding: "8 12",
+                    background: theme.background_tertiary,
+                    corner_radius: "8",
+                    a11y_role: "toolbar",
+                    a11y_name: "窗口设置",
+
+                    label {
+                        font_size: "14",
+                        color: if *IS_ELEVATED { theme.accent } else { theme.label_secondary },
+                        if *IS_ELEVATED { "🛡️ 管理员" } else { "标准用户" }
+                    }
+
+                    rect {
+                        width: "16"
+                    }
+
+                    label {
+                        font_size: "14",
+                        color: theme.label_secondary,
+                        "主题"
+                    }
+
+                    rect {
+                        width: "8"
+                    }
+
+                    Button {
+                        onclick: move |_| {
+                            let new_mode = match theme_mode() {
+                                ThemeMode::Dark => ThemeMode::Light,
+                                ThemeMode::Light => ThemeMode::Dark,
+                            };
+                            theme_mode.set(new_mode);
+                            if *BACKDROP_ACTIVE {
+                                platform.with_window(move |window| window.set_theme(Some(window_theme(new_mode))));
+                            }
+                            let mut session = load_session_state();
+                            session.theme = new_mode;
+                            save_session_state(&session);
+                        },
+                        theme: theme_with!(ButtonTheme {
+                            background: std::borrow::Cow::Borrowed("transparent"),
+                            hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                        }),
+                        label {
+                            font_size: "14",
+                            a11y_name: "切换深色/浅色主题",
+                            "{theme_icon}"
+                        }
+                    }
+
+                    rect {
+                        width: "16"
+                    }
+
+                    // 开关与说明文字组合在一起，读屏软件将文字作为开关名称
+                    rect {
+                        direction: "horizontal",
+                        cross_align: "center",
+                        a11y_role: "group",
+                        a11y_name: "批量模式",
+
+                        label {
+                            font_size: "14",
+                            color: theme.label_secondary,
+                            "批量模式"
+                        }
+
+                        rect {
+                            width: "8"
+                        }
+
+                        Switch {
+                            enabled: show_batch_mode(),
+                            ontoggled: move |_| show_batch_mode.set(!show_batch_mode()),
+                        }
+                    }
+
+                    rect {
+                        width: "16"
+                    }
+
+                    rect {
+                        direction: "horizontal",
+                        cross_align: "center",
+                        a11y_role: "group",
+                        a11y_name: "窗口置顶",
+
+                        label {
+                            font_size: "14",
+                            color: theme.label_secondary,
+                            "置顶"
+                        }
+
+                        rect {
+                            width: "8"
+                        }
+
+                        Switch {
+                            enabled: always_on_top(),
+                            ontoggled: move |_| {
+                                let on_top = !always_on_top();
+                                always_on_top.set(on_top);
+                                platform.with_window(move |window| window.set_window_level(window_level(on_top)));
+
+                                let mut session = load_session_state();
+                                session.always_on_top = on_top;
+                                save_session_state(&session);
+                            },
+                        }
+                    }
+
+                    rect {
+                        width: "16"
+                    }
+
+                    // 审计模式下不写入配置文件
+                    if !*AUDIT_MODE {
+                        Button {
+                            onclick: move |_| show_rule_wizard.set(Some(String::new())),
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed("transparent"),
+                                hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                            }),
+                            label {
+                                font_size: "14",
+                                color: theme.label_secondary,
+                                "新建规则"
+                            }
+                        }
+
+                        Button {
+                            onclick: move |_| show_rule_import.set(true),
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed("transparent"),
+                                hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                            }),
+                            label {
+                                font_size: "14",
+                                color: theme.label_secondary,
+                                "导入规则"
+                            }
+                        }
+                    }
+
+                    Button {
+                        onclick: move |_| show_large_files.set(true),
+                        theme: theme_with!(ButtonTheme {
+                            background: std::borrow::Cow::Borrowed("transparent"),
+                            hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                        }),
+                        label {
+                            font_size: "14",
+                            color: theme.label_secondary,
+                            "大文件"
+                        }
+                    }
+
+                    if !*AUDIT_MODE {
+                        Button {
+                            onclick: move |_| show_run_queue.set(true),
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed("transparent"),
+                                hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                            }),
+                            label {
+                                font_size: "14",
+                                color: theme.label_secondary,
+                                "队列"
+                            }
+                        }
+                    }
+
+                    Button {
+                        onclick: move |_| show_history.set(true),
+                        theme: theme_with!(ButtonTheme {
+                            background: std::borrow::Cow::Borrowed("transparent"),
+                            hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                        }),
+                        label {
+                            font_size: "14",
+                            color: theme.label_secondary,
+                            "历史"
+                        }
+                    }
+
+                    Button {
+                        onclick: move |_| show_diagnostics.set(true),
+                        theme: theme_with!(ButtonTheme {
+                            background: std::borrow::Cow::Borrowed("transparent"),
+                            hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                        }),
+                        label {
+                            font_size: "14",
+                            color: theme.label_secondary,
+                            "诊断"
+                        }
+                    }
+                }
+            }
+
+            if !drives().is_empty() {
+                DiskDashboard {
+                    drives: drives(),
+                    narrow: narrow,
+                    theme: theme,
+                }
+            }
+
+            // 审计模式横幅 - 说明当前为只读，并提供报告导出
+            if *AUDIT_MODE {
+                rect {
+                    width: "100%",
+                    direction: "horizontal",
+                    main_align: "space_between",
+                    cross_align: "center",
+                    padding: "10 16",
+                    margin: "0 0 12 0",
+                    background: theme.background_secondary,
+                    corner_radius: "12",
+                    a11y_role: "status",
+
+                    rect {
+                        width: "calc(100% - 120)",
+
+                        label {
+                            font_size: "14",
+                            font_weight: "semibold",
+                            color: theme.warning,
+                            "🔍 审计模式：所有清理和规则写入均已禁用，只扫描并报告"
+                        }
+
+                        match audit_export() {
+                            Some(Ok(path)) => rsx!(
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_secondary,
+                                    "报告已导出: {path}"
+                                }
+                            ),
+                            Some(Err(e)) => rsx!(
+                                label {
+                                    font_size: "12",
+                                    color: theme.danger,
+                                    "{e}"
+                                }
+                            ),
+                            None => rsx!(),
+                        }
+                    }
+
+                    FilledButton {
+                        onclick: move |_| {
+                            let tasks = audit_tasks.clone();
+                            spawn(async move {
+                                let result = tokio::task::spawn_blocking(move || export_audit_report(&tasks))
+                                    .await
+                                    .unwrap_or_else(|e| Err(format!("报告生成失败: {}", e)));
+                                audit_export.set(Some(result));
+                            });
+                        },
+                        label {
+                            color: "white",
+                            "导出报告"
+                        }
+                    }
+                }
+            }
+
+            // 主内容区域 - 类似macOS侧边栏布局，窄窗口时侧边栏收起为顶部分类条
+            rect {
+                direction: if narrow { "vertical" } else { "horizontal" },
+                width: "100%",
+                height: "fill",  // 使用fill填充剩余空间
+
+                // 左侧边栏 - 分类和通知区域
+                rect {
+                    width: if narrow { "100%" } else { "200" },
+                    direction: "vertical",
+                    height: if narrow { "auto" } else { "fill" },
+
+                    // 分类选择区域
+                    rect {
+                        width: "100%",
+                        padding: if narrow { "8" } else { "16" },
+                        direction: if narrow { "horizontal" } else { "vertical" },
+                        background: theme.background_secondary,
+                        corner_radius: "12",
+                        margin: "0 0 12 0",
+                        a11y_role: "navigation",
+                        a11y_name: "清理分类",
+
+                        if !narrow {
+                            label {
+                                font_size: "16",
+                                font_weight: "semibold",
+                                color: theme.label_primary,
+                                margin: "0 0 16 0",
+                                a11y_role: "heading",
+                                "清理分类"
+                            }
+                        }
+
+                        for (name, category) in categories {
+                            Button {
+                                onclick: move |_| {
+                                    let mut positions = scroll_positions();
+                                    positions.insert(selected_category(), *list_scroll.y().peek());
+                                    let restored = positions.get(&category).copied().unwrap_or(0);
+                                    scroll_positions.set(positions);
+                                    selected_category.set(category);
+                                    list_scroll.scroll_to_y(restored);
+                                },
+                                theme: theme_with!(ButtonTheme {
+                                    background: if category == selected_category() {
+                                        std::borrow::Cow::Borrowed(theme.accent)
+                                    } else {
+                                        std::borrow::Cow::Borrowed("transparent")
+                                    },
+                                    hover_background: if category == selected_category() {
+                                        std::borrow::Cow::Borrowed(theme.accent_hover)
+                                    } else {
+                                        std::borrow::Cow::Borrowed(theme.background_tertiary)
+                                    },
+                                }),
+                                label {
+                                    font_size: "14",
+                                    color: if category == selected_category() { "white" } else { theme.label_primary },
+                                    a11y_selected: "{category == selected_category()}",
+                                    "{name}"
+                                }
+                            }
+
+                            rect {
+                                width: if narrow { "6" } else { "0" },
+                                height: if narrow { "0" } else { "6" },
+                            }
+                        }
+                    }
+
+                    // 标签 - 点击后进入批量模式并选中带该标签的所有任务；窄窗口时隐藏标签和提醒卡片
+                    if !narrow && !all_tags.is_empty() {
+                        rect {
+                            width: "100%",
+                            padding: "16",
+                            background: theme.background_secondary,
+                            corner_radius: "12",
+                            margin: "0 0 12 0",
+                            a11y_role: "group",
+                            a11y_name: "标签",
+
+                            label {
+                                font_size: "16",
+                                font_weight: "semibold",
+                                color: theme.label_primary,
+                                margin: "0 0 12 0",
+                                a11y_role: "heading",
+                                "标签"
+                            }
+
+                            for (tag_label, tag, ids) in all_tags {
+                                Button {
+                                    onclick: move |_| {
+                                        show_batch_mode.set(true);
+                                        search_query.set(format!("#{}", tag));
+                                        let mut selected = selected_tasks();
+                                        selected.extend(ids.iter().cloned());
+                                        selected_tasks.set(selected);
+                                    },
+                                    theme: theme_with!(ButtonTheme {
+                                        background: std::borrow::Cow::Borrowed("transparent"),
+                                        hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                    }),
+                                    label {
+                                        font_size: "14",
+                                        color: theme.label_primary,
+                                        "{tag_label}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some((space_text, target, space_progress)) = emergency_view {
+                        rect {
+                            width: "100%",
+                            padding: "16",
+                            background: theme.background_secondary,
+                            corner_radius: "12",
+                            margin: "0 0 12 0",
+                            a11y_role: "alert",
+
+                            label {
+                                font_size: "16",
+                                font_weight: "semibold",
+                                color: theme.danger,
+                                margin: "0 0 8 0",
+                                "🚨 系统盘空间告急"
+                            }
+                            label {
+                                font_size: "12",
+                                color: theme.label_secondary,
+                                "{space_text}"
+                            }
+                            ProgressBar {
+                                progress: space_progress,
+                                show_progress: false,
+                                width: "100%",
+                            }
+
+                            if emergency_running() {
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_secondary,
+                                    margin: "8 0 0 0",
+                                    "正在紧急清理，达到目标后自动停止"
+                                }
+                            } else if emergency_planning() {
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_secondary,
+                                    margin: "8 0 0 0",
+                                    "⟳ 正在快速估算可释放空间…"
+                                }
+                            } else if let Some(rows) = emergency_rows {
+                                if rows.is_empty() {
+                                    label {
+                                        font_size: "12",
+                                        color: theme.label_secondary,
+                                        margin: "8 0 0 0",
+                                        "没有可快速释放空间的低风险任务，请手动删除大文件或卸载程序"
+                                    }
+                                }
+                                for (index, (name, size)) in rows.into_iter().enumerate() {
+                                    label {
+                                        font_size: "12",
+                                        color: theme.label_primary,
+                                        margin: if index == 0 { "8 0 0 0" } else { "0" },
+                                        "{index + 1}. {name} · 约 {size}"
+                                    }
+                                }
+                                if !emergency_tasks.is_empty() {
+                                    Button {
+                                        onclick: move |_| {
+                                            let tasks = emergency_tasks.clone();
+                                            let stop = BatchStop::FreeSpace(target);
+                                            emergency_running.set(true);
+                                            spawn(async move {
+                                                record_feature("紧急清理");
+                                                run_batch_clean_until(tasks, HashSet::new(), Some(stop), app_state, progress, selected_tasks).await;
+                                                emergency_running.set(false);
+                                                emergency_plan.set(None);
+                                                system_space.set(volume_space(&system_drive_root()));
+                                            });
+                                        },
+                                        theme: theme_with!(ButtonTheme {
+                                            background: std::borrow::Cow::Borrowed(theme.danger),
+                                            hover_background: std::borrow::Cow::Borrowed(theme.danger_hover),
+                                        }),
+                                        label {
+                                            font_size: "13",
+                                            color: "white",
+                                            "开始紧急清理"
+                                        }
+                                    }
+                                }
+                            } else {
+                                Button {
+                                    onclick: move |_| {
+                                        let candidates = emergency_candidates.clone();
+                                        emergency_planning.set(true);
+                                        spawn(async move {
+                                            let plan = tokio::task::spawn_blocking(move || plan_emergency(candidates))
+                                                .await
+                                                .unwrap_or_default();
+                                            emergency_plan.set(Some(plan));
+                                            emergency_planning.set(false);
+                                        });
+                                    },
+                                    theme: theme_with!(ButtonTheme {
+                                        background: std::borrow::Cow::Borrowed(theme.danger),
+                                        hover_background: std::borrow::Cow::Borrowed(theme.danger_hover),
+                                    }),
+                                    label {
+                                        font_size: "13",
+                                        color: "white",
+                                        "制定紧急清理计划"
+                                    }
+                                }
+                            }
+
+                            if !emergency_running() {
+                                Button {
+                                    onclick: move |_| emergency_dismissed.set(true),
+                                    theme: theme_with!(ButtonTheme {
+                                        background: std::borrow::Cow::Borrowed("transparent"),
+                                        hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                        padding: std::borrow::Cow::Borrowed("2 6"),
+                                    }),
+                                    label {
+                                        font_size: "12",
+                                        color: theme.label_tertiary,
+                                        "忽略"
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // 定期清理 - 已设置的计划，以及把常用的手动清理转为每周计划的建议
+                    if !narrow && (!schedule_rows.is_empty() || !schedule_offer.is_empty()) {
+                        rect {
+                            width: "100%",
+                            padding: "16",
+                            background: theme.background_secondary,
+                            corner_radius: "12",
+                            margin: "0 0 12 0",
+                            a11y_role: "group",
+                            a11y_name: "定期清理",
+
+                            label {
+                                font_size: "16",
+                                font_weight: "semibold",
+                                color: theme.label_primary,
+                                margin: "0 0 12 0",
+                                a11y_role: "heading",
+                                "🗓 定期清理"
+                            }
+
+                            for (label_text, count, next_run) in schedule_rows {
+                                rect {
+                                    width: "100%",
+                                    margin: "0 0 8 0",
+
+                                    label {
+                                        font_size: "13",
+                                        color: theme.label_primary,
+                                        "{label_text} {SCHEDULE_HOUR}:00 · {count} 个规则"
+                                    }
+                                    label {
+                                        font_size: "12",
+                                        color: theme.label_secondary,
+                                        "下次执行：{next_run}"
+                                    }
+                                }
+                            }
+
+                            if !schedule_offer.is_empty() {
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_secondary,
+                                    margin: "0 0 6 0",
+                                    "最近经常手动清理：{schedule_offer_names}"
+                                }
+                                rect {
+                                    direction: "horizontal",
+
+                                    Button {
+                                        onclick: move |_| {
+                                            match append_schedule_overrides(&schedule_offer_ids, TaskSchedule::Weekly) {
+                                                Ok(()) => {
+                                                    schedule_error.set(None);
+                                                    loaded_tasks.with_mut(|loaded| {
+                                                        if let Some((tasks, _)) = loaded {
+                                                            for task in tasks.iter_mut().filter(|task| schedule_offer_ids.contains(&task.id)) {
+                                                                task.schedule = Some(TaskSchedule::Weekly);
+                                                            }
+                                                            let schedule_tasks = tasks.clone();
+                                                            std::thread::spawn(move || sync_schedule_jobs(&schedule_tasks));
+                                                        }
+                                                    });
+                                                }
+                                                Err(e) => schedule_error.set(Some(e)),
+                                            }
+                                        },
+                                        theme: theme_with!(ButtonTheme {
+                                            background: std::borrow::Cow::Borrowed("transparent"),
+                                            hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                            padding: std::borrow::Cow::Borrowed("2 6"),
+                                        }),
+                                        label {
+                                            font_size: "12",
+                                            color: theme.accent,
+                                            "为这些任务创建每周计划"
+                                        }
+                                    }
+
+                                    Button {
+                                        onclick: move |_| {
+                                            acknowledge(SCHEDULE_OFFER_KEY, 0);
+                                            schedule_error.set(None);
+                                        },
+                                        theme: theme_with!(ButtonTheme {
+                                            background: std::borrow::Cow::Borrowed("transparent"),
+                                            hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                            padding: std::borrow::Cow::Borrowed("2 6"),
+                                        }),
+                                        label {
+                                            font_size: "12",
+                                            color: theme.label_secondary,
+                                            "不再提示"
+                                        }
+                                    }
+                                }
+                            }
+
+                            if let Some(error) = schedule_error() {
+                                label {
+                                    font_size: "12",
+                                    color: theme.danger,
+                                    "{error}"
+                                }
+                            }
+                        }
+                    }
+
+                    // 增长提醒 - 监测目录短时间内异常增长，可直接为其创建规则
+                    if !narrow && (!growth_rows.is_empty() || acknowledged_count > 0) {
+                        rect {
+                            width: "100%",
+                            padding: "16",
+                            background: theme.background_secondary,
+                            corner_radius: "12",
+                            margin: "0 0 12 0",
+                            a11y_role: "group",
+                            a11y_name: "增长提醒",
+
+                            label {
+                                font_size: "16",
+                                font_weight: "semibold",
+                                color: theme.label_primary,
+                                margin: "0 0 12 0",
+                                a11y_role: "heading",
+                                "📈 增长提醒"
+                            }
+
+                            for (path, summary, wizard_path, key, current) in growth_rows {
+                                rect {
+                                    width: "100%",
+                                    margin: "0 0 8 0",
+
+                                    label {
+                                        font_size: "13",
+                                        color: theme.label_primary,
+                                        "{path}"
+                                    }
+                                    label {
+                                        font_size: "12",
+                                        color: theme.warning,
+                                        "{summary}"
+                                    }
+                                    rect {
+                                        direction: "horizontal",
+
+                                        if !*AUDIT_MODE {
+                                            Button {
+                                                onclick: move |_| show_rule_wizard.set(Some(wizard_path.clone())),
+                                                theme: theme_with!(ButtonTheme {
+                                                    background: std::borrow::Cow::Borrowed("transparent"),
+                                                    hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                                    padding: std::borrow::Cow::Borrowed("2 6"),
+                                                }),
+                                                label {
+                                                    font_size: "12",
+                                                    color: theme.accent,
+                                                    "创建规则…"
+                                                }
+                                            }
+                                        }
+
+                                        Button {
+                                            onclick: move |_| {
+                                                acknowledge(&key, current);
+                                                growth_warnings.set(growth_alerts());
+                                            },
+                                            theme: theme_with!(ButtonTheme {
+                                                background: std::borrow::Cow::Borrowed("transparent"),
+                                                hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                                padding: std::borrow::Cow::Borrowed("2 6"),
+                                            }),
+                                            label {
+                                                font_size: "12",
+                                                color: theme.label_secondary,
+                                                "忽略"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+
+                            if acknowledged_count > 0 {
+                                Button {
+                                    onclick: move |_| show_acknowledged.set(true),
+                                    theme: theme_with!(ButtonTheme {
+                                        background: std::borrow::Cow::Borrowed("transparent"),
+                                        hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                        padding: std::borrow::Cow::Borrowed("2 6"),
+                                    }),
+                                    label {
+                                        font_size: "12",
+                                        color: theme.label_tertiary,
+                                        "已忽略 {acknowledged_count} 条…"
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some((message, key)) = av_hint_view.filter(|_| !narrow) {
+                        rect {
+                            width: "100%",
+                            padding: "16",
+                            background: theme.background_secondary,
+                            corner_radius: "12",
+                            margin: "0 0 12 0",
+                            a11y_role: "alert",
+
+                            label {
+                                font_size: "14",
+                                font_weight: "semibold",
+                                color: theme.warning,
+                                margin: "0 0 8 0",
+                                "🛡️ 杀毒软件可能拖慢了清理"
+                            }
+                            label {
+                                font_size: "12",
+                                color: theme.label_secondary,
+                                "{message}"
+                            }
+                            Button {
+                                onclick: move |_| {
+                                    acknowledge(&key, 0);
+                                    av_hint.set(None);
+                                },
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed("transparent"),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                    padding: std::borrow::Cow::Borrowed("2 6"),
+                                }),
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_tertiary,
+                                    "不再提示"
+                                }
+                            }
+                        }
+                    }
+
+                    // 通知气泡独立区域 - 放在分类下方但分隔开，就绪和运行中状态由底部状态栏显示
+                    if !matches!(app_state(), AppState::Idle | AppState::Running(_)) {
+                        NotificationBubble {
+                            app_state: app_state(),
+                            theme: theme
+                        }
+                    }
+
+                    rect {
+                        height: "16"
+                    }
+
+                    // 进度条（批量模式时显示）- Apple风格
+                    if show_batch_mode() && matches!(app_state(), AppState::Running(_)) {
+                        rect {
+                            padding: "16",
+                            background: theme.background_secondary,
+                            corner_radius: "12",
+                            margin: "0 0 20 0",
+                            width: "100%",
+
+                            rect {
+                                direction: "horizontal",
+                                main_align: "space_between",
+                                cross_align: "center",
+                                margin: "0 0 8 0",
+
+                                label {
+                                    font_size: "14",
+                                    font_weight: "medium",
+                                    "批量清理进度"
+                                }
+
+                                CancelButton {
+                                    theme: theme,
+                                }
+                            }
+
+                            ProgressBar {
+                                progress: (progress() * 100.0) as f32,
+                                show_progress: true,
+                                width: "100%",
+                            }
+                        }
+                    }
+
+                }
+
+                rect {
+                    width: if narrow { "0" } else { "20" },
+                }
+
+                // 右侧任务列表 - 类似macOS主内容区域
+                rect {
+                    reference: list_reference,
+                    width: if narrow { "100%" } else { "calc(100% - 220)" },
+                    padding: "16",
+                    background: theme.background_secondary,
+                    corner_radius: "12",
+                    height: "fill",  // 确保占满父容器高度
+                    a11y_role: "list",
+                    a11y_name: "{category_title} 清理任务",
+
+                    ScrollView {
+                        width: "100%",
+                        height: "100%",
+                        scroll_controller: list_scroll,
+
+                        // 列表头部 - 类似Finder工具栏
+                        rect {
+                            direction: "horizontal",
+                            width: "100%",
+                            padding: "0 0 16 0",
+                            main_align: "space_between",
+                            cross_align: "center",
+                            margin: "0 0 16 0",
+
+                            label {
+                                font_size: "18",
+                                font_weight: "semibold",
+                                color: theme.label_primary,
+                                "{category_title}"
+                            }
+
+                            Input {
+                                value: search_query(),
+                                placeholder: "搜索名称、备注或 #标签".to_string(),
+                                width: "220",
+                                onchange: move |value: String| search_query.set(value),
+                            }
+
+                            if missing_in_category > 0 {
+                                Button {
+                                    onclick: move |_| show_all_tasks.set(!show_all_tasks()),
+                                    theme: theme_with!(ButtonTheme {
+                                        background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                        hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                                    }),
+                                    label {
+                                        font_size: "14",
+                                        color: theme.label_secondary,
+                                        if show_all_tasks() { "隐藏未安装 ({missing_in_category})" } else { "显示全部 (+{missing_in_category})" }
+                                    }
+                                }
+                            }
+
+                            if show_run_category {
+                                Button {
+                                    onclick: move |_| {
+                                        let batch = category_safe_tasks.clone();
+                                        spawn(async move {
+                                            run_batch_clean(batch, HashSet::new(), app_state, progress, selected_tasks).await;
+                                        });
+                                    },
+                                    theme: theme_with!(ButtonTheme {
+                                        background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                        hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                                    }),
+                                    label {
+                                        font_size: "14",
+                                        color: theme.label_primary,
+                                        "清理本分类全部安全任务 ({category_safe_count})"
+                                    }
+                                }
+                            }
+
+                            if show_time_box {
+                                Button {
+                                    onclick: move |_| {
+                                        let candidates = time_box_tasks.clone();
+                                        spawn(async move {
+                                            let budget = Duration::from_secs(time_box_minutes * 60);
+                                            let deadline = std::time::Instant::now() + budget;
+                                            let plan = tokio::task::spawn_blocking(move || plan_time_box(candidates, budget))
+                                                .await
+                                                .unwrap_or_default();
+                                            record_feature("限时清理");
+                                            run_batch_clean_until(plan, HashSet::new(), Some(BatchStop::Deadline(deadline)), app_state, progress, selected_tasks).await;
+                                        });
+                                    },
+                                    theme: theme_with!(ButtonTheme {
+                                        background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                        hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                                    }),
+                                    label {
+                                        font_size: "14",
+                                        color: theme.label_primary,
+                                        "⏱ 只花 {time_box_minutes} 分钟"
+                                    }
+                                }
+                            }
+
+                            if show_batch_mode() && !selected_tasks().is_empty() {
+                                rect {
+                                    direction: "horizontal",
+                                    cross_align: "center",
+
+                                    Button {
+                                        onclick: move |_| show_simulation.set(Some(simulation_tasks.clone())),
+                                        theme: theme_with!(ButtonTheme {
+                                            background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                            hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                                        }),
+                                        label {
+                                            font_size: "14",
+                                            color: theme.label_primary,
+                                            "模拟运行"
+                                        }
+                                    }
+
+                                    if !*AUDIT_MODE {
+                                        rect {
+                                            width: "8"
+                                        }
+
+                                        FilledButton {
+                                            onclick: move |_| {
+                                                let batch = batch_task_list.clone();
+                                                // 已有清理在运行时不开始新的批量清理
+                                                if batch.is_empty() || matches!(app_state(), AppState::Running(_)) {
+                                                    return;
+                                                }
+
+                                                // 批量中包含危险或需确认的任务时，先统一确认
+                                                spawn(async move {
+                                                    prescan_uncached(&batch).await;
+                                                    let flagged = batch
+                                                        .iter()
+                                                        .filter(|task| task.needs_confirmation())
+                                                        .cloned()
+                                                        .collect::<Vec<_>>();
+                                                    if flagged.is_empty() {
+                                                        run_batch_clean(batch, HashSet::new(), app_state, progress, selected_tasks).await;
+                                                    } else {
+                                                        // 高风险任务默认不勾选，需逐项主动选择
+                                                        batch_opt_outs.set(
+                                                            flagged
+                                                                .iter()
+                                                                .filter(|task| task.risk == RiskLevel::High)
+                                                                .map(|task| task.id.clone())
+                                                                .collect(),
+                                                        );
+                                                        show_batch_confirmation.set(Some(flagged));
+                                                    }
+                                                });
+                                            },
+
+                                            label {
+                                                font_size: "14",
+                                                color: "white",
+                                                if matches!(app_state(), AppState::Running(_)) {
+                                                    "清理进行中…"
+                                                } else {
+                                                    "清理选中 ({selected_tasks().len()})"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        // 已选任务抽屉 - 跨分类查看和移除已选项
+                        if show_batch_mode() && !selected_task_list.is_empty() {
+                            SelectionDrawer {
+                                tasks: selected_task_list,
+                                expanded: show_selection_drawer(),
+                                on_toggle_expanded: move |_| show_selection_drawer.set(!show_selection_drawer()),
+                                on_remove: move |task_id: String| {
+                                    let mut selected = selected_tasks();
+                                    selected.remove(&task_id);
+                                    selected_tasks.set(selected);
+                                },
+                                theme: theme,
+                            }
+                        }
+
+                        if tasks_loading {
+                            label {
+                                font_size: "14",
+                                color: theme.label_secondary,
+                                "⟳ 正在加载清理规则…"
+                            }
+                        } else if list_empty {
+                            label {
+                                font_size: "14",
+                                color: theme.label_secondary,
+                                if search_query().trim().is_empty() { "该分类下没有清理任务" } else { "没有匹配的清理任务" }
+                            }
+                        } else {
+                            rect {
+                                height: "{top_space}"
+                            }
+
+                            for row in visible_rows {
+                                match row {
+                                    TaskListRow::Header(title) => rsx!(
+                                        label {
+                                            font_size: "14",
+                                            font_weight: "semibold",
+                                            color: theme.label_secondary,
+                                            margin: "4 0 8 0",
+                                            "{title}"
+                                        }
+                                    ),
+                                    TaskListRow::Task(task) => {
+                                        let selection_order = selection_order.clone();
+                                        rsx!(
+                                            TaskCard {
+                                                key: "{task.id}",
+                                                task: task.clone(),
+                                                show_batch_mode: show_batch_mode(),
+                                                selected_tasks: selected_tasks(),
+                                                // 单击或 Ctrl+单击切换单个任务；Shift+单击选中上次单击的任务到当前任务之间的全部任务
+                                                on_toggle: move |_| {
+                                                    let (selected, anchor) = click_selection(
+                                                        &selected_tasks(),
+                                                        &selection_order,
+                                                        selection_anchor().as_deref(),
+                                                        &task.id,
+                                                        held_modifiers().contains(Modifiers::SHIFT),
+                                                    );
+                                                    selected_tasks.set(selected);
+                                                    selection_anchor.set(anchor);
+                                                },
+                                                app_state: app_state.clone(),
+                                                show_confirmation: show_confirmation.clone(),
+                                                process_warning: process_warning,
+                                                show_item_picker: show_item_picker,
+                                                compact: narrow,
+                                                theme: theme,
+                                            }
+                                            rect {
+                                                height: "12"
+                                            }
+                                        )
+                                    }
+                                }
+                            }
+
+                            rect {
+                                height: "{bottom_space}"
+                            }
+                        }
+                    }
+                }
+            }
+
+            // 底部状态栏 - 后台活动、可清理空间与可用空间
+            StatusBar {
+                app_state: app_state(),
+                prescan_progress: prescan_progress(),
+                tasks: all_tasks.clone(),
+                theme: theme,
+            }
+        }
+
+        // 使用Freya内置Popup组件替代自定义对话框
+        if let Some(task) = show_confirmation() {
+            Popup {
+                oncloserequest: move |_| {
+                    large_delete_ack.set(false);
+                    high_risk_ack.set(false);
+                    show_confirmation.set(None);
+                },
+                show_close_button: true,
+                theme: theme_with!(PopupTheme {
+                    background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                    color: std::borrow::Cow::Borrowed(theme.label_primary),
+                    cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                    width: fit_popup_size(460.0, window_width),
+                    height: fit_popup_size(460.0, window_height),
+                }),
+
+                PopupTitle {
+                    label {
+                        color: theme.label_primary,
+                        a11y_role: "heading",
+                        "确认执行清理操作"
+                    }
+                }
+
+                PopupContent {
+                    rect {
+                        height: "100%",
+                        a11y_role: if task.risk == RiskLevel::High { "alert-dialog" } else { "dialog" },
+                        a11y_name: "确认执行清理操作",
+                        a11y_description: "{task.name}：{task.description}",
+                        a11y_modal: "true",
+
+                        // 内容区域使用ScrollView包裹，支持滚动
+                        ScrollView {
+                            height: "calc(100% - 60)",  // 为按钮区域预留空间
+
+                            label {
+                                color: theme.label_primary,
+                                "您确定要执行以下清理操作吗？"
+                            }
+
+                            rect {
+                                height: "10"
+                            }
+
+                            rect {
+                                padding: "16",
+                                background: theme.background_tertiary,
+                                corner_radius: "8",
+
+                                label {
+                                    font_weight: "bold",
+                                    color: theme.label_primary,
+                                    margin: "0 0 8 0",
+                                    "{task.name}"
+                                }
+                                label {
+                                    font_size: "14",
+                                    color: theme.label_secondary,
+                                    margin: "0 0 12 0",
+                                    "{task.description}"
+                                }
+
+                                // 原始命令与展开后的命令对照，避免空变量悄悄改变清理目标
+                                rect {
+                                    width: "100%",
+                                    margin: "0 0 12 0",
+
+                                    if !task.command.trim().is_empty() {
+                                        ExpansionPreview {
+                                            title: "命令".to_string(),
+                                            source: task.command.clone(),
+                                            theme: theme,
+                                        }
+                                    }
+
+                                    if let Some(path_check) = &task.path_check {
+                                        ExpansionPreview {
+                                            title: "检查路径".to_string(),
+                                            source: path_check.clone(),
+                                            theme: theme,
+                                        }
+                                    }
+
+                                    if !task.patterns.is_empty() {
+                                        PatternPreview {
+                                            task: task.clone(),
+                                            theme: theme,
+                                        }
+                                    }
+
+                                    if !confirmation_unresolved.is_empty() {
+                                        label {
+                                            font_size: "12",
+                                            color: theme.danger,
+                                            margin: "6 0 0 0",
+                                            "⚠️ 变量 {confirmation_unresolved} 未定义或为空，执行时将被拒绝"
+                                        }
+                                    }
+                                }
+
+                                if task.risk == RiskLevel::Medium {
+                                    rect {
+                                        padding: "12",
+                                        corner_radius: "6",
+                                        border: "1 solid {theme.warning}",
+
+                                        label {
+                                            font_size: "13",
+                                            color: theme.warning,
+                                            "⚠️ 中风险: 清理后相关应用可能需要重新下载或重新登录"
+                                        }
+                                    }
+                                }
+
+                                // 高风险任务需单独勾选确认
+                                if task.risk == RiskLevel::High {
+                                    rect {
+                                        padding: "12",
+                                        background: theme.danger_background,
+                                        corner_radius: "6",
+                                        border: "1 solid {theme.danger}",
+
+                                        label {
+                                            font_size: "13",
+                                            color: theme.danger,
+                                            "⚠️ 高风险: 此操作可能影响系统或应用的正常使用，且无法撤销！"
+                                        }
+
+                                        Tile {
+                                            leading: rsx!(
+                                                Checkbox {
+                                                    selected: high_risk_ack(),
+                                                }
+                                            ),
+                                            onselect: move |_| high_risk_ack.set(!high_risk_ack()),
+                                            label {
+                                                font_size: "13",
+                                                color: theme.label_primary,
+                                                "我了解风险，仍要执行"
+                                            }
+                                        }
+                                    }
+                                }
+
+                                // 大体积删除保护 - 与危险标记无关，需单独勾选确认
+                                if let Some(bytes) = task.large_delete_size() {
+                                    rect {
+                                        margin: "8 0 0 0",
+                                        padding: "12",
+                                        background: theme.danger_background,
+                                        corner_radius: "6",
+                                        border: "1 solid {theme.danger}",
+
+                                        label {
+                                            font_size: "13",
+                                            color: theme.danger,
+                                            "⚠️ 将永久删除 {format_size(bytes)}（{bytes} 字节），超过 {SETTINGS.large_delete_threshold} 阈值。请确认目标路径正确。"
+                                        }
+
+                                        Tile {
+                                            leading: rsx!(
+                                                Checkbox {
+                                                    selected: large_delete_ack(),
+                                                }
+                                            ),
+                                            onselect: move |_| large_delete_ack.set(!large_delete_ack()),
+                                            label {
+                                                font_size: "13",
+                                                color: theme.label_primary,
+                                                "我确认要删除 {format_size(bytes)}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        // 按钮区域固定底部；Esc 由 Popup 处理为取消。
+                        // 高风险或大体积删除时初始焦点在"取消"上，误按 Enter 不会直接执行
+                        rect {
+                            height: "60",
+                            padding: "12 0 0 0",
+                            direction: "horizontal",
+                            main_align: "end",
+
+                            DialogButton {
+                                text: "取消".to_string(),
+                                onpress: move |_| {
+                                    large_delete_ack.set(false);
+                                    high_risk_ack.set(false);
+                                    show_confirmation.set(None);
+                                },
+                                background: theme.background_tertiary,
+                                hover_background: theme.background_primary,
+                                color: theme.label_secondary,
+                                auto_focus: confirmation_dangerous,
+                                guarded: false,
+                                theme: theme,
+                            }
+
+                            rect {
+                                width: "20"
+                            }
+
+                            DialogButton {
+                                text: "确认".to_string(),
+                                background: task.risk.color(theme),
+                                hover_background: task.risk.hover_color(theme),
+                                color: "white",
+                                auto_focus: !confirmation_dangerous,
+                                guarded: true,
+                                theme: theme,
+                                onpress: move |_| {
+                                    if task.large_delete_size().is_some() && !large_delete_ack() {
+                                        return;
+                                    }
+                                    if task.risk == RiskLevel::High && !high_risk_ack() {
+                                        return;
+                                    }
+
+                                    let task_clone = task.clone();
+                                    large_delete_ack.set(false);
+                                    high_risk_ack.set(false);
+                                    show_confirmation.set(None);
+                                    submit_clean_task(task_clone, app_state);
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // 所属程序正在运行 - 可请求程序正常退出后清理，或忽略提示继续
+        if let Some((task, running)) = process_warning() {
+            Popup {
+                oncloserequest: move |_| {
+                    process_close_error.set(None);
+                    process_warning.set(None);
+                },
+                show_close_button: true,
+                theme: theme_with!(PopupTheme {
+                    background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                    color: std::borrow::Cow::Borrowed(theme.label_primary),
+                    cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                    width: fit_popup_size(440.0, window_width),
+                    height: fit_popup_size(280.0, window_height),
+                }),
+
+                PopupTitle {
+                    label {
+                        color: theme.label_primary,
+                        a11y_role: "heading",
+                        "程序正在运行"
+                    }
+                }
+
+                PopupContent {
+                    rect {
+                        height: "100%",
+                        a11y_role: "alert-dialog",
+                        a11y_name: "程序正在运行",
+                        a11y_description: "{process_warning_text}",
+                        a11y_modal: "true",
+
+                        ScrollView {
+                            height: "calc(100% - 60)",
+
+                            label {
+                                color: theme.label_primary,
+                                "{process_warning_text}"
+                            }
+                            label {
+                                font_size: "13",
+                                color: theme.label_secondary,
+                                margin: "8 0 0 0",
+                                "\"关闭程序并清理\"会请求程序正常退出（可保存数据），不会强制结束。"
+                            }
+                            if let Some(error) = process_close_error() {
+                                label {
+                                    font_size: "13",
+                                    color: theme.danger,
+                                    margin: "8 0 0 0",
+                                    "⚠️ {error}"
+                                }
+                            }
+                        }
+
+                        rect {
+                            height: "60",
+                            padding: "12 0 0 0",
+                            direction: "horizontal",
+                            main_align: "end",
+
+                            DialogButton {
+                                text: "取消".to_string(),
+                                onpress: move |_| {
+                                    process_close_error.set(None);
+                                    process_warning.set(None);
+                                },
+                                background: theme.background_tertiary,
+                                hover_background: theme.background_primary,
+                                color: theme.label_secondary,
+                                auto_focus: true,
+                                guarded: false,
+                                theme: theme,
+                            }
+
+                            rect {
+                                width: "12"
+                            }
+
+                            DialogButton {
+                                text: "仍然清理".to_string(),
+                                onpress: {
+                                    let task = task.clone();
+                                    move |_| {
+                                        process_close_error.set(None);
+                                        process_warning.set(None);
+                                        proceed_to_clean(task.clone(), show_confirmation, app_state);
+                                    }
+                                },
+                                background: theme.warning,
+                                hover_background: theme.warning_hover,
+                                color: "white",
+                                auto_focus: false,
+                                guarded: true,
+                                theme: theme,
+                            }
+
+                            rect {
+                                width: "12"
+                            }
+
+                            DialogButton {
+                                text: process_close_label.to_string(),
+                                onpress: move |_| {
+                                    if process_closing() {
+                                        return;
+                                    }
+                                    process_closing.set(true);
+                                    process_close_error.set(None);
+                                    let task = task.clone();
+                                    let running = running.clone();
+                                    spawn(async move {
+                                        let result = tokio::task::spawn_blocking(move || close_processes(&running))
+                                            .await
+                                            .unwrap_or_else(|e| Err(e.to_string()));
+                                        process_closing.set(false);
+                                        match result {
+                                            Ok(()) => {
+                                                process_warning.set(None);
+                                                proceed_to_clean(task, show_confirmation, app_state);
+                                            }
+                                            Err(e) => process_close_error.set(Some(e)),
+                                        }
+                                    });
+                                },
+                                background: theme.accent,
+                                hover_background: theme.accent_hover,
+                                color: "white",
+                                auto_focus: false,
+                                guarded: true,
+                                theme: theme,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // 批量确认对话框 - 仅列出危险或需确认的任务，可逐项取消勾选
+        if let Some(flagged) = show_batch_confirmation() {
+            Popup {
+                oncloserequest: move |_| show_batch_confirmation.set(None),
+                show_close_button: true,
+                theme: theme_with!(PopupTheme {
+                    background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                    color: std::borrow::Cow::Borrowed(theme.label_primary),
+                    cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                    width: fit_popup_size(420.0, window_width),
+                    height: fit_popup_size(380.0, window_height),
+                }),
+
+                PopupTitle {
+                    label {
+                        color: theme.label_primary,
+                        "确认批量清理"
+                    }
+                }
+
+                PopupContent {
+                    rect {
+                        height: "100%",
+                        a11y_role: "dialog",
+                        a11y_name: "确认批量清理",
+                        a11y_modal: "true",
+
+                        ScrollView {
+                            height: "calc(100% - 60)",
+
+                            label {
+                                color: theme.label_primary,
+                                "以下任务需要确认，取消勾选的任务将不会执行："
+                            }
+
+                            rect {
+                                height: "10"
+                            }
+
+                            for task in flagged {
+                                Tile {
+                                    leading: rsx!(
+                                        Checkbox {
+                                            selected: !batch_opt_outs().contains(&task.id),
+                                        }
+                                    ),
+                                    onselect: move |_| {
+                                        let mut opt_outs = batch_opt_outs();
+                                        if !opt_outs.remove(&task.id) {
+                                            opt_outs.insert(task.id.clone());
+                                        }
+                                        batch_opt_outs.set(opt_outs);
+                                    },
+                                    label {
+                                        color: if task.risk == RiskLevel::Low { theme.label_primary } else { task.risk.color(theme) },
+                                        if task.risk == RiskLevel::Low { "{task.name}" } else { "⚠️ {task.name}（{task.risk.label()}）" }
+                                    }
+
+                                    if let Some(bytes) = task.large_delete_size() {
+                                        label {
+                                            font_size: "12",
+                                            color: theme.danger,
+                                            "将永久删除 {format_size(bytes)}，超过 {SETTINGS.large_delete_threshold} 阈值"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        rect {
+                            height: "60",
+                            padding: "12 0 0 0",
+                            direction: "horizontal",
+                            main_align: "end",
+
+                            Button {
+                                onclick: move |_| show_batch_confirmation.set(None),
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                                }),
+                                label {
+                                    color: theme.label_secondary,
+                                    "取消"
+                                }
+                            }
+
+                            rect {
+                                width: "20"
+                            }
+
+                            FilledButton {
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed(theme.danger),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.danger_hover),
+                                }),
+                                onclick: move |_| {
+                                    // 未取消勾选的任务视为已确认，取消勾选的由执行器跳过并计入统计
+                                    let opt_outs = batch_opt_outs();
+                                    let confirmed_ids = flagged_ids
+                                        .iter()
+                                        .filter(|id| !opt_outs.contains(*id))
+                                        .cloned()
+                                        .collect::<HashSet<_>>();
+                                    let batch = confirmed_task_list.clone();
+                                    show_batch_confirmation.set(None);
+                                    spawn(async move {
+                                        run_batch_clean(batch, confirmed_ids, app_state, progress, selected_tasks).await;
+                                    });
+                                },
+                                label {
+                                    color: "white",
+                                    "确认执行"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(path) = dropped_folder() {
+            DroppedFolderPanel {
+                path: path,
+                existing_rule: dropped_folder_rule,
+                dropped_folder: dropped_folder,
+                show_item_picker: show_item_picker,
+                show_rule_wizard: show_rule_wizard,
+                theme: theme,
+            }
+        }
+
+        if let Some(initial_path) = show_rule_wizard() {
+            RuleWizard {
+                initial_path: initial_path,
+                show_rule_wizard: show_rule_wizard,
+                theme: theme,
+            }
+        }
+
+        if let Some(tasks) = show_simulation() {
+            SimulationReport {
+                tasks: tasks,
+                show_simulation: show_simulation,
+                theme: theme,
+            }
+        }
+
+        if let Some((started, finished, remaining)) = interrupted_summary {
+            Popup {
+                oncloserequest: move |_| interrupted_batch.set(None),
+                show_close_button: true,
+                theme: theme_with!(PopupTheme {
+                    background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                    color: std::borrow::Cow::Borrowed(theme.label_primary),
+                    cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                    width: fit_popup_size(460.0, window_width),
+                    height: fit_popup_size(460.0, window_height),
+                }),
+
+                PopupTitle {
+                    label {
+                        color: theme.label_primary,
+                        "批量清理未完成"
+                    }
+                }
+
+                PopupContent {
+                    ScrollView {
+                        height: "calc(100% - 60)",
+
+                        label {
+                            font_size: "13",
+                            color: theme.label_secondary,
+                            margin: "0 0 8 0",
+                            "{started} 开始的批量清理在完成前被中断，以下是各任务的状态："
+                        }
+
+                        for (name, detail) in finished.into_iter().chain(remaining) {
+                            rect {
+                                width: "100%",
+                                padding: "8",
+                                margin: "0 0 6 0",
+                                background: theme.background_tertiary,
+                                corner_radius: "6",
+
+                                label {
+                                    font_size: "13",
+                                    color: theme.label_primary,
+                                    "{name}"
+                                }
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_tertiary,
+                                    "{detail}"
+                                }
+                            }
+                        }
+                    }
+
+                    rect {
+                        height: "60",
+                        padding: "12 0 0 0",
+                        direction: "horizontal",
+                        main_align: "end",
+
+                        Button {
+                            onclick: move |_| {
+                                clear_batch_journal();
+                                interrupted_batch.set(None);
+                            },
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                            }),
+                            label {
+                                color: theme.label_secondary,
+                                "放弃"
+                            }
+                        }
+
+                        if resume_count > 0 {
+                            rect {
+                                width: "20"
+                            }
+
+                            FilledButton {
+                                onclick: move |_| {
+                                    let batch = resume_task_list.clone();
+                                    clear_batch_journal();
+                                    interrupted_batch.set(None);
+                                    selected_tasks.set(batch.iter().map(|task| task.id.clone()).collect());
+                                    show_batch_mode.set(true);
+
+                                    // 与"清理选中"相同：需确认的任务重新确认后再执行
+                                    spawn(async move {
+                                        prescan_uncached(&batch).await;
+                                        let flagged = batch
+                                            .iter()
+                                            .filter(|task| task.needs_confirmation())
+                                            .cloned()
+                                            .collect::<Vec<_>>();
+                                        if flagged.is_empty() {
+                                            run_batch_clean(batch, HashSet::new(), app_state, progress, selected_tasks).await;
+                                        } else {
+                                            batch_opt_outs.set(
+                                                flagged
+                                                    .iter()
+                                                    .filter(|task| task.risk == RiskLevel::High)
+                                                    .map(|task| task.id.clone())
+                                                    .collect(),
+                                            );
+                                            show_batch_confirmation.set(Some(flagged));
+                                        }
+                                    });
+                                },
+                                label {
+                                    color: "white",
+                                    "继续剩余 {resume_count} 项"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if show_diagnostics() {
+            DiagnosticsPanel {
+                show_diagnostics: show_diagnostics,
+                theme: theme,
+            }
+        }
+
+        if show_acknowledged() {
+            AcknowledgedPanel {
+                show_acknowledged: show_acknowledged,
+                growth_warnings: growth_warnings,
+                theme: theme,
+            }
+        }
+
+        if show_rule_import() {
+            RuleImportPanel {
+                existing_names: task_names,
+                show_rule_import: show_rule_import,
+                theme: theme,
+            }
+        }
+
+        if show_history() {
+            HistoryPanel {
+                show_history: show_history,
+                theme: theme,
+            }
+        }
+
+        if show_large_files() {
+            LargeFilePanel {
+                show_large_files: show_large_files,
+                theme: theme,
+            }
+        }
+
+        if show_run_queue() {
+            RunQueuePanel {
+                show_run_queue: show_run_queue,
+                app_state: app_state,
+                theme: theme,
+            }
+        }
+
+        // 逐项选择清理
+        if let Some(task) = show_item_picker() {
+            ItemPicker {
+                task: task,
+                show_item_picker: show_item_picker,
+                app_state: app_state,
+                theme: theme,
+            }
+        }
+    )
+}
+
+#[component]
+fn TaskCard(
+    task: CleanTask,
+    show_batch_mode: bool,
+    selected_tasks: HashSet<String>,
+    on_toggle: EventHandler<()>,
+    mut app_state: Signal<AppState>,
+    mut show_confirmation: Signal<Option<CleanTask>>,
+    mut process_warning: Signal<Option<(CleanTask, Vec<String>)>>,
+    mut show_item_picker: Signal<Option<CleanTask>>,
+    // 窄窗口：按钮移到文字下方
+    compact: bool,
+    theme: &'static AppTheme,
+) -> Element {
+    let is_selected = selected_tasks.contains(&task.id);
+    let risk = task.risk;
+    let admin_blocked = task.requires_admin && !*IS_ELEVATED;
+    let item_only = task.is_item_only();
+    // 没有空闲的执行位置时"清理"改为排队
+    let slots_full = run_slots_full();
+    let queue_slot = queue_slot(&task.id);
+    // 读取刷新计数以便在单卡重新扫描后重新渲染
+    let mut size_refreshes = use_signal(|| 0u32);
+    let _ = size_refreshes();
+    let scan_age = task
+        .get_size_entry()
+        .filter(|entry| !entry.scanning)
+        .map(|entry| entry.age_text());
+    // 后台扫描进行中时定期重新渲染，扫描结束后再刷新一次显示最终结果
+    let scan_key = task.size_cache_key();
+    use_future(move || {
+        let scan_key = scan_key.clone();
+        async move {
+            let mut was_running = false;
+            loop {
+                tokio::time::sleep(SCAN_POLL_INTERVAL).await;
+                let running = scan_key.as_deref().is_some_and(is_scan_running);
+                if running || was_running {
+                    size_refreshes += 1;
+                }
+                was_running = running;
+            }
+        }
+    });
+    // 订阅运行状态，任务执行结束后显示最新的清理对比
+    let _ = app_state();
+    let mut show_comparison = use_signal(|| false);
+    let last_run = last_history_entry(&task.id)
+        .and_then(|entry| Some((entry.time_text(), entry.before?, entry.after?)));
+    // 上次执行失败时显示错误徽章，直到下次成功执行
+    let last_failure = last_history_entry(&task.id)
+        .filter(|entry| !entry.success)
+        .map(|entry| failure_badge(&entry));
+    let stale = last_failure.is_none() && is_stale_task(&task.id);
+    let refresh_task = task.clone();
+    let picker_task = task.clone();
+    let actual_size = task.get_actual_size();
+    let estimated_size_text = actual_size.as_deref().unwrap_or("未知");
+    let icon_text = task.icon.as_deref().unwrap_or("");
+    let app_icon = task.app_icon();
+    let recycles = task.recycles();
+    let schedule_text = task.schedule.map(|schedule| (schedule.label(), schedule.next_run_text()));
+    // 读屏软件朗读的卡片摘要：描述、预估大小以及风险和权限提示
+    let mut a11y_summary = format!("{}，预估可清理 {}", task.description, estimated_size_text);
+    if risk != RiskLevel::Low {
+        a11y_summary.push_str(&format!("，{}", risk.label()));
+    }
+    if admin_blocked {
+        a11y_summary.push_str("，需要管理员权限，清理时将请求提升权限");
+    }
+
+    rsx!(
+        rect {
+            width: "100%",
+            padding: "16",
+            background: if is_selected && show_batch_mode { theme.accent } else { theme.background_tertiary },
+            corner_radius: "12",
+            direction: if compact { "vertical" } else { "horizontal" },
+            main_align: "space_between",
+            cross_align: if compact { "start" } else { "center" },
+            opacity: if admin_blocked && show_batch_mode { "0.6" } else { "1" },
+            a11y_role: "list-item",
+            a11y_name: "{task.name}",
+            a11y_description: "{a11y_summary}",
+            a11y_focusable: "true",
+            a11y_selected: "{is_selected && show_batch_mode}",
+            onclick: move |_| {
+                if show_batch_mode && !admin_blocked && !item_only {
+                    on_toggle.call(());
+                }
+            },
+
+            rect {
+                width: if compact { "100%" } else { "auto" },
+                direction: "horizontal",
+                cross_align: "center",
+
+                if show_batch_mode {
+                    rect {
+                        width: "20",
+                        height: "20",
+                        corner_radius: "6",
+                        background: if is_selected { theme.accent } else { theme.background_secondary },
+                        main_align: "center",
+                        cross_align: "center",
+
+                        if is_selected {
+                            label {
+                                font_size: "14",
+                                font_weight: "bold",
+                                color: "white",
+                                "✓"
+                            }
+                        }
+                    }
+
+                    rect {
+                        width: "12"
+                    }
+                }
+
+                // 图标区域 - Apple风格
+                rect {
+                    width: "48",
+                    height: "48",
+                    corner_radius: "10",
+                    background: theme.background_secondary,
+                    main_align: "center",
+                    cross_align: "center",
+
+                    if let Some(app_icon) = app_icon {
+                        image {
+                            image_data: dynamic_bytes(app_icon),
+                            width: "32",
+                            height: "32",
+                        }
+                    } else {
+                        label {
+                            font_size: "20",
+                            color: theme.label_primary,
+                            "{icon_text}"
+                        }
+                    }
+                }
+
+                rect {
+                    width: "12"
+                }
+
+                // 文本内容区域
+                rect {
+                    width: if compact { "calc(100% - 60)" } else { "calc(100% - 220)" },  // 为按钮区域预留足够空间
+
+                    rect {
+                        direction: "horizontal",
+                        cross_align: "center",
+
+                        label {
+                            font_size: "15",
+                            font_weight: "medium",
+                            color: theme.label_primary,
+                            "{task.name.clone()}"
+                        }
+
+                        if risk != RiskLevel::Low {
+                            label {
+                                font_size: "12",
+                                color: risk.color(theme),
+                                margin: "0 0 0 6",
+                                "⚠️ {risk.label()}"
+                            }
+                        }
+
+                        if let Some((badge, retryable, _)) = &last_failure {
+                            rect {
+                                padding: "1 6",
+                                margin: "0 0 0 6",
+                                corner_radius: "6",
+                                background: if *retryable { theme.warning } else { theme.danger },
+
+                                label {
+                                    font_size: "11",
+                                    font_weight: "semibold",
+                                    color: "white",
+                                    "{badge}"
+                                }
+                            }
+                        }
+
+                        if task.requires_admin {
+                            rect {
+                                padding: "1 6",
+                                margin: "0 0 0 6",
+                                corner_radius: "6",
+                                background: theme.background_secondary,
+
+                                label {
+                                    font_size: "11",
+                                    font_weight: "semibold",
+                                    color: if admin_blocked { theme.accent } else { theme.label_secondary },
+                                    "🛡️ 管理员"
+                                }
+                            }
+                        }
+                    }
+
+                    rect {
+                        height: "4"
+                    }
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        "{task.description.clone()}"
+                    }
+
+                    rect {
+                        height: "6"
+                    }
+
+                    rect {
+                        direction: "horizontal",
+                        cross_align: "center",
+
+                        label {
+                            font_size: "12",
+                            color: theme.label_tertiary,
+                            "预估可清理: {estimated_size_text}"
+                        }
+
+                        if let Some(age) = scan_age {
+                            label {
+                                font_size: "12",
+                                color: theme.label_tertiary,
+                                margin: "0 0 0 6",
+                                "· {age}"
+                            }
+
+                            Button {
+                                onclick: move |_| {
+                                    refresh_task.invalidate_size();
+                                    size_refreshes += 1;
+                                },
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed("transparent"),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                                    padding: std::borrow::Cow::Borrowed("2 6"),
+                                }),
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_secondary,
+                                    "↻"
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some(source) = &task.override_source {
+                        label {
+                            font_size: "12",
+                            color: theme.label_tertiary,
+                            margin: "2 0 0 0",
+                            "⚙ {source}"
+                        }
+                    }
+
+                    if let Some(notes) = &task.notes {
+                        label {
+                            font_size: "12",
+                            color: theme.label_secondary,
+                            margin: "2 0 0 0",
+                            "📝 {notes}"
+                        }
+                    }
+
+                    if recycles {
+                        label {
+                            font_size: "12",
+                            color: theme.label_secondary,
+                            margin: "2 0 0 0",
+                            "♻️ 清理时移入回收站，可以还原"
+                        }
+                    }
+
+                    if let Some((schedule_label, next_run)) = &schedule_text {
+                        label {
+                            font_size: "12",
+                            color: theme.label_secondary,
+                            margin: "2 0 0 0",
+                            "🗓 {schedule_label}自动执行 · 下次 {next_run}"
+                        }
+                    }
+
+                    if let Some((_, retryable, excerpt)) = &last_failure {
+                        label {
+                            font_size: "12",
+                            color: if *retryable { theme.warning } else { theme.danger },
+                            margin: "2 0 0 0",
+                            max_lines: "1",
+                            text_overflow: "ellipsis",
+                            "{excerpt}"
+                        }
+                    }
+
+                    if stale {
+                        label {
+                            font_size: "12",
+                            color: theme.warning,
+                            margin: "2 0 0 0",
+                            "💤 最近 {STALE_TASK_RUNS} 次清理几乎没有释放空间，可考虑停用该规则或检查其路径是否已变化"
+                        }
+                    }
+
+                    if !task.tags.is_empty() {
+                        rect {
+                            direction: "horizontal",
+                            margin: "4 0 0 0",
+
+                            for tag in task.tags.iter() {
+                                rect {
+                                    padding: "2 8",
+                                    margin: "0 6 0 0",
+                                    corner_radius: "8",
+                                    background: theme.background_secondary,
+
+                                    label {
+                                        font_size: "11",
+                                        color: theme.label_secondary,
+                                        "#{tag}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // 上次清理的前后对比，点击展开
+                    if let Some((time_text, before, after)) = last_run {
+                        rect {
+                            margin: "2 0 0 0",
+                            onclick: move |_| show_comparison.set(!show_comparison()),
+
+                            label {
+                                font_size: "12",
+                                color: theme.label_tertiary,
+                                if show_comparison() { "▾ 上次清理 {time_text}" } else { "▸ 上次清理 {time_text}" }
+                            }
+                        }
+
+                        if show_comparison() {
+                            SnapshotComparison {
+                                before: before,
+                                after: after,
+                                theme: theme,
+                            }
+                        }
+                    }
+                }
+            }
+
+            // 操作按钮区域
+            rect {
+                width: if compact { "100%" } else { "160" },  // 固定按钮区域宽度
+                margin: if compact { "12 0 0 0" } else { "0" },
+                direction: "horizontal",
+                main_align: "end",  // 按钮靠右对齐
+                cross_align: "center",
+
+                if !show_batch_mode {
+                    if picker_task.item_selection {
+                        Button {
+                            onclick: move |_| show_item_picker.set(Some(picker_task.clone())),
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                                hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                            }),
+                            label {
+                                font_size: "14",
+                                color: theme.label_primary,
+                                "选择…"
+                            }
+                        }
+
+                        if !item_only && !*AUDIT_MODE {
+                            rect {
+                                width: "8"
+                            }
+                        }
+                    }
+
+                    // 审计模式下只能逐项查看，不提供清理按钮
+                    if !item_only && !*AUDIT_MODE && queue_slot != QueueSlot::Idle {
+                        rect {
+                            padding: "8 16",
+                            corner_radius: "8",
+                            background: theme.background_secondary,
+
+                            label {
+                                font_size: "14",
+                                font_weight: "medium",
+                                color: theme.label_tertiary,
+                                if queue_slot == QueueSlot::Running { "清理中…" } else { "已排队" }
+                            }
+                        }
+                    } else if !item_only && !*AUDIT_MODE {
+                        Button {
+                            onclick: move |_| {
+                                let task_clone = task.clone();
+                                // 所属程序正在运行时先提示，可选择关闭程序后再清理
+                                if task.process_check.is_none() {
+                                    proceed_to_clean(task_clone, show_confirmation, app_state);
+                                    return;
+                                }
+                                spawn(async move {
+                                    let check_task = task_clone.clone();
+                                    let running = tokio::task::spawn_blocking(move || check_task.running_owner_processes())
+                                        .await
+                                        .unwrap_or_default();
+                                    if running.is_empty() {
+                                        proceed_to_clean(task_clone, show_confirmation, app_state);
+                                    } else {
+                                        process_warning.set(Some((task_clone, running)));
+                                    }
+                                });
+                            },
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed(risk.color(theme)),
+                                hover_background: std::borrow::Cow::Borrowed(risk.hover_color(theme)),
+                            }),
+                            label {
+                                font_size: "14",
+                                font_weight: "medium",
+                                color: "white",
+                                // 未提升权限时通过 UAC 单独以管理员身份执行
+                                if slots_full {
+                                    "排队"
+                                } else if admin_blocked {
+                                    "🛡️ 清理"
+                                } else {
+                                    "清理"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+        }
+    )
+}
+
+// 通配符任务的匹配预览：逐个模式显示匹配的文件数和大小
+#[component]
+fn PatternPreview(task: CleanTask, theme: &'static AppTheme) -> Element {
+    let mut lines = use_signal(|| None::<Vec<String>>);
+    use_future(move || {
+        let task = task.clone();
+        async move {
+            let result = tokio::task::spawn_blocking(move || {
+                task.pattern_matches(&std::sync::atomic::AtomicBool::new(false))
+                    .iter()
+                    .map(|matched| {
+                        format!(
+                            "{} → {} 个文件，{}",
+                            matched.pattern,
+                            matched.files.len(),
+                            format_size(matched.total_bytes())
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .unwrap_or_default();
+            lines.set(Some(result));
+        }
+    });
+
+    rsx!(
+        rect {
+            width: "100%",
+            margin: "6 0 0 0",
+
+            label {
+                font_size: "12",
+                font_weight: "bold",
+                color: theme.label_secondary,
+                "匹配的文件"
+            }
+            if let Some(lines) = lines() {
+                for line in lines {
+                    label {
+                        font_size: "12",
+                        color: theme.label_primary,
+                        margin: "2 0 0 0",
+                        "{line}"
+                    }
+                }
+            } else {
+                label {
+                    font_size: "12",
+                    color: theme.label_tertiary,
+                    "正在查找匹配的文件…"
+                }
+            }
+        }
+    )
+}
+
+// 在后台查询各磁盘空间，记录与上次查询相比可用空间的变化
+fn refresh_drive_usage(mut drives: Signal<Vec<(DriveUsage, i64)>>) {
+    spawn(async move {
+        let current = tokio::task::spawn_blocking(drive_usage).await.unwrap_or_default();
+        let previous = drives.peek().clone();
+        let rows = current
+            .into_iter()
+            .map(|drive| {
+                let change = previous
+                    .iter()
+                    .find(|(old, _)| old.root == drive.root)
+                    .map_or(0, |(old, _)| drive.free as i64 - old.free as i64);
+                (drive, change)
+            })
+            .collect();
+        drives.set(rows);
+    });
+}
+
+#[component]
+fn DiskDashboard(drives: Vec<(DriveUsage, i64)>, narrow: bool, theme: &'static AppTheme) -> Element {
+    // (盘符, 已用/总容量, 可用空间, 已用百分比, 可用空间是否告急, 本次清理增加的可用空间, 宽度, 外边距)
+    let count = drives.len();
+    let rows = drives
+        .iter()
+        .enumerate()
+        .map(|(index, (drive, change))| {
+            let last = index + 1 == count;
+            (
+                drive.root.trim_end_matches('\\').to_string(),
+                format!("已用 {} / 共 {}", format_size(drive.used()), format_size(drive.total)),
+                format!("可用 {}", format_size(drive.free)),
+                drive.used_percent(),
+                below_emergency_line(drive.free, drive.total),
+                (*change > 0).then(|| format!("清理后 +{}", format_size(*change as u64))),
+                if narrow { "100%".to_string() } else { format!("calc({}% - 8)", 100 / count) },
+                match (last, narrow) {
+                    (true, _) => "0",
+                    (false, true) => "0 0 8 0",
+                    (false, false) => "0 8 0 0",
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    rsx!(
+        rect {
+            width: "100%",
+            direction: if narrow { "vertical" } else { "horizontal" },
+            margin: "0 0 12 0",
+            a11y_role: "group",
+            a11y_name: "磁盘空间",
+
+            for (name, used_text, free_text, percent, low, freed, width, margin) in rows {
+                rect {
+                    width: width,
+                    padding: "10 14",
+                    margin: margin,
+                    background: theme.background_secondary,
+                    corner_radius: "12",
+
+                    rect {
+                        width: "100%",
+                        direction: "horizontal",
+                        main_align: "space_between",
+                        cross_align: "center",
+
+                        label {
+                            font_size: "14",
+                            font_weight: "semibold",
+                            color: theme.label_primary,
+                            "💽 {name}"
+                        }
+
+                        label {
+                            font_size: "12",
+                            color: if low { theme.danger } else { theme.label_secondary },
+                            "{free_text}"
+                        }
+                    }
+
+                    ProgressBar {
+                        progress: percent,
+                        show_progress: false,
+                        width: "100%",
+                    }
+
+                    rect {
+                        width: "100%",
+                        direction: "horizontal",
+                        main_align: "space_between",
+
+                        label {
+                            font_size: "12",
+                            color: theme.label_tertiary,
+                            "{used_text}"
+                        }
+
+                        if let Some(freed) = freed {
+                            label {
+                                font_size: "12",
+                                color: theme.accent,
+                                "{freed}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 通过程序运行检查后继续：需要确认的任务打开确认弹窗，否则直接提交
+fn proceed_to_clean(task: CleanTask, mut show_confirmation: Signal<Option<CleanTask>>, app_state: Signal<AppState>) {
+    spawn(async move {
+        prescan_uncached(std::slice::from_ref(&task)).await;
+        if task.needs_confirmation() {
+            show_confirmation.set(Some(task));
+        } else {
+            submit_clean_task(task, app_state);
+        }
+    });
+}
+
+// 对话框按钮 - 可用 Tab 聚焦，Enter 或空格触发；auto_focus 时对话框打开即获得焦点。
+// guarded 的按钮在对话框打开后的短时间内不响应，避免双击打开对话框时第二下直接确认
+const DIALOG_BUTTON_GUARD: Duration = Duration::from_millis(600);
+
+#[component]
+fn DialogButton(
+    text: String,
+    onpress: EventHandler<()>,
+    background: &'static str,
+    hover_background: &'static str,
+    color: &'static str,
+    auto_focus: bool,
+    guarded: bool,
+    theme: &'static AppTheme,
+) -> Element {
+    let mut focus = use_focus();
+    let mut hovering = use_signal(|| false);
+    let platform = use_platform();
+    let opened_at = use_hook(std::time::Instant::now);
+    let press = move || {
+        if !guarded || opened_at.elapsed() >= DIALOG_BUTTON_GUARD {
+            onpress.call(());
+        }
+    };
+
+    rsx!(
+        rect {
+            a11y_id: focus.attribute(),
+            a11y_role: "button",
+            a11y_name: "{text}",
+            a11y_auto_focus: "{auto_focus}",
+            padding: "8 16",
+            corner_radius: "8",
+            background: if hovering() { hover_background } else { background },
+            border: if focus.is_focused() { "2 inner {theme.accent}" } else { "2 inner transparent" },
+            main_align: "center",
+            cross_align: "center",
+            onmouseenter: move |_| {
+                platform.set_cursor(CursorIcon::Pointer);
+                hovering.set(true);
+            },
+            onmouseleave: move |_| {
+                platform.set_cursor(CursorIcon::default());
+                hovering.set(false);
+            },
+            onclick: move |_| {
+                focus.request_focus();
+                press();
+            },
+            onkeydown: move |event: KeyboardEvent| {
+                if focus.validate_keydown(&event) {
+                    press();
+                }
+            },
+
+            label {
+                color: color,
+                "{text}"
+            }
+        }
+    )
+}
+
+// 清理前后对比 - 左右两栏显示大小、文件数和最大的子目录
+#[component]
+fn SnapshotComparison(before: DirSnapshot, after: DirSnapshot, theme: &'static AppTheme) -> Element {
+    let freed_text = format_size(before.bytes.saturating_sub(after.bytes));
+    let removed_files = before.files.saturating_sub(after.files);
+    let panes = [("清理前", before), ("清理后", after)]
+        .into_iter()
+        .map(|(title, snapshot)| {
+            let summary = format!("{} · {} 个文件", format_size(snapshot.bytes), snapshot.files);
+            let folders = snapshot
+                .top_folders
+                .iter()
+                .map(|(name, bytes)| format!("{} · {}", name, format_size(*bytes)))
+                .collect::<Vec<_>>();
+            (title, summary, folders)
+        })
+        .collect::<Vec<_>>();
+
+    rsx!(
+        rect {
+            width: "100%",
+            margin: "6 0 0 0",
+
+            rect {
+                width: "100%",
+                direction: "horizontal",
+
+                for (title, summary, folders) in panes {
+                    rect {
+                        width: "50%",
+                        padding: "8",
+                        margin: "0 4 0 0",
+                        corner_radius: "6",
+                        background: theme.background_secondary,
+
+                        label {
+                            font_size: "12",
+                            font_weight: "medium",
+                            color: theme.label_primary,
+                            "{title}"
+                        }
+                        label {
+                            font_size: "12",
+                            color: theme.label_secondary,
+                            "{summary}"
+                        }
+
+                        if folders.is_empty() {
+                            label {
+                                font_size: "11",
+                                color: theme.label_tertiary,
+                                "无子目录"
+                            }
+                        }
+
+                        for folder in folders {
+                            label {
+                                font_size: "11",
+                                color: theme.label_tertiary,
+                                "{folder}"
+                            }
+                        }
+                    }
+                }
+            }
+
+            label {
+                font_size: "12",
+                color: theme.label_secondary,
+                margin: "4 0 0 0",
+                "释放 {freed_text}，减少 {removed_files} 个文件"
+            }
+        }
+    )
+}
+
+// 新建规则向导 - 选择文件夹、路径泛化、删除方式、风险等级、图标和分类，保存到配置文件
+const RULE_ICONS: [&str; 10] = ["📁", "🗂️", "💾", "🧹", "📦", "🎮", "🌐", "🛠️", "📝", "🎵"];
+
+#[component]
+fn RuleWizard(
+    initial_path: String,
+    mut show_rule_wizard: Signal<Option<String>>,
+    theme: &'static AppTheme,
+) -> Element {
+    let initial_name = Path::new(&initial_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut folder = use_signal(|| initial_path.clone());
+    let mut name = use_signal(|| initial_name.clone());
+    let mut chosen_path = use_signal(|| None::<String>);
+    let mut strategy = use_signal(|| DeleteStrategy::RemoveFolder);
+    let mut risk = use_signal(|| RiskLevel::Medium);
+    let mut icon = use_signal(|| RULE_ICONS[0]);
+    let mut category = use_signal(|| CleanCategory::Custom);
+    let mut save_result = use_signal(|| None::<Result<(), String>>);
+
+    let folder_path = expand_environment_variables(&folder());
+    let suggestions = path_suggestions(&folder_path);
+    // 未手动选择时默认使用最具体的环境变量路径
+    let path_check = chosen_path()
+        .filter(|path| suggestions.contains(path))
+        .unwrap_or_else(|| generalize_path(&folder_path));
+    let expanded = expand_environment_variables(&path_check);
+    let validation = if path_check.is_empty() {
+        Err("请输入或拖放要清理的文件夹".to_string())
+    } else if !Path::new(&expanded).is_dir() {
+        Err(format!("文件夹不存在: {}", expanded))
+    } else if name().trim().is_empty() {
+        Err("请输入规则名称".to_string())
+    } else {
+        check_target_sanity(Path::new(&expanded))
+    };
+    let task = rule_task(&path_check, name().trim(), strategy(), risk(), icon(), category());
+    let command_preview = if task.command.is_empty() {
+        "（无整体清理命令，只能通过\"选择…\"逐项清理）".to_string()
+    } else {
+        task.command.clone()
+    };
+    let can_save = validation.is_ok() && !matches!(save_result(), Some(Ok(())));
+    let show_suggestions = suggestions.len() > 1;
+    let machine_specific = !path_check.is_empty() && !path_check.contains('%');
+    let suggestion_options = suggestions
+        .iter()
+        .map(|suggestion| (suggestion.clone(), suggestion.clone(), *suggestion == path_check))
+        .collect::<Vec<_>>();
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| show_rule_wizard.set(None),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("560"),
+                height: std::borrow::Cow::Borrowed("560"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "新建规则"
+                }
+            }
+
+            PopupContent {
+                ScrollView {
+                    height: "calc(100% - 60)",
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        "1. 文件夹（也可以把文件夹拖到窗口上）"
+                    }
+                    Input {
+                        value: folder(),
+                        placeholder: "例如 C:\\Users\\me\\AppData\\Roaming\\Foo\\Cache".to_string(),
+                        width: "100%",
+                        onchange: move |value: String| {
+                            folder.set(value);
+                            chosen_path.set(None);
+                            save_result.set(None);
+                        },
+                    }
+
+                    if show_suggestions {
+                        label {
+                            font_size: "13",
+                            color: theme.label_secondary,
+                            margin: "10 0 0 0",
+                            "2. 规则中使用的路径（推荐使用环境变量，便于在其他电脑上使用）"
+                        }
+                        for (text, value, selected) in suggestion_options {
+                            Tile {
+                                leading: rsx!(
+                                    Radio {
+                                        selected: selected,
+                                    }
+                                ),
+                                onselect: move |_| chosen_path.set(Some(value.clone())),
+                                label {
+                                    font_size: "13",
+                                    color: theme.label_primary,
+                                    "{text}"
+                                }
+                            }
+                        }
+                    }
+
+                    if machine_specific {
+                        label {
+                            font_size: "12",
+                            color: theme.label_tertiary,
+                            margin: "6 0 0 0",
+                            "ℹ️ 使用绝对路径，规则只适用于本机"
+                        }
+                    }
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        margin: "10 0 0 0",
+                        "3. 规则名称"
+                    }
+                    Input {
+                        value: name(),
+                        width: "100%",
+                        onchange: move |value: String| {
+                            name.set(value);
+                            save_result.set(None);
+                        },
+                    }
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        margin: "10 0 0 0",
+                        "4. 删除方式"
+                    }
+                    for option in DeleteStrategy::ALL {
+                        Tile {
+                            leading: rsx!(
+                                Radio {
+                                    selected: strategy() == option,
+                                }
+                            ),
+                            onselect: move |_| strategy.set(option),
+                            label {
+                                font_size: "13",
+                                color: theme.label_primary,
+                                "{option.label()}"
+                            }
+                        }
+                    }
+                    label {
+                        font_size: "12",
+                        color: theme.label_tertiary,
+                        "{command_preview}"
+                    }
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        margin: "10 0 0 0",
+                        "5. 风险等级、图标和分类"
+                    }
+                    rect {
+                        direction: "horizontal",
+                        cross_align: "center",
+
+                        for level in [RiskLevel::Low, RiskLevel::Medium, RiskLevel::High] {
+                            Button {
+                                onclick: move |_| risk.set(level),
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed(if risk() == level { level.color(theme) } else { theme.background_tertiary }),
+                                    hover_background: std::borrow::Cow::Borrowed(level.hover_color(theme)),
+                                }),
+                                label {
+                                    font_size: "13",
+                                    color: if risk() == level { "white" } else { theme.label_primary },
+                                    "{level.label()}"
+                                }
+                            }
+                        }
+                    }
+                    rect {
+                        direction: "horizontal",
+                        cross_align: "center",
+
+                        for option in RULE_ICONS {
+                            Button {
+                                onclick: move |_| icon.set(option),
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed(if icon() == option { theme.accent } else { "transparent" }),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                    padding: std::borrow::Cow::Borrowed("4 6"),
+                                }),
+                                label {
+                                    font_size: "16",
+                                    "{option}"
+                                }
+                            }
+                        }
+                    }
+                    rect {
+                        direction: "horizontal",
+                        cross_align: "center",
+
+                        for option in CleanCategory::ALL {
+                            Button {
+                                onclick: move |_| category.set(option),
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed(if category() == option { theme.accent } else { theme.background_tertiary }),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.accent_hover),
+                                }),
+                                label {
+                                    font_size: "13",
+                                    color: if category() == option { "white" } else { theme.label_primary },
+                                    "{option.label()}"
+                                }
+                            }
+                        }
+                    }
+
+                    match (validation.clone().err(), save_result()) {
+                        (_, Some(Ok(()))) => rsx!(
+                            label {
+                                font_size: "13",
+                                color: theme.accent,
+                                margin: "10 0 0 0",
+                                "✓ 已添加到 {CONFIG_FILE}"
+                            }
+                        ),
+                        (_, Some(Err(e))) | (Some(e), None) => rsx!(
+                            label {
+                                font_size: "13",
+                                color: theme.danger,
+                                margin: "10 0 0 0",
+                                "{e}"
+                            }
+                        ),
+                        (None, None) => rsx!(),
+                    }
+                }
+
+                rect {
+                    height: "60",
+                    padding: "12 0 0 0",
+                    direction: "horizontal",
+                    main_align: "end",
+
+                    Button {
+                        onclick: move |_| show_rule_wizard.set(None),
+                        theme: theme_with!(ButtonTheme {
+                            background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                            hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                        }),
+                        label {
+                            color: theme.label_secondary,
+                            "关闭"
+                        }
+                    }
+
+                    if can_save {
+                        rect {
+                            width: "20"
+                        }
+
+                        FilledButton {
+                            onclick: move |_| save_result.set(Some(append_custom_task(&task))),
+                            label {
+                                color: "white",
+                                "保存规则"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 已忽略的提醒列表 - 可逐条恢复
+#[component]
+fn AcknowledgedPanel(
+    mut show_acknowledged: Signal<bool>,
+    mut growth_warnings: Signal<Vec<GrowthAlert>>,
+    theme: &'static AppTheme,
+) -> Element {
+    let mut acknowledgements = use_signal(load_acknowledgements);
+    let rows = acknowledgements()
+        .iter()
+        .map(|ack| {
+            (
+                ack.target().to_string(),
+                if ack.bytes > 0 {
+                    format!("{} 忽略，当时 {}", ack.time_text(), format_size(ack.bytes))
+                } else {
+                    format!("{} 忽略", ack.time_text())
+                },
+                ack.key.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| show_acknowledged.set(false),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("480"),
+                height: std::borrow::Cow::Borrowed("420"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "已忽略的提醒"
+                }
+            }
+
+            PopupContent {
+                ScrollView {
+                    height: "100%",
+
+                    if rows.is_empty() {
+                        label {
+                            font_size: "13",
+                            color: theme.label_secondary,
+                            "没有已忽略的提醒"
+                        }
+                    }
+
+                    for (target, detail, key) in rows {
+                        rect {
+                            width: "100%",
+                            direction: "horizontal",
+                            main_align: "space_between",
+                            cross_align: "center",
+                            padding: "8",
+                            margin: "0 0 6 0",
+                            background: theme.background_tertiary,
+                            corner_radius: "6",
+
+                            rect {
+                                width: "calc(100% - 80)",
+
+                                label {
+                                    font_size: "13",
+                                    color: theme.label_primary,
+                                    "{target}"
+                                }
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_tertiary,
+                                    "{detail}"
+                                }
+                            }
+
+                            Button {
+                                onclick: move |_| {
+                                    unacknowledge(&key);
+                                    acknowledgements.set(load_acknowledgements());
+                                    growth_warnings.set(growth_alerts());
+                                },
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                                }),
+                                label {
+                                    font_size: "13",
+                                    color: theme.label_primary,
+                                    "恢复"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 模拟运行报告 - 列出将执行和将跳过的任务，以及预计可释放的空间
+#[component]
+fn SimulationReport(
+    tasks: Vec<CleanTask>,
+    mut show_simulation: Signal<Option<Vec<CleanTask>>>,
+    theme: &'static AppTheme,
+) -> Element {
+    let checked = use_signal(|| 0usize);
+    let mut report = use_signal(|| None::<Vec<SimulationEntry>>);
+
+    let total = tasks.len();
+    use_hook(move || {
+        spawn(async move {
+            report.set(Some(simulate_tasks(tasks, checked).await));
+        })
+    });
+
+    let entries = report().unwrap_or_default();
+    let run_entries = entries
+        .iter()
+        .filter_map(|entry| match &entry.outcome {
+            SimulationOutcome::Run { command, needs_confirmation } => Some((
+                format!(
+                    "{} · {}{}",
+                    entry.task_name,
+                    entry.bytes.map(format_size).unwrap_or_else(|| "大小未知".to_string()),
+                    if *needs_confirmation { " · 需确认" } else { "" }
+                ),
+                command.clone(),
+                entry.new_content.as_ref().map(|(time, content)| {
+                    let text = if content.files == 0 {
+                        format!("自上次清理（{}）以来没有新增内容", time)
+                    } else {
+                        format!(
+                            "🆕 自上次清理（{}）以来新增 {} 个文件 · {}",
+                            time,
+                            content.files,
+                            format_size(content.bytes)
+                        )
+                    };
+                    (text, content.files > 0)
+                }),
+            )),
+            SimulationOutcome::Skip(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let skip_entries = entries
+        .iter()
+        .filter_map(|entry| match &entry.outcome {
+            SimulationOutcome::Skip(reason) => Some((entry.task_name.clone(), reason.replace('\n', " "))),
+            SimulationOutcome::Run { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    let total_bytes = entries.iter().filter_map(|entry| entry.bytes).sum::<u64>();
+    let summary = if report().is_none() {
+        format!("正在检查 {}/{} …", checked(), total)
+    } else {
+        format!(
+            "将执行 {} 个任务，跳过 {} 个，预计可释放 {}",
+            run_entries.len(),
+            skip_entries.len(),
+            format_size(total_bytes)
+        )
+    };
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| show_simulation.set(None),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("560"),
+                height: std::borrow::Cow::Borrowed("520"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "模拟运行"
+                }
+            }
+
+            PopupContent {
+                ScrollView {
+                    height: "calc(100% - 60)",
+
+                    label {
+                        font_size: "14",
+                        color: theme.label_primary,
+                        a11y_role: "status",
+                        a11y_live: "polite",
+                        "{summary}"
+                    }
+
+                    label {
+                        font_size: "12",
+                        color: theme.label_tertiary,
+                        margin: "2 0 0 0",
+                        "只执行检查和大小扫描，不会删除任何文件"
+                    }
+
+                    if !run_entries.is_empty() {
+                        label {
+                            font_size: "13",
+                            font_weight: "semibold",
+                            color: theme.label_secondary,
+                            margin: "12 0 4 0",
+                            "将执行"
+                        }
+                    }
+
+                    for (title, command, new_content) in run_entries {
+                        rect {
+                            width: "100%",
+                            padding: "8",
+                            margin: "0 0 6 0",
+                            background: theme.background_tertiary,
+                            corner_radius: "6",
+
+                            label {
+                                font_size: "13",
+                                color: theme.label_primary,
+                                "✓ {title}"
+                            }
+                            if let Some((text, has_new)) = new_content {
+                                label {
+                                    font_size: "12",
+                                    color: if has_new { theme.accent } else { theme.label_tertiary },
+                                    "{text}"
+                                }
+                            }
+                            label {
+                                font_size: "12",
+                                color: theme.label_tertiary,
+                                "{command}"
+                            }
+                        }
+                    }
+
+                    if !skip_entries.is_empty() {
+                        label {
+                            font_size: "13",
+                            font_weight: "semibold",
+                            color: theme.label_secondary,
+                            margin: "12 0 4 0",
+                            "将跳过"
+                        }
+                    }
+
+                    for (name, reason) in skip_entries {
+                        rect {
+                            width: "100%",
+                            padding: "8",
+                            margin: "0 0 6 0",
+                            background: theme.background_tertiary,
+                            corner_radius: "6",
+
+                            label {
+                                font_size: "13",
+                                color: theme.label_primary,
+                                "– {name}"
+                            }
+                            label {
+                                font_size: "12",
+                                color: theme.warning,
+                                "{reason}"
+                            }
+                        }
+                    }
+                }
+
+                rect {
+                    height: "60",
+                    padding: "12 0 0 0",
+                    direction: "horizontal",
+                    main_align: "end",
+
+                    Button {
+                        onclick: move |_| show_simulation.set(None),
+                        theme: theme_with!(ButtonTheme {
+                            background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                            hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                        }),
+                        label {
+                            color: theme.label_secondary,
+                            "关闭"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 环境变量展开对照：原文中的变量以强调色显示，展开结果中高亮被替换的部分，空变量以红色标出
+#[component]
+fn ExpansionPreview(title: String, source: String, theme: &'static AppTheme) -> Element {
+    let segments = expansion_segments(&source);
+    let has_variables = segments
+        .iter()
+        .any(|segment| matches!(segment, ExpansionSegment::Variable { .. }));
+    let expanded_segments = segments.clone();
+
+    rsx!(
+        label {
+            font_size: "12",
+            color: theme.label_tertiary,
+            margin: "8 0 2 0",
+            "{title}"
+        }
+
+        paragraph {
+            width: "100%",
+            font_size: "12",
+
+            for segment in segments {
+                match segment {
+                    ExpansionSegment::Text(literal) => rsx!(
+                        text {
+                            color: theme.label_secondary,
+                            "{literal}"
+                        }
+                    ),
+                    ExpansionSegment::Variable { token, .. } => rsx!(
+                        text {
+                            color: theme.accent,
+                            "{token}"
+                        }
+                    ),
+                }
+            }
+        }
+
+        if has_variables {
+            paragraph {
+                width: "100%",
+                font_size: "12",
+                margin: "2 0 0 0",
+
+                text {
+                    color: theme.label_tertiary,
+                    "→ "
+                }
+
+                for segment in expanded_segments {
+                    match segment {
+                        ExpansionSegment::Text(literal) => rsx!(
+                            text {
+                                color: theme.label_primary,
+                                "{literal}"
+                            }
+                        ),
+                        ExpansionSegment::Variable { value: Some(value), .. } => rsx!(
+                            text {
+                                color: theme.warning,
+                                font_weight: "bold",
+                                "{value}"
+                            }
+                        ),
+                        ExpansionSegment::Variable { token, value: None } => rsx!(
+                            text {
+                                color: theme.danger,
+                                font_weight: "bold",
+                                "⟨{token} 为空⟩"
+                            }
+                        ),
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 从剪贴板导入规则 - 解析 TOML/JSON 片段，逐条显示安全检查结果，勾选后追加到配置文件
+#[component]
+fn RuleImportPanel(
+    existing_names: Vec<String>,
+    mut show_rule_import: Signal<bool>,
+    theme: &'static AppTheme,
+) -> Element {
+    let mut clipboard = use_clipboard();
+    let mut clipboard_text = use_signal(|| clipboard.get().unwrap_or_default());
+    let mut excluded = use_signal(|| HashSet::<usize>::new());
+    let mut import_result = use_signal(|| None::<Result<usize, String>>);
+
+    let parsed = parse_shared_rules(&clipboard_text()).map(|tasks| {
+        tasks
+            .into_iter()
+            .map(|task| review_imported_rule(task, &existing_names))
+            .collect::<Vec<_>>()
+    });
+    let rules = parsed.clone().unwrap_or_default();
+    let selected_tasks = rules
+        .iter()
+        .enumerate()
+        .filter(|(index, rule)| rule.errors.is_empty() && !excluded().contains(index))
+        .map(|(_, rule)| rule.task.clone())
+        .collect::<Vec<_>>();
+    let selected_count = selected_tasks.len();
+    let can_import = selected_count > 0 && import_result().is_none();
+    // 预先整理每条规则的显示内容：(序号, 可导入, 已勾选, 标题, 命令, 路径, 错误, 提示)
+    let rows = rules
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| {
+            let importable = rule.errors.is_empty();
+            (
+                index,
+                importable,
+                importable && !excluded().contains(&index),
+                format!("{} · {}", rule.task.name, rule.task.risk.label()),
+                rule.task.command.clone(),
+                rule.task.path_check.clone().unwrap_or_default(),
+                rule.errors.clone(),
+                rule.warnings.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| show_rule_import.set(false),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("600"),
+                height: std::borrow::Cow::Borrowed("560"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "从剪贴板导入规则"
+                }
+            }
+
+            PopupContent {
+                ScrollView {
+                    height: "calc(100% - 60)",
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        "支持 [[task]] 格式的 TOML 片段，或单条/多条规则的 JSON。导入的规则都需要确认后才会执行。"
+                    }
+
+                    if let Err(e) = &parsed {
+                        label {
+                            font_size: "13",
+                            color: theme.danger,
+                            margin: "10 0 0 0",
+                            "{e}"
+                        }
+                    }
+
+                    for (index, importable, selected, title, command, path_check, errors, warnings) in rows {
+                        rect {
+                            width: "100%",
+                            margin: "10 0 0 0",
+                            padding: "10",
+                            background: if importable { theme.background_tertiary } else { theme.danger_background },
+                            corner_radius: "6",
+
+                            Tile {
+                                leading: rsx!(
+                                    Checkbox {
+                                        selected: selected,
+                                    }
+                                ),
+                                onselect: move |_| {
+                                    if importable {
+                                        let mut set = excluded();
+                                        if !set.remove(&index) {
+                                            set.insert(index);
+                                        }
+                                        excluded.set(set);
+                                    }
+                                },
+                                label {
+                                    font_size: "14",
+                                    color: theme.label_primary,
+                                    "{title}"
+                                }
+                            }
+
+                            if !command.is_empty() {
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_secondary,
+                                    "命令: {command}"
+                                }
+                            }
+
+                            if !path_check.is_empty() {
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_secondary,
+                                    "路径: {path_check}"
+                                }
+                            }
+
+                            for error in errors {
+                                label {
+                                    font_size: "12",
+                                    color: theme.danger,
+                                    "⛔ {error}"
+                                }
+                            }
+
+                            for warning in warnings {
+                                label {
+                                    font_size: "12",
+                                    color: theme.warning,
+                                    "⚠️ {warning}"
+                                }
+                            }
+                        }
+                    }
+
+                    match import_result() {
+                        Some(Ok(count)) => rsx!(
+                            label {
+                                font_size: "13",
+                                color: theme.accent,
+                                margin: "10 0 0 0",
+                                "✓ 已导入 {count} 条规则到 {CONFIG_FILE}"
+                            }
+                        ),
+                        Some(Err(e)) => rsx!(
+                            label {
+                                font_size: "13",
+                                color: theme.danger,
+                                margin: "10 0 0 0",
+                                "{e}"
+                            }
+                        ),
+                        None => rsx!(),
+                    }
+                }
+
+                rect {
+                    height: "60",
+                    padding: "12 0 0 0",
+                    direction: "horizontal",
+                    main_align: "end",
+
+                    Button {
+                        onclick: move |_| {
+                            clipboard_text.set(clipboard.get().unwrap_or_default());
+                            excluded.set(HashSet::new());
+                            import_result.set(None);
+                        },
+                        theme: theme_with!(ButtonTheme {
+                            background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                            hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                        }),
+                        label {
+                            color: theme.label_secondary,
+                            "重新读取剪贴板"
+                        }
+                    }
+
+                    rect {
+                        width: "20"
+                    }
+
+                    Button {
+                        onclick: move |_| show_rule_import.set(false),
+                        theme: theme_with!(ButtonTheme {
+                            background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                            hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                        }),
+                        label {
+                            color: theme.label_secondary,
+                            "关闭"
+                        }
+                    }
+
+                    if can_import {
+                        rect {
+                            width: "20"
+                        }
+
+                        FilledButton {
+                            onclick: move |_| {
+                                // 逐条追加，遇到写入失败立即停止，已写入的规则保留
+                                let result = selected_tasks
+                                    .iter()
+                                    .try_for_each(append_custom_task)
+                                    .map(|_| selected_tasks.len());
+                                import_result.set(Some(result));
+                            },
+                            label {
+                                color: "white",
+                                "导入 {selected_count} 条规则"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 拖放文件夹分析 - 显示大小和最大的子目录，可逐项预览或保存为自定义规则
+#[component]
+fn DroppedFolderPanel(
+    path: std::path::PathBuf,
+    existing_rule: Option<String>,
+    mut dropped_folder: Signal<Option<std::path::PathBuf>>,
+    mut show_item_picker: Signal<Option<CleanTask>>,
+    mut show_rule_wizard: Signal<Option<String>>,
+    theme: &'static AppTheme,
+) -> Element {
+    let mut snapshot = use_signal(|| None::<Option<DirSnapshot>>);
+
+    let scan_path = path.to_string_lossy().to_string();
+    use_hook(move || {
+        spawn(async move {
+            let result = tokio::task::spawn_blocking(move || snapshot_directory(&scan_path))
+                .await
+                .ok()
+                .flatten();
+            snapshot.set(Some(result));
+        })
+    });
+
+    let preview_task = folder_task(&path);
+    let title = format!("分析文件夹 - {}", preview_task.name);
+    let path_text = path.display().to_string();
+    // 内置禁区、驱动器根目录等不允许作为清理目标
+    let blocked = check_target_sanity(&path).err();
+    let summary = match snapshot() {
+        None => "正在扫描…".to_string(),
+        Some(None) => "无法读取该文件夹".to_string(),
+        Some(Some(snapshot)) => format!("{} · {} 个文件", format_size(snapshot.bytes), snapshot.files),
+    };
+    let top_folders = snapshot()
+        .flatten()
+        .map(|snapshot| {
+            snapshot
+                .top_folders
+                .iter()
+                .map(|(name, bytes)| format!("{} · {}", name, format_size(*bytes)))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let can_save = blocked.is_none() && existing_rule.is_none() && !*AUDIT_MODE;
+    let wizard_path = path.to_string_lossy().to_string();
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| dropped_folder.set(None),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("460"),
+                height: std::borrow::Cow::Borrowed("400"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "{title}"
+                }
+            }
+
+            PopupContent {
+                ScrollView {
+                    height: "calc(100% - 60)",
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        "{path_text}"
+                    }
+
+                    label {
+                        font_size: "14",
+                        color: theme.label_primary,
+                        margin: "8 0",
+                        "{summary}"
+                    }
+
+                    for folder in top_folders {
+                        label {
+                            font_size: "12",
+                            color: theme.label_tertiary,
+                            "{folder}"
+                        }
+                    }
+
+                    if let Some(reason) = &blocked {
+                        label {
+                            font_size: "13",
+                            color: theme.danger,
+                            margin: "8 0 0 0",
+                            "⚠️ {reason}"
+                        }
+                    }
+
+                    if let Some(rule) = &existing_rule {
+                        label {
+                            font_size: "13",
+                            color: theme.label_secondary,
+                            margin: "8 0 0 0",
+                            "已有规则覆盖该文件夹: {rule}"
+                        }
+                    }
+                }
+
+                rect {
+                    height: "60",
+                    padding: "12 0 0 0",
+                    direction: "horizontal",
+                    main_align: "end",
+
+                    if blocked.is_none() {
+                        Button {
+                            onclick: move |_| {
+                                dropped_folder.set(None);
+                                show_item_picker.set(Some(preview_task.clone()));
+                            },
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                            }),
+                            label {
+                                color: theme.label_primary,
+                                "逐项预览…"
+                            }
+                        }
+                    }
+
+                    if can_save {
+                        rect {
+                            width: "20"
+                        }
+
+                        FilledButton {
+                            onclick: move |_| {
+                                dropped_folder.set(None);
+                                show_rule_wizard.set(Some(wizard_path.clone()));
+                            },
+                            label {
+                                color: "white",
+                                "创建自定义规则…"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 信息亭界面 - 只有一个固定任务集的清理按钮、进度和结果
+#[component]
+fn KioskView(
+    tasks: Vec<CleanTask>,
+    loading: bool,
+    app_state: Signal<AppState>,
+    progress: Signal<f32>,
+    selected_tasks: Signal<HashSet<String>>,
+    theme: &'static AppTheme,
+) -> Element {
+    let running = matches!(app_state(), AppState::Running(_));
+    let can_run = !running && !tasks.is_empty();
+    let task_count = tasks.len();
+    let button_label = SETTINGS.kiosk_button_label.clone();
+
+    rsx!(
+        rect {
+            width: "100%",
+            height: "100%",
+            padding: "40",
+            background: if *BACKDROP_ACTIVE { "transparent" } else { theme.background_primary },
+            color: theme.label_primary,
+            font_family: theme.font_family(),
+            direction: "vertical",
+            main_align: "center",
+            cross_align: "center",
+
+            label {
+                font_size: "28",
+                font_weight: "bold",
+                color: theme.label_primary,
+                "WinCleaner"
+            }
+
+            label {
+                font_size: "14",
+                color: theme.label_secondary,
+                margin: "8 0 32 0",
+                if loading {
+                    "正在加载清理规则…"
+                } else {
+                    "将清理 {task_count} 项临时文件和缓存，不会删除个人文件"
+                }
+            }
+
+            Button {
+                onclick: move |_| {
+                    if can_run {
+                        let batch = tasks.clone();
+                        spawn(async move {
+                            run_batch_clean(batch, HashSet::new(), app_state, progress, selected_tasks).await;
+                        });
+                    }
+                },
+                theme: theme_with!(ButtonTheme {
+                    background: std::borrow::Cow::Borrowed(theme.accent),
+                    hover_background: std::borrow::Cow::Borrowed(theme.accent),
+                    padding: std::borrow::Cow::Borrowed("16 48"),
+                    corner_radius: std::borrow::Cow::Borrowed("12"),
+                }),
+                label {
+                    font_size: "20",
+                    font_weight: "semibold",
+                    color: "white",
+                    if running { "正在清理…" } else { "{button_label}" }
+                }
+            }
+
+            if running {
+                rect {
+                    width: "320",
+                    margin: "24 0 0 0",
+                    ProgressBar {
+                        progress: (progress() * 100.0) as f32,
+                        show_progress: true,
+                        width: "100%",
+                    }
+                }
+            }
+
+            rect {
+                width: "480",
+                NotificationBubble {
+                    app_state: app_state(),
+                    theme: theme
+                }
+            }
+        }
+    )
+}
+
+// 诊断弹窗 - 开启或关闭本机使用统计、查看已记录的内容并导出诊断包
+#[component]
+fn DiagnosticsPanel(mut show_diagnostics: Signal<bool>, theme: &'static AppTheme) -> Element {
+    let mut stats = use_signal(usage_stats);
+    let mut export_result = use_signal(|| None::<Result<String, String>>);
+    let enabled = stats().enabled;
+    let report = stats().report();
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| show_diagnostics.set(false),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("480"),
+                height: std::borrow::Cow::Borrowed("460"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "诊断"
+                }
+            }
+
+            PopupContent {
+                ScrollView {
+                    height: "calc(100% - 60)",
+
+                    rect {
+                        width: "100%",
+                        direction: "horizontal",
+                        main_align: "space_between",
+                        cross_align: "center",
+                        margin: "0 0 8 0",
+                        a11y_role: "group",
+                        a11y_name: "使用统计",
+
+                        label {
+                            font_size: "14",
+                            color: theme.label_primary,
+                            "记录本机使用统计"
+                        }
+
+                        Switch {
+                            enabled: enabled,
+                            ontoggled: move |_| {
+                                set_usage_enabled(!enabled);
+                                stats.set(usage_stats());
+                            },
+                        }
+                    }
+
+                    label {
+                        font_size: "12",
+                        color: theme.label_tertiary,
+                        margin: "0 0 12 0",
+                        "只记录功能使用次数、错误类型和扫描耗时，保存在 {USAGE_FILE}，不含路径和文件名，也不会上传；关闭时清空已记录的数据"
+                    }
+
+                    if enabled {
+                        rect {
+                            width: "100%",
+                            padding: "8",
+                            background: theme.background_tertiary,
+                            corner_radius: "6",
+
+                            label {
+                                font_size: "12",
+                                color: theme.label_secondary,
+                                "{report}"
+                            }
+                        }
+                    }
+
+                    match export_result() {
+                        Some(Ok(path)) => rsx!(
+                            label {
+                                font_size: "12",
+                                color: theme.label_secondary,
+                                margin: "8 0 0 0",
+                                "诊断包已导出: {path}"
+                            }
+                        ),
+                        Some(Err(e)) => rsx!(
+                            label {
+                                font_size: "12",
+                                color: theme.danger,
+                                margin: "8 0 0 0",
+                                "{e}"
+                            }
+                        ),
+                        None => rsx!(),
+                    }
+                }
+
+                rect {
+                    height: "60",
+                    padding: "12 0 0 0",
+                    direction: "horizontal",
+                    main_align: "end",
+
+                    FilledButton {
+                        onclick: move |_| {
+                            spawn(async move {
+                                let result = tokio::task::spawn_blocking(export_diagnostics_bundle)
+                                    .await
+                                    .unwrap_or_else(|e| Err(format!("诊断包生成失败: {}", e)));
+                                export_result.set(Some(result));
+                            });
+                        },
+                        label {
+                            color: "white",
+                            "导出诊断包"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 日志导出的时间范围选项：(文字, 距今秒数)
+const LOG_EXPORT_RANGES: [(&str, Option<i64>); 4] = [
+    ("最近 24 小时", Some(86400)),
+    ("最近 7 天", Some(7 * 86400)),
+    ("最近 30 天", Some(30 * 86400)),
+    ("全部", None),
+];
+
+// 日志导出弹窗 - 选择时间范围、任务和级别后导出日志与清理历史
+#[component]
+fn LogExportPanel(mut show_export: Signal<bool>, theme: &'static AppTheme) -> Element {
+    let mut range = use_signal(|| 1usize);
+    let mut task = use_signal(String::new);
+    let mut severity = use_signal(LogSeverity::default);
+    let mut format = use_signal(|| LogExportFormat::Json);
+    let mut export_result = use_signal(|| None::<Result<String, String>>);
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| show_export.set(false),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("520"),
+                height: std::borrow::Cow::Borrowed("420"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "导出日志"
+                }
+            }
+
+            PopupContent {
+                ScrollView {
+                    height: "calc(100% - 60)",
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        "时间范围"
+                    }
+                    rect {
+                        direction: "horizontal",
+                        margin: "4 0 10 0",
+
+                        for (index, (text, _)) in LOG_EXPORT_RANGES.into_iter().enumerate() {
+                            Button {
+                                onclick: move |_| range.set(index),
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed(if range() == index { theme.accent } else { theme.background_tertiary }),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.accent_hover),
+                                }),
+                                label {
+                                    font_size: "13",
+                                    color: if range() == index { "white" } else { theme.label_primary },
+                                    "{text}"
+                                }
+                            }
+                        }
+                    }
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        "任务（ID 或名称中的文字，留空为全部）"
+                    }
+                    rect {
+                        margin: "4 0 10 0",
+                        Input {
+                            value: task(),
+                            placeholder: "如 npm-cache".to_string(),
+                            width: "240",
+                            onchange: move |value: String| task.set(value),
+                        }
+                    }
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        "级别"
+                    }
+                    rect {
+                        direction: "horizontal",
+                        margin: "4 0 10 0",
+
+                        for option in LogSeverity::ALL {
+                            Button {
+                                onclick: move |_| severity.set(option),
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed(if severity() == option { theme.accent } else { theme.background_tertiary }),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.accent_hover),
+                                }),
+                                label {
+                                    font_size: "13",
+                                    color: if severity() == option { "white" } else { theme.label_primary },
+                                    "{option.filter_label()}"
+                                }
+                            }
+                        }
+                    }
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        "格式"
+                    }
+                    rect {
+                        direction: "horizontal",
+                        margin: "4 0 10 0",
+
+                        for option in [LogExportFormat::Json, LogExportFormat::Csv] {
+                            Button {
+                                onclick: move |_| format.set(option),
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed(if format() == option { theme.accent } else { theme.background_tertiary }),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.accent_hover),
+                                }),
+                                label {
+                                    font_size: "13",
+                                    color: if format() == option { "white" } else { theme.label_primary },
+                                    "{option.extension().to_uppercase()}"
+                                }
+                            }
+                        }
+                    }
+
+                    match export_result() {
+                        Some(Ok(path)) => rsx!(
+                            label {
+                                font_size: "12",
+                                color: theme.label_secondary,
+                                "已导出: {path}"
+                            }
+                        ),
+                        Some(Err(e)) => rsx!(
+                            label {
+                                font_size: "12",
+                                color: theme.danger,
+                                "{e}"
+                            }
+                        ),
+                        None => rsx!(),
+                    }
+                }
+
+                rect {
+                    height: "60",
+                    padding: "12 0 0 0",
+                    direction: "horizontal",
+                    main_align: "end",
+
+                    FilledButton {
+                        onclick: move |_| {
+                            let filter = LogFilter {
+                                since: LOG_EXPORT_RANGES[range()].1.map(|seconds| chrono::Local::now().timestamp() - seconds),
+                                until: None,
+                                task: task(),
+                                min_severity: severity(),
+                            };
+                            let format = format();
+                            spawn(async move {
+                                let result = tokio::task::spawn_blocking(move || export_logs(&filter, format, None))
+                                    .await
+                                    .unwrap_or_else(|e| Err(format!("日志导出失败: {}", e)));
+                                export_result.set(Some(result));
+                            });
+                        },
+                        label {
+                            color: "white",
+                            "导出"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 大文件查找弹窗 - 列出用户目录中超过阈值的文件，可移入回收站或移到归档文件夹
+#[component]
+fn LargeFilePanel(mut show_large_files: Signal<bool>, theme: &'static AppTheme) -> Element {
+    let min_size = parse_size(&SETTINGS.large_file_threshold).unwrap_or(1024 * 1024 * 1024);
+    let mut files = use_signal(|| None::<Vec<LargeFile>>);
+    let mut checked = use_signal(HashSet::<std::path::PathBuf>::new);
+    let mut sort = use_signal(|| LargeFileSort::Size);
+    let mut archive_dir = use_signal(|| SETTINGS.archive_dir.clone());
+    let mut busy = use_signal(|| false);
+    let mut action_result = use_signal(|| None::<Result<String, String>>);
+
+    // 扫描期间定期刷新已检查的文件数
+    let scanned = use_hook(|| Arc::new(AtomicU64::new(0)));
+    let mut refreshes = use_signal(|| 0u32);
+    let _ = refreshes();
+    let counter = scanned.clone();
+    use_hook(move || {
+        spawn(async move {
+            let scan = tokio::task::spawn_blocking(move || find_large_files(min_size, &counter));
+            while !scan.is_finished() {
+                tokio::time::sleep(SCAN_POLL_INTERVAL).await;
+                refreshes += 1;
+            }
+            files.set(Some(scan.await.unwrap_or_default()));
+        })
+    });
+
+    let mut list = files().unwrap_or_default();
+    sort().sort(&mut list);
+    let summary = match files() {
+        None => format!("正在扫描用户目录… 已检查 {} 个文件", scanned.load(Ordering::Relaxed)),
+        Some(found) if found.is_empty() => format!("没有找到超过 {} 的文件", format_size(min_size)),
+        Some(found) => format!(
+            "找到 {} 个超过 {} 的文件，共 {}",
+            found.len(),
+            format_size(min_size),
+            format_size(found.iter().map(|file| file.size).sum())
+        ),
+    };
+    let rows = list
+        .into_iter()
+        .map(|file| {
+            let detail = format!(
+                "{} · 修改于 {} · {}",
+                file.path.parent().map(|parent| parent.display().to_string()).unwrap_or_default(),
+                file.modified_text(),
+                file.kind_label()
+            );
+            (file.as_target_item(), detail)
+        })
+        .collect::<Vec<_>>();
+    let checked_size = files()
+        .unwrap_or_default()
+        .iter()
+        .filter(|file| checked().contains(&file.path))
+        .map(|file| file.size)
+        .sum::<u64>();
+    let checked_text = format!("{} 项 · {}", checked().len(), format_size(checked_size));
+    let can_act = !busy() && !checked().is_empty();
+
+    // 逐个处理选中的文件：移入回收站与逐项清理走同一安全检查，归档时移动到指定文件夹
+    let mut process = move |archive: bool| {
+        let checked_paths = checked();
+        let selected = files()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|file| checked_paths.contains(&file.path))
+            .collect::<Vec<_>>();
+        if selected.is_empty() || busy() {
+            return;
+        }
+        record_feature(if archive { "大文件归档" } else { "大文件移入回收站" });
+        let destination = archive_dir();
+        busy.set(true);
+        action_result.set(None);
+        spawn(async move {
+            let (done, bytes, errors) = tokio::task::spawn_blocking(move || {
+                let mut done = Vec::new();
+                let mut bytes = 0;
+                let mut errors = Vec::new();
+                for file in &selected {
+                    let result = if archive {
+                        archive_file(&file.path, &destination).map(|_| file.size)
+                    } else {
+                        delete_target_item(&file.as_target_item(), DeleteMode::RecycleBin)
+                    };
+                    match result {
+                        Ok(size) => {
+                            done.push(file.path.clone());
+                            bytes += size;
+                        }
+                        Err(e) => {
+                            log(&format!("大文件处理失败: {}", e));
+                            errors.push(e);
+                        }
+                    }
+                }
+                (done, bytes, errors)
+            })
+            .await
+            .unwrap_or_default();
+
+            files.with_mut(|found| {
+                if let Some(found) = found {
+                    found.retain(|file| !done.contains(&file.path));
+                }
+            });
+            checked.with_mut(|paths| paths.retain(|path| !done.contains(path)));
+            let message = if archive {
+                format!("已移动 {} 个文件（{}）到归档文件夹", done.len(), format_size(bytes))
+            } else {
+                format!("已将 {} 个文件（{}）移入回收站，清空回收站后释放空间", done.len(), format_size(bytes))
+            };
+            action_result.set(Some(match errors.first() {
+                None => Ok(message),
+                Some(first) => Err(format!("{}；{} 个失败: {}", message, errors.len(), first)),
+            }));
+            busy.set(false);
+        });
+    };
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| show_large_files.set(false),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("640"),
+                height: std::borrow::Cow::Borrowed("560"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "大文件查找"
+                }
+            }
+
+            PopupContent {
+                rect {
+                    width: "100%",
+                    direction: "horizontal",
+                    main_align: "space_between",
+                    cross_align: "center",
+                    margin: "0 0 8 0",
+
+                    label {
+                        font_size: "13",
+                        color: theme.label_secondary,
+                        a11y_role: "status",
+                        a11y_live: "polite",
+                        "{summary}"
+                    }
+
+                    rect {
+                        direction: "horizontal",
+                        cross_align: "center",
+
+                        label {
+                            font_size: "12",
+                            color: theme.label_tertiary,
+                            "排序："
+                        }
+                        for option in LargeFileSort::ALL {
+                            Button {
+                                onclick: move |_| sort.set(option),
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed(if sort() == option { theme.background_tertiary } else { "transparent" }),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                    padding: std::borrow::Cow::Borrowed("2 8"),
+                                }),
+                                label {
+                                    font_size: "12",
+                                    color: if sort() == option { theme.label_primary } else { theme.label_secondary },
+                                    "{option.label()}"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                ScrollView {
+                    height: "calc(100% - 150)",
+
+                    for (item, detail) in rows {
+                        Tile {
+                            leading: rsx!(
+                                Checkbox {
+                                    selected: checked().contains(&item.path),
+                                }
+                            ),
+                            onselect: move |_| {
+                                let mut selected = checked();
+                                if !selected.remove(&item.path) {
+                                    selected.insert(item.path.clone());
+                                }
+                                checked.set(selected);
+                                action_result.set(None);
+                            },
+                            rect {
+                                label {
+                                    color: theme.label_primary,
+                                    "{item.name} · {format_size(item.size)}"
+                                }
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_tertiary,
+                                    max_lines: "1",
+                                    text_overflow: "ellipsis",
+                                    "{detail}"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if let Some(result) = action_result() {
+                    label {
+                        font_size: "12",
+                        color: if result.is_ok() { theme.label_secondary } else { theme.danger },
+                        margin: "6 0 0 0",
+                        max_lines: "2",
+                        text_overflow: "ellipsis",
+                        match &result {
+                            Ok(message) => message.clone(),
+                            Err(error) => error.clone(),
+                        }
+                    }
+                }
+
+                // 审计模式下只列出文件
+                if !*AUDIT_MODE {
+                    rect {
+                        width: "100%",
+                        direction: "horizontal",
+                        cross_align: "center",
+                        padding: "8 0 0 0",
+
+                        Input {
+                            value: archive_dir(),
+                            placeholder: "归档文件夹，例如 D:\\Archive".to_string(),
+                            width: "220",
+                            onchange: move |value: String| archive_dir.set(value),
+                        }
+
+                        Button {
+                            onclick: move |_| {
+                                if can_act {
+                                    process(true);
+                                }
+                            },
+                            label {
+                                color: if can_act { theme.label_primary } else { theme.label_tertiary },
+                                "移到归档文件夹"
+                            }
+                        }
+
+                        FilledButton {
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed(theme.danger),
+                                hover_background: std::borrow::Cow::Borrowed(theme.danger_hover),
+                            }),
+                            onclick: move |_| {
+                                if can_act {
+                                    process(false);
+                                }
+                            },
+                            label {
+                                color: "white",
+                                if busy() { "正在处理…" } else { "移入回收站 ({checked_text})" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 清理队列面板 - 正在执行、排队中和最近完成的单项清理，排队的任务可调整顺序或移除
+#[component]
+fn RunQueuePanel(
+    mut show_run_queue: Signal<bool>,
+    mut app_state: Signal<AppState>,
+    theme: &'static AppTheme,
+) -> Element {
+    // 队列不经过信号，定期刷新
+    let mut refreshes = use_signal(|| 0u32);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(SCAN_POLL_INTERVAL).await;
+            refreshes += 1;
+        }
+    });
+    let _ = refreshes();
+
+    let (running, pending, finished) = run_queue_snapshot();
+    let pending_count = pending.len();
+    // 调整队列后刷新任务卡片和状态栏
+    let mut changed = move || {
+        refreshes += 1;
+        let state = app_state();
+        app_state.set(state);
+    };
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| show_run_queue.set(false),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("460"),
+                height: std::borrow::Cow::Borrowed("480"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "清理队列"
+                }
+            }
+
+            PopupContent {
+                label {
+                    font_size: "12",
+                    color: theme.label_tertiary,
+                    margin: "0 0 8 0",
+                    "同时执行 {max_concurrent_runs()} 个任务，其余按顺序排队"
+                }
+
+                ScrollView {
+                    height: "calc(100% - 24)",
+
+                    if running.is_empty() && pending.is_empty() && finished.is_empty() {
+                        label {
+                            color: theme.label_secondary,
+                            "队列为空"
+                        }
+                    }
+
+                    if !running.is_empty() {
+                        label {
+                            font_size: "13",
+                            font_weight: "medium",
+                            color: theme.label_secondary,
+                            margin: "0 0 6 0",
+                            "执行中"
+                        }
+                    }
+
+                    for task in running {
+                        rect {
+                            width: "100%",
+                            padding: "10",
+                            margin: "0 0 6 0",
+                            corner_radius: "8",
+                            background: theme.background_tertiary,
+
+                            label {
+                                font_size: "13",
+                                color: theme.accent,
+                                "⟳ {task.name}"
+                            }
+                        }
+                    }
+
+                    if !pending.is_empty() {
+                        label {
+                            font_size: "13",
+                            font_weight: "medium",
+                            color: theme.label_secondary,
+                            margin: "6 0 6 0",
+                            "排队中 ({pending_count})"
+                        }
+                    }
+
+                    for (index, task) in pending.into_iter().enumerate() {
+                        rect {
+                            width: "100%",
+                            padding: "6 10",
+                            margin: "0 0 6 0",
+                            corner_radius: "8",
+                            background: theme.background_tertiary,
+                            direction: "horizontal",
+                            cross_align: "center",
+
+                            label {
+                                width: "fill",
+                                font_size: "13",
+                                color: theme.label_primary,
+                                max_lines: "1",
+                                text_overflow: "ellipsis",
+                                "{index + 1}. {task.name}"
+                            }
+
+                            if index > 0 {
+                                Button {
+                                    onclick: {
+                                        let task_id = task.id.clone();
+                                        move |_| {
+                                            move_queued_run(&task_id, -1);
+                                            changed();
+                                        }
+                                    },
+                                    theme: theme_with!(ButtonTheme {
+                                        background: std::borrow::Cow::Borrowed("transparent"),
+                                        hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                                        padding: std::borrow::Cow::Borrowed("2 8"),
+                                    }),
+                                    label {
+                                        font_size: "12",
+                                        color: theme.label_secondary,
+                                        "上移"
+                                    }
+                                }
+                            }
+
+                            if index + 1 < pending_count {
+                                Button {
+                                    onclick: {
+                                        let task_id = task.id.clone();
+                                        move |_| {
+                                            move_queued_run(&task_id, 1);
+                                            changed();
+                                        }
+                                    },
+                                    theme: theme_with!(ButtonTheme {
+                                        background: std::borrow::Cow::Borrowed("transparent"),
+                                        hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                                        padding: std::borrow::Cow::Borrowed("2 8"),
+                                    }),
+                                    label {
+                                        font_size: "12",
+                                        color: theme.label_secondary,
+                                        "下移"
+                                    }
+                                }
+                            }
+
+                            Button {
+                                onclick: {
+                                    let task_id = task.id.clone();
+                                    move |_| {
+                                        remove_queued_run(&task_id);
+                                        changed();
+                                    }
+                                },
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed("transparent"),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                                    padding: std::borrow::Cow::Borrowed("2 8"),
+                                }),
+                                label {
+                                    font_size: "12",
+                                    color: theme.danger,
+                                    "移除"
+                                }
+                            }
+                        }
+                    }
+
+                    if !finished.is_empty() {
+                        rect {
+                            width: "100%",
+                            margin: "6 0 6 0",
+                            direction: "horizontal",
+                            main_align: "space_between",
+                            cross_align: "center",
+
+                            label {
+                                font_size: "13",
+                                font_weight: "medium",
+                                color: theme.label_secondary,
+                                "最近完成"
+                            }
+
+                            Button {
+                                onclick: move |_| {
+                                    clear_finished_runs();
+                                    refreshes += 1;
+                                },
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed("transparent"),
+                                    hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                                    padding: std::borrow::Cow::Borrowed("2 8"),
+                                }),
+                                label {
+                                    font_size: "12",
+                                    color: theme.accent,
+                                    "清除"
+                                }
+                            }
+                        }
+                    }
+
+                    for run in finished {
+                        rect {
+                            width: "100%",
+                            padding: "8 10",
+                            margin: "0 0 6 0",
+                            corner_radius: "8",
+                            background: theme.background_tertiary,
+                            direction: "horizontal",
+                            cross_align: "center",
+
+                            label {
+                                width: "fill",
+                                font_size: "13",
+                                color: theme.label_primary,
+                                max_lines: "1",
+                                text_overflow: "ellipsis",
+                                "{run.task_name}"
+                            }
+
+                            label {
+                                font_size: "12",
+                                color: run.outcome.color(theme),
+                                margin: "0 8",
+                                "{run.outcome.label()}"
+                            }
+
+                            label {
+                                font_size: "12",
+                                color: theme.label_tertiary,
+                                "{run.time_text()}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 清理历史弹窗 - 最近的执行记录，带清理前后对比
+#[component]
+fn HistoryPanel(mut show_history: Signal<bool>, theme: &'static AppTheme) -> Element {
+    let mut show_export = use_signal(|| false);
+    if show_export() {
+        return rsx!(LogExportPanel {
+            show_export: show_export,
+            theme: theme,
+        });
+    }
+    // 按时间范围（默认全部）和任务筛选
+    let mut range = use_signal(|| LOG_EXPORT_RANGES.len() - 1);
+    let mut task_filter = use_signal(String::new);
+    let since = LOG_EXPORT_RANGES[range()].1.map(|seconds| chrono::Local::now().timestamp() - seconds);
+    let (matching, entries) = filtered_history(since, &task_filter(), HISTORY_VIEW_LIMIT);
+    let filtered = since.is_some() || !task_filter().trim().is_empty();
+    let mut undo_result = use_signal(|| None::<Result<String, String>>);
+    let mut undoing = use_signal(|| false);
+    let last_run = if undoing() { None } else { last_quarantine_summary() };
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| show_history.set(false),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("560"),
+                height: std::borrow::Cow::Borrowed("540"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "清理历史"
+                }
+            }
+
+            PopupContent {
+                rect {
+                    width: "100%",
+                    height: "44",
+                    direction: "horizontal",
+                    cross_align: "center",
+
+                    for (index, (text, _)) in LOG_EXPORT_RANGES.into_iter().enumerate() {
+                        Button {
+                            onclick: move |_| range.set(index),
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed(if range() == index { theme.accent } else { theme.background_tertiary }),
+                                hover_background: std::borrow::Cow::Borrowed(theme.accent_hover),
+                                padding: std::borrow::Cow::Borrowed("4 8"),
+                            }),
+                            label {
+                                font_size: "12",
+                                color: if range() == index { "white" } else { theme.label_primary },
+                                "{text}"
+                            }
+                        }
+                    }
+
+                    rect {
+                        width: "fill",
+                        main_align: "end",
+                        direction: "horizontal",
+
+                        Input {
+                            value: task_filter(),
+                            placeholder: "任务 ID 或名称".to_string(),
+                            width: "150",
+                            onchange: move |value: String| task_filter.set(value),
+                        }
+                    }
+                }
+
+                if filtered {
+                    label {
+                        font_size: "12",
+                        color: theme.label_tertiary,
+                        margin: "0 0 6 0",
+                        if matching > entries.len() {
+                            "符合条件的记录 {matching} 条，显示最近 {entries.len()} 条"
+                        } else {
+                            "符合条件的记录 {matching} 条"
+                        }
+                    }
+                }
+
+                ScrollView {
+                    height: if filtered { "calc(100% - 124)" } else { "calc(100% - 104)" },
+
+                    if entries.is_empty() {
+                        label {
+                            color: theme.label_secondary,
+                            if filtered { "没有符合条件的记录" } else { "暂无清理记录" }
+                        }
+                    }
+
+                    for entry in entries {
+                        rect {
+                            width: "100%",
+                            padding: "10",
+                            margin: "0 0 8 0",
+                            corner_radius: "8",
+                            background: theme.background_tertiary,
+
+                            rect {
+                                width: "100%",
+                                direction: "horizontal",
+                                main_align: "space_between",
+
+                                label {
+                                    font_size: "13",
+                                    font_weight: "medium",
+                                    color: match (entry.success, entry.skipped.is_some()) {
+                                        (true, true) => theme.label_secondary,
+                                        (true, false) => theme.label_primary,
+                                        (false, _) => theme.danger,
+                                    },
+                                    if !entry.success {
+                                        "✗ {entry.task_name}"
+                                    } else if entry.skipped.is_some() {
+                                        "⏭ {entry.task_name}"
+                                    } else {
+                                        "✓ {entry.task_name}"
+                                    }
+                                }
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_tertiary,
+                                    "{entry.time_text()}"
+                                }
+                            }
+
+                            if let Some(details) = history_details(&entry) {
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_secondary,
+                                    "{details}"
+                                }
+                            }
+
+                            if let Some(command) = &entry.command {
+                                label {
+                                    font_size: "11",
+                                    color: theme.label_tertiary,
+                                    max_lines: "1",
+                                    text_overflow: "ellipsis",
+                                    "{command}"
+                                }
+                            }
+
+                            if let Some(environment) = &entry.environment {
+                                label {
+                                    font_size: "11",
+                                    color: theme.label_tertiary,
+                                    max_lines: "1",
+                                    text_overflow: "ellipsis",
+                                    "{environment.short_text()}"
+                                }
+                            }
+
+                            if let Some(error) = &entry.error {
+                                label {
+                                    font_size: "12",
+                                    color: theme.danger,
+                                    "{error}"
+                                }
+                            }
+
+                            if let Some(reason) = &entry.skipped {
+                                label {
+                                    font_size: "12",
+                                    color: theme.label_secondary,
+                                    "已跳过：{reason}"
+                                }
+                            }
+
+                            if let Some(log_file) = existing_log_file(&entry) {
+                                Button {
+                                    onclick: move |_| open_task_log(&log_file),
+                                    theme: theme_with!(ButtonTheme {
+                                        background: std::borrow::Cow::Borrowed("transparent"),
+                                        hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                                        padding: std::borrow::Cow::Borrowed("2 6"),
+                                    }),
+                                    label {
+                                        font_size: "12",
+                                        color: theme.accent,
+                                        "查看日志"
+                                    }
+                                }
+                            }
+
+                            if let (Some(before), Some(after)) = (entry.before.clone(), entry.after.clone()) {
+                                SnapshotComparison {
+                                    before: before,
+                                    after: after,
+                                    theme: theme,
+                                }
+                            }
+                        }
+                    }
+                }
+
+                rect {
+                    height: "60",
+                    padding: "12 0 0 0",
+                    direction: "horizontal",
+                    main_align: "space_between",
+                    cross_align: "center",
+
+                    rect {
+                        width: "fill",
+                        padding: "0 12 0 0",
+
+                        match undo_result() {
+                            Some(Ok(message)) => rsx!(label {
+                                font_size: "12",
+                                color: theme.label_secondary,
+                                "{message}"
+                            }),
+                            Some(Err(error)) => rsx!(label {
+                                font_size: "12",
+                                color: theme.danger,
+                                "撤销失败：{error}"
+                            }),
+                            None if undoing() => rsx!(label {
+                                font_size: "12",
+                                color: theme.label_secondary,
+                                "正在还原…"
+                            }),
+                            None => match &last_run {
+                                Some(summary) => rsx!(label {
+                                    font_size: "12",
+                                    color: theme.label_secondary,
+                                    "{summary}"
+                                }),
+                                None => rsx!(rect {}),
+                            },
+                        }
+                    }
+
+                    if last_run.is_some() {
+                        Button {
+                            onclick: move |_| {
+                                undoing.set(true);
+                                undo_result.set(None);
+                                spawn(async move {
+                                    let result = tokio::task::spawn_blocking(undo_last_cleanup)
+                                        .await
+                                        .unwrap_or_else(|e| Err(format!("异步执行任务失败: {}", e)));
+                                    undo_result.set(Some(result));
+                                    undoing.set(false);
+                                });
+                            },
+                            label {
+                                "撤销上次清理"
+                            }
+                        }
+                    }
+
+                    Button {
+                        onclick: move |_| show_export.set(true),
+                        label {
+                            "导出日志…"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn SelectionDrawer(
+    tasks: Vec<CleanTask>,
+    expanded: bool,
+    on_toggle_expanded: EventHandler<()>,
+    on_remove: EventHandler<String>,
+    theme: &'static AppTheme,
+) -> Element {
+    let known_sizes = tasks
+        .iter()
+        .filter_map(|task| task.get_estimated_bytes())
+        .collect::<Vec<_>>();
+    let unknown_count = tasks.len() - known_sizes.len();
+    let total_text = format_size(known_sizes.iter().sum());
+    let summary = if unknown_count > 0 {
+        format!("预估合计: ≥ {}（{} 项未知）", total_text, unknown_count)
+    } else {
+        format!("预估合计: ~{}", total_text)
+    };
+    let arrow = if expanded { "▾" } else { "▸" };
+    let task_count = tasks.len();
+    let items = tasks
+        .iter()
+        .map(|task| {
+            let size_text = task.get_actual_size().unwrap_or_else(|| "未知".to_string());
+            let icon_text = task.icon.as_deref().unwrap_or("");
+            (task.id.clone(), format!("{} {} · {}", icon_text, task.name, size_text))
+        })
+        .collect::<Vec<_>>();
+
+    rsx!(
+        rect {
+            width: "100%",
+            padding: "12",
+            background: theme.background_tertiary,
+            corner_radius: "12",
+            margin: "0 0 16 0",
+
+            // 抽屉标题 - 点击展开/收起
+            rect {
+                width: "100%",
+                direction: "horizontal",
+                main_align: "space_between",
+                cross_align: "center",
+                onclick: move |_| on_toggle_expanded.call(()),
+
+                label {
+                    font_size: "14",
+                    font_weight: "medium",
+                    color: theme.label_primary,
+                    "{arrow} 已选 {task_count} 项"
+                }
+
+                label {
+                    font_size: "13",
+                    color: theme.label_secondary,
+                    "{summary}"
+                }
+            }
+
+            if expanded {
+                rect {
+                    height: "8"
+                }
+
+                for (task_id, item_text) in items {
+                    rect {
+                        width: "100%",
+                        direction: "horizontal",
+                        main_align: "space_between",
+                        cross_align: "center",
+                        padding: "4 0",
+
+                        label {
+                            font_size: "13",
+                            color: theme.label_primary,
+                            "{item_text}"
+                        }
+
+                        Button {
+                            onclick: move |_| on_remove.call(task_id.clone()),
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed("transparent"),
+                                hover_background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                            }),
+                            label {
+                                font_size: "12",
+                                color: theme.label_secondary,
+                                "移除"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn ItemPicker(
+    task: CleanTask,
+    mut show_item_picker: Signal<Option<CleanTask>>,
+    mut app_state: Signal<AppState>,
+    theme: &'static AppTheme,
+) -> Element {
+    let mut items = use_signal(|| None::<Vec<TargetItem>>);
+    let mut checked = use_signal(HashSet::<std::path::PathBuf>::new);
+    // 接管所有权需勾选后再次点击清理按钮确认
+    let mut take_ownership = use_signal(|| false);
+    let mut takeover_armed = use_signal(|| false);
+    // 上次清理后有新增内容的子项及新增的大小
+    let mut new_items = use_signal(HashMap::<std::path::PathBuf, NewContent>::new);
+    let previous_clean = use_hook(|| last_clean(&task.id));
+
+    // 打开时在后台扫描子项，分析器判定为孤立的项默认勾选
+    let target = task.get_expanded_path().unwrap_or_default();
+    let analyzer = task.item_analyzer;
+    let since = previous_clean.as_ref().map(|entry| entry.finished_at);
+    use_hook(move || {
+        spawn(async move {
+            let list = tokio::task::spawn_blocking(move || list_target_items(&target, analyzer))
+                .await
+                .unwrap_or_default();
+            checked.set(
+                list.iter()
+                    .filter(|item| item.orphaned)
+                    .map(|item| item.path.clone())
+                    .collect(),
+            );
+            items.set(Some(list.clone()));
+
+            // 列表先显示，再补充上次清理以来新增的内容
+            if let Some(since) = since {
+                let found = tokio::task::spawn_blocking(move || {
+                    list.iter()
+                        .map(|item| (item.path.clone(), item_new_content(item, since)))
+                        .filter(|(_, content)| content.files > 0)
+                        .collect::<HashMap<_, _>>()
+                })
+                .await
+                .unwrap_or_default();
+                new_items.set(found);
+            }
+        })
+    });
+    let new_summary = previous_clean.as_ref().map(|entry| {
+        let new_bytes = new_items().values().map(|content| content.bytes).sum::<u64>();
+        if new_items().is_empty() {
+            format!("自上次清理（{}）以来没有新增内容", entry.time_text())
+        } else {
+            format!(
+                "🆕 自上次清理（{}）以来 {} 项有新增内容，共 {}",
+                entry.time_text(),
+                new_items().len(),
+                format_size(new_bytes)
+            )
+        }
+    });
+
+    let checked_size: u64 = items()
+        .unwrap_or_default()
+        .iter()
+        .filter(|item| checked().contains(&item.path))
+        .map(|item| item.size)
+        .sum();
+    let checked_count = checked().len();
+    let checked_size_text = format_size(checked_size);
+    let title = format!("选择要清理的项目 - {}", task.name);
+    let analyzer_warning = task.item_analyzer.and_then(|analyzer| analyzer.warning());
+    let confirm_text = if takeover_armed() {
+        "再次点击以确认接管所有权并删除".to_string()
+    } else {
+        format!("清理选中 ({}) · {}", checked_count, checked_size_text)
+    };
+
+    rsx!(
+        Popup {
+            oncloserequest: move |_| show_item_picker.set(None),
+            show_close_button: true,
+            theme: theme_with!(PopupTheme {
+                background: std::borrow::Cow::Borrowed(theme.background_secondary),
+                color: std::borrow::Cow::Borrowed(theme.label_primary),
+                cross_fill: std::borrow::Cow::Borrowed(theme.label_secondary),
+                width: std::borrow::Cow::Borrowed("480"),
+                height: std::borrow::Cow::Borrowed("460"),
+            }),
+
+            PopupTitle {
+                label {
+                    color: theme.label_primary,
+                    "{title}"
+                }
+            }
+
+            PopupContent {
+                ScrollView {
+                    height: "calc(100% - 60)",
+
+                    if let Some(warning) = analyzer_warning {
+                        rect {
+                            width: "100%",
+                            padding: "12",
+                            margin: "0 0 8 0",
+                            corner_radius: "6",
+                            border: "1 solid {theme.danger}",
+
+                            label {
+                                font_size: "13",
+                                color: theme.danger,
+                                "{warning}"
+                            }
+                        }
+                    }
+
+                    if let (Some(summary), Some(_)) = (new_summary, items()) {
+                        label {
+                            font_size: "12",
+                            color: if new_items().is_empty() { theme.label_tertiary } else { theme.accent },
+                            margin: "0 0 8 0",
+                            "{summary}"
+                        }
+                    }
+
+                    match items() {
+                        None => rsx!(
+                            label {
+                                color: theme.label_secondary,
+                                "正在扫描…"
+                            }
+                        ),
+                        Some(list) if list.is_empty() => rsx!(
+                            label {
+                                color: theme.label_secondary,
+                                "目标目录为空或不存在"
+                            }
+                        ),
+                        Some(list) => rsx!(
+                            for item in list {
+                                Tile {
+                                    leading: rsx!(
+                                        Checkbox {
+                                            selected: checked().contains(&item.path),
+                                        }
+                                    ),
+                                    onselect: move |_| {
+                                        let mut selected = checked();
+                                        if !selected.remove(&item.path) {
+                                            selected.insert(item.path.clone());
+                                        }
+                                        checked.set(selected);
+                                    },
+                                    rect {
+                                        label {
+                                            color: theme.label_primary,
+                                            "{item.name} · {format_size(item.size)}"
+                                        }
+
+                                        if let Some(content) = new_items().get(&item.path) {
+                                            label {
+                                                font_size: "12",
+                                                color: theme.accent,
+                                                if content.bytes >= item.size {
+                                                    "🆕 上次清理后新增"
+                                                } else {
+                                                    "🆕 其中 {format_size(content.bytes)} 为上次清理后新增"
+                                                }
+                                            }
+                                        }
+
+                                        if let Some(detail) = &item.detail {
+                                            label {
+                                                font_size: "12",
+                                                color: if item.orphaned { theme.danger } else { theme.label_tertiary },
+                                                "{detail}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        ),
+                    }
+
+                    // 受保护残留：接管所有权并重置权限后删除，仅管理员可用
+                    if *IS_ELEVATED {
+                        rect {
+                            width: "100%",
+                            margin: "12 0 0 0",
+                            padding: "8",
+                            corner_radius: "6",
+                            border: "1 solid {theme.separator}",
+
+                            Tile {
+                                leading: rsx!(
+                                    Checkbox {
+                                        selected: take_ownership(),
+                                    }
+                                ),
+                                onselect: move |_| {
+                                    take_ownership.set(!take_ownership());
+                                    takeover_armed.set(false);
+                                },
+                                label {
+                                    font_size: "13",
+                                    color: theme.label_primary,
+                                    "接管所有权并重置权限后删除"
+                                }
+                            }
+
+                            if take_ownership() {
+                                label {
+                                    font_size: "12",
+                                    color: theme.danger,
+                                    "⚠️ 将把选中项目及其全部内容的所有者改为 Administrators 并重置访问权限。仅用于 TrustedInstaller 或其他用户遗留、无法直接删除的文件；用在仍在使用的系统或用户数据上会造成无法恢复的损坏。"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                rect {
+                    height: "60",
+                    padding: "12 0 0 0",
+                    direction: "horizontal",
+                    main_align: "end",
+
+                    Button {
+                        onclick: move |_| show_item_picker.set(None),
+                        theme: theme_with!(ButtonTheme {
+                            background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                            hover_background: std::borrow::Cow::Borrowed(theme.background_primary),
+                        }),
+                        label {
+                            color: theme.label_secondary,
+                            if *AUDIT_MODE { "关闭" } else { "取消" }
+                        }
+                    }
+
+                    if !*AUDIT_MODE {
+                        rect {
+                            width: "20"
+                        }
+
+                        FilledButton {
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed(theme.danger),
+                                hover_background: std::borrow::Cow::Borrowed(theme.danger_hover),
+                            }),
+                            onclick: move |_| {
+                                let checked_paths = checked();
+                                let selected = items()
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .filter(|item| checked_paths.contains(&item.path))
+                                    .collect::<Vec<_>>();
+                                if selected.is_empty() {
+                                    return;
+                                }
+
+                                // 接管所有权需要第二次点击确认
+                                if take_ownership() && !takeover_armed() {
+                                    takeover_armed.set(true);
+                                    return;
+                                }
+
+                                let task = task.clone();
+                                let take_ownership = take_ownership();
+                                show_item_picker.set(None);
+                                spawn(async move {
+                                    run_item_cleanup(task, selected, take_ownership, app_state).await;
+                                });
+                            },
+                            label {
+                                color: "white",
+                                "{confirm_text}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// 取消当前清理；按下后等待正在执行的条目停止
+#[component]
+fn CancelButton(theme: &'static AppTheme) -> Element {
+    let mut cancelling = use_signal(cancel_requested);
+
+    rsx!(
+        Button {
+            onclick: move |_| {
+                if !cancelling() {
+                    cancel_running_clean();
+                    cancelling.set(true);
+                }
+            },
+            theme: theme_with!(ButtonTheme {
+                background: std::borrow::Cow::Borrowed("transparent"),
+                hover_background: std::borrow::Cow::Borrowed(theme.background_tertiary),
+                padding: std::borrow::Cow::Borrowed("2 8"),
+            }),
+            label {
+                font_size: "12",
+                color: if cancelling() { theme.label_tertiary } else { theme.danger },
+                if cancelling() { "正在取消…" } else { "取消" }
+            }
+        }
+    )
+}
+
+#[component]
+fn StatusBar(
+    app_state: AppState,
+    prescan_progress: Option<(usize, usize)>,
+    tasks: Vec<CleanTask>,
+    theme: &'static AppTheme,
+) -> Element {
+    // 扫描线程和磁盘空间不经过信号，定期刷新
+    let mut refreshes = use_signal(|| 0u32);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(STATUS_REFRESH_INTERVAL).await;
+            refreshes += 1;
+        }
+    });
+    let _ = refreshes();
+
+    let scans_in_flight = SIZE_SCANS.lock().unwrap().len();
+    let activity = match (&app_state, prescan_progress) {
+        (AppState::Running(msg), _) => match queued_run_count() {
+            0 => msg.clone(),
+            queued => format!("{}，另有 {} 个任务排队", msg, queued),
+        },
+        (_, Some((scanned, total))) => format!(
+            "正在预扫描大小 {}/{}，排队 {} 项",
+            scanned,
+            total,
+            total - scanned
+        ),
+        _ if scans_in_flight > 0 => format!("后台扫描中: {} 项", scans_in_flight),
+        _ => "就绪".to_string(),
+    };
+    let is_busy = activity != "就绪";
+    let activity_text = if is_busy { format!("⟳ {}", activity) } else { activity };
+
+    let detected_bytes = tasks
+        .iter()
+        .filter_map(|task| task.size_cache_key())
+        .collect::<HashSet<_>>()
+        .iter()
+        .filter_map(|key| peek_size_bytes(key))
+        .sum::<u64>();
+    let detected_text = format_size(detected_bytes);
+
+    let drive_root = system_drive_root();
+    let free_text = volume_free_space(&drive_root)
+        .map(|bytes| format!("{} 可用空间: {}", drive_root.trim_end_matches('\\'), format_size(bytes)))
+        .unwrap_or_default();
+
+    rsx!(
+        rect {
+            width: "100%",
+            direction: "horizontal",
+            main_align: "space_between",
+            cross_align: "center",
+            padding: "12 4 0 4",
+            a11y_role: "status",
+            a11y_live: "polite",
+
+            rect {
+                direction: "horizontal",
+                cross_align: "center",
+
+                label {
+                    font_size: "12",
+                    color: if is_busy { theme.accent } else { theme.label_tertiary },
+                    margin: "0 8 0 0",
+                    "{activity_text}"
+                }
+
+                if let Some((scanned, total)) = prescan_progress {
+                    ProgressBar {
+                        progress: scanned as f32 / total as f32 * 100.0,
+                        width: "160",
+                    }
+                }
+
+                if matches!(app_state, AppState::Running(_)) && ELEVATED_RUNNING.load(Ordering::Relaxed) == 0 {
+                    CancelButton {
+                        theme: theme,
+                    }
+                }
+            }
+
+            rect {
+                direction: "horizontal",
+                cross_align: "center",
+
+                label {
+                    font_size: "12",
+                    color: theme.label_tertiary,
+                    "已检测可清理: {detected_text}"
+                }
+
+                if !free_text.is_empty() {
+                    label {
+                        font_size: "12",
+                        color: theme.label_tertiary,
+                        margin: "0 0 0 16",
+                        "{free_text}"
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn NotificationBubble(app_state: AppState, theme: &'static AppTheme) -> Element {
+    // 预计算统计消息，避免生命周期问题
+    let stats = match &app_state {
+        AppState::SuccessWithStats(stats) | AppState::Cancelled(stats) => Some(stats),
+        _ => None,
+    };
+    let stats_message = if let Some(stats) = stats {
+        let space_freed = stats
+            .total_space_freed
+            .map(|bytes| format_size(bytes))
+            .unwrap_or_else(|| "0 B".to_string());
+
+        if matches!(app_state, AppState::Cancelled(_)) {
+            format!(
+                "已取消。完成: {}/{}，释放空间: {}",
+                stats.successful_tasks, stats.total_tasks, space_freed
+            )
+        } else if stats.skipped_tasks > 0 {
+            format!(
+                "清理完成！成功: {}，失败: {}，跳过: {}，释放空间: {}",
+                stats.successful_tasks, stats.failed_tasks, stats.skipped_tasks, space_freed
+            )
+        } else if stats.failed_tasks > 0 {
+            format!(
+                "清理完成！成功: {}，失败: {}，释放空间: {}",
+                stats.successful_tasks, stats.failed_tasks, space_freed
+            )
+        } else {
+            format!(
+                "清理完成！成功: {}，释放空间: {}",
+                stats.successful_tasks, space_freed
+            )
+        }
+    } else {
+        String::new()
+    };
+    // 跳过的任务单独列出，与失败区分，最多显示前几项
+    const SKIPPED_SHOWN: usize = 3;
+    let (skipped_message, only_skipped) = if let Some(stats) = stats {
+        let mut message = stats
+            .skipped
+            .iter()
+            .take(SKIPPED_SHOWN)
+            .map(|(_, reason)| reason.clone())
+            .collect::<Vec<_>>()
+            .join("；");
+        if stats.skipped.len() > SKIPPED_SHOWN {
+            message.push_str(&format!(" 等 {} 项", stats.skipped.len()));
+        }
+        (message, stats.skipped_tasks > 0 && stats.successful_tasks == 0 && stats.failed_tasks == 0)
+    } else {
+        (String::new(), false)
+    };
+    // 结果对应的任务日志：单项清理取最近一条记录，批量清理取第一个失败的任务
+    let log_file = match &app_state {
+        AppState::Success | AppState::Error(_) => recent_history(1).into_iter().next(),
+        AppState::SuccessWithStats(stats) | AppState::Cancelled(stats) => {
+            stats.errors.first().and_then(|(task_id, _)| last_history_entry(task_id))
+        }
+        AppState::Idle | AppState::Running(_) => None,
+    }
+    .and_then(|entry| existing_log_file(&entry));
+    // 批量清理的结果可导出报告
+    let has_report = matches!(
+        app_state,
+        AppState::Success | AppState::SuccessWithStats(_) | AppState::Cancelled(_)
+    ) && LAST_BATCH_REPORT.lock().unwrap().is_some();
+    let mut report_export = use_signal(|| None::<Result<String, String>>);
+    let volume_message = if let Some(stats) = stats {
+        stats
+            .volume_freed
+            .iter()
+            .map(|(root, bytes)| format!("{} +{}", root.trim_end_matches('\\'), format_size(*bytes)))
+            .collect::<Vec<_>>()
+            .join("，")
+    } else {
+        String::new()
+    };
+
+    let (bg_color, text_color, icon, message, font_weight, icon_bg_color, icon_color) =
+        match &app_state {
+            AppState::Idle => (
+                theme.background_tertiary,
+                theme.label_secondary,
+                "",
+                "就绪",
+                "normal",
+                theme.background_primary,
+                theme.label_secondary,
+            ),
+            AppState::Running(msg) => (
+                theme.accent,
+                "white",
+                "⟳",
+                msg.as_str(),
+                "medium",
+                "rgb(255, 255, 255)",
+                theme.accent,
+            ),
+            AppState::Success => (
+                "rgb(34, 197, 94)",
+                "white",
+                "✓",
+                "清理完成！",
+                "medium",
+                "rgb(255, 255, 255)",
+                "rgb(34, 197, 94)",
+            ),
+            // 全部跳过时没有执行任何清理，用中性的灰色显示
+            AppState::SuccessWithStats(_) if only_skipped => (
+                theme.background_tertiary,
+                theme.label_primary,
+                "⏭",
+                stats_message.as_str(),
+                "medium",
+                theme.background_primary,
+                theme.label_secondary,
+            ),
+            AppState::SuccessWithStats(_) => (
+                "rgb(34, 197, 94)",
+                "white",
+                "✓",
+                stats_message.as_str(),
+                "medium",
+                "rgb(255, 255, 255)",
+                "rgb(34, 197, 94)",
+            ),
+            // 取消不是失败，用中性的灰色显示
+            AppState::Cancelled(_) => (
+                theme.background_tertiary,
+                theme.label_primary,
+                "■",
+                stats_message.as_str(),
+                "medium",
+                theme.background_primary,
+                theme.label_secondary,
+            ),
+            AppState::Error(msg) => (
+                "rgb(239, 68, 68)",
+                "white",
+                "✗",
+                msg.as_str(),
+                "medium",
+                "rgb(255, 255, 255)",
+                "rgb(239, 68, 68)",
+            ),
+        };
+
+    rsx!(
+        rect {
+            width: "100%",
+            padding: "16 20",
+            background: bg_color,
+            corner_radius: "12",
+            margin: "16 0 0 0",
+            direction: "horizontal",
+            cross_align: "center",
+
+            // 图标区域 - 增强对比度
+            if !icon.is_empty() {
+                rect {
+                    width: "28",
+                    height: "28",
+                    corner_radius: "14",
+                    background: icon_bg_color,
+                    main_align: "center",
+                    cross_align: "center",
+                    margin: "0 12 0 0",
+                    border: "2 solid {text_color}",
+
+                    label {
+                        font_size: "16",
+                        font_weight: "bold",
+                        color: icon_color,
+                        "{icon}"
+                    }
+                }
+            }
+
+            // 文本内容
+            rect {
+                direction: "vertical",
+
+                label {
+                    font_size: "15",
+                    font_weight: font_weight,
+                    color: text_color,
+                    "{message}"
+                }
+
+                if !skipped_message.is_empty() {
+                    label {
+                        font_size: "13",
+                        color: if only_skipped { theme.label_secondary } else { text_color },
+                        margin: "4 0 0 0",
+                        max_lines: "2",
+                        text_overflow: "ellipsis",
+                        "⏭ 已跳过: {skipped_message}"
+                    }
+                }
+
+                // 按卷统计的可用空间变化
+                if !volume_message.is_empty() {
+                    label {
+                        font_size: "13",
+                        color: text_color,
+                        margin: "4 0 0 0",
+                        "按磁盘: {volume_message}"
+                    }
+                }
+
+                if has_report {
+                    match report_export() {
+                        Some(Ok(path)) => rsx!(
+                            label {
+                                font_size: "12",
+                                color: text_color,
+                                margin: "4 0 0 0",
+                                "报告已导出: {path}"
+                            }
+                        ),
+                        Some(Err(e)) => rsx!(
+                            label {
+                                font_size: "12",
+                                color: text_color,
+                                margin: "4 0 0 0",
+                                "{e}"
+                            }
+                        ),
+                        None => rsx!(),
+                    }
+                }
+            }
+
+            if log_file.is_some() || has_report {
+                rect {
+                    width: "fill",
+                    main_align: "end",
+                    direction: "horizontal",
+
+                    if let Some(log_file) = log_file {
+                        Button {
+                            onclick: move |_| open_task_log(&log_file),
+                            theme: theme_with!(ButtonTheme {
+                                background: std::borrow::Cow::Borrowed("transparent"),
+                                hover_background: std::borrow::Cow::Borrowed(bg_color),
+                                padding: std::borrow::Cow::Borrowed("4 10"),
+                            }),
+                            label {
+                                font_size: "13",
+                                color: text_color,
+                                "查看日志"
+                            }
+                        }
+                    }
+
+                    // 批量清理结束后导出报告
+                    if has_report {
+                        for format in [ReportFormat::Html, ReportFormat::Csv] {
+                            Button {
+                                onclick: move |_| {
+                                    spawn(async move {
+                                        let result = tokio::task::spawn_blocking(move || export_batch_report(format))
+                                            .await
+                                            .unwrap_or_else(|e| Err(format!("报告生成失败: {}", e)));
+                                        report_export.set(Some(result));
+                                    });
+                                },
+                                theme: theme_with!(ButtonTheme {
+                                    background: std::borrow::Cow::Borrowed("transparent"),
+                                    hover_background: std::borrow::Cow::Borrowed(bg_color),
+                                    padding: std::borrow::Cow::Borrowed("4 10"),
+                                }),
+                                label {
+                                    font_size: "13",
+                                    color: text_color,
+                                    "导出报告 ({format.extension().to_uppercase()})"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn click_toggles_task_and_moves_anchor() {
+        let order = ids(&["a", "b", "c"]);
+        let (selected, anchor) = click_selection(&HashSet::new(), &order, None, "b", false);
+        assert_eq!(selected, HashSet::from(["b".to_string()]));
+        assert_eq!(anchor.as_deref(), Some("b"));
+
+        let (selected, anchor) = click_selection(&selected, &order, Some("b"), "b", false);
+        assert!(selected.is_empty());
+        assert_eq!(anchor.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn shift_click_selects_range_and_keeps_anchor() {
+        let order = ids(&["a", "b", "c", "d", "e"]);
+        let start = HashSet::from(["a".to_string()]);
+        let (selected, anchor) = click_selection(&start, &order, Some("d"), "b", true);
+        assert_eq!(selected, ["a", "b", "c", "d"].iter().map(|id| id.to_string()).collect());
+        assert_eq!(anchor.as_deref(), Some("d"));
+    }
+
+    #[test]
+    fn shift_click_without_anchor_toggles() {
+        let order = ids(&["a", "b"]);
+        let (selected, anchor) = click_selection(&HashSet::new(), &order, None, "a", true);
+        assert_eq!(selected, HashSet::from(["a".to_string()]));
+        assert_eq!(anchor.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn range_with_missing_anchor_selects_target_only() {
+        let order = ids(&["a", "b"]);
+        assert_eq!(selection_range(&order, "gone", "b"), ["b".to_string()]);
+        assert!(selection_range(&order, "a", "gone").is_empty());
+    }
+}